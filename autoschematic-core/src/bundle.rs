@@ -120,6 +120,28 @@ impl BundleMapFile {
         Ok(Self::path(prefix, child))
     }
 
+    /// List the address of every resource in `prefix` with a bundle map file.
+    pub fn list(prefix: &Path) -> Vec<PathBuf> {
+        let dir = prefix.join(".bundle");
+
+        let mut addrs: Vec<PathBuf> = walkdir::WalkDir::new(&dir)
+            .into_iter()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().is_file())
+            .filter_map(|entry| {
+                let path = entry.path().strip_prefix(&dir).ok()?;
+                let addr_name = path.file_name()?.to_str()?.strip_suffix(".bun.ron")?;
+                if addr_name.is_empty() {
+                    return None;
+                }
+                Some(path.with_file_name(addr_name))
+            })
+            .collect();
+
+        addrs.sort();
+        addrs
+    }
+
     pub fn delete(prefix: &Path, addr: &Path) -> anyhow::Result<Option<PathBuf>> {
         let path = Self::path(prefix, addr);
         if path.is_file() {
@@ -5,7 +5,7 @@ use autoschematic_macros::FieldTypes;
 use documented::{Documented, DocumentedFields};
 use serde::{Deserialize, Serialize};
 
-use crate::macros::FieldTypes;
+use crate::{macros::FieldTypes, util::python_venv_binary};
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Documented, DocumentedFields, FieldTypes)]
 #[serde(deny_unknown_fields)]
@@ -62,6 +62,14 @@ pub struct Prefix {
     /// [Optional] A map of common environment variables shared between all connectors in this prefix. Takes precedence over env_file.
     #[serde(default)]
     pub env: HashMap<String, String>,
+    /// [Optional] A list of address globs, relative to the prefix, for resources that must not be deleted or replaced.
+    /// `autoschematic apply` refuses any plan that would delete or replace a protected resource unless
+    /// `--allow-destroy-protected` is passed.
+    /// ```ignore
+    /// protect: ["aws/rds/**", "aws/s3/*/buckets/prod-*.ron"],
+    /// ```
+    #[serde(default)]
+    pub protect: Vec<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Documented, DocumentedFields, FieldTypes)]
@@ -299,21 +307,20 @@ impl Spec {
             Spec::Pip {
                 name, binary, module, ..
             } => {
+                // Prefer the per-repository virtualenv that `autoschematic install` sets up.
+                let python = python_venv_binary("python").unwrap_or("python".into());
                 if let Some(binary) = binary {
-                    let binary = which::which(binary)?;
+                    let binary = match python_venv_binary(binary) {
+                        Some(binary) => binary,
+                        None => which::which(binary)?,
+                    };
                     Ok(SpecCommand { binary, args: vec![] })
                 } else if let Some(module) = module {
                     let args = vec!["-m".into(), module.into()];
-                    Ok(SpecCommand {
-                        binary: "python".into(),
-                        args,
-                    })
+                    Ok(SpecCommand { binary: python, args })
                 } else {
                     let args = vec!["-m".into(), name.into()];
-                    Ok(SpecCommand {
-                        binary: "python".into(),
-                        args,
-                    })
+                    Ok(SpecCommand { binary: python, args })
                 }
             }
             Spec::PythonLocal { path } => {
@@ -357,6 +364,18 @@ pub struct Connector {
     /// [Optional] An env file path (like ".env") to read environment variables from.
     #[serde(default)]
     pub env_file: Option<String>,
+    /// [Optional] A list of address globs for resources managed by this connector that must not be deleted or replaced.
+    /// These apply in addition to Prefix.protect.
+    #[serde(default)]
+    pub protect: Vec<String>,
+    /// [Optional] In long-running processes like the language server, stop this connector once it has gone
+    /// this many minutes without being used. It is started again the next time it's needed.
+    #[serde(default)]
+    pub idle_timeout: Option<u64>,
+    /// [Optional] If true, this connector is never stopped to bring the total memory of running connectors
+    /// under AUTOSCHEMATIC_CONNECTOR_MEMORY_LIMIT. idle_timeout still applies.
+    #[serde(default)]
+    pub keep_resident: bool,
     // #[serde(default)]
     // The set of secrets that this connector is allowed to unseal at runtime.
     // TODO where do we plug this in now?
@@ -0,0 +1,318 @@
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context;
+use documented::{Documented, DocumentedFields, DocumentedVariants};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config_rbac::{AutoschematicRbacConfig, User},
+    connector::PlanResponseElement,
+    glob::addr_matches_glob,
+    report::PlanReport,
+};
+
+#[derive(Debug, Default, Deserialize, Serialize, Documented, DocumentedFields)]
+/// The policy config for Autoschematic, kept in autoschematic.policy.ron.
+/// Every rule is checked against the plan before apply, and apply is refused
+/// if any rule is violated, unless the user holds one of `override_roles`.
+pub struct AutoschematicPolicyConfig {
+    /// [Optional] Users with any of these roles in autoschematic.rbac.ron may apply plans that violate the policy.
+    #[serde(default)]
+    pub override_roles: Vec<String>,
+    /// The rules that every plan must satisfy.
+    #[serde(default)]
+    pub rules: Vec<PolicyRule>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Documented, DocumentedFields)]
+/// A rule that the plans selected by `matches` must satisfy.
+/// ```ignore
+/// PolicyRule(name: "no-prod-deletes", matches: PolicyMatch(prefix: "prod", deleted: true), check: Deny),
+/// PolicyRule(name: "small-changes", check: MaxOps(20)),
+/// PolicyRule(name: "dns-create-only", matches: PolicyMatch(connector: "dns"), check: OnlyOps("^Create")),
+/// ```
+pub struct PolicyRule {
+    /// The name of the rule, as shown when it is violated.
+    pub name: String,
+    /// [Optional] Which plans and ops the rule applies to. By default, every op in every plan.
+    #[serde(default)]
+    pub matches: PolicyMatch,
+    /// What the rule requires of the ops it applies to.
+    pub check: PolicyCheck,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Documented, DocumentedFields)]
+/// Selects the ops that a PolicyRule applies to. Every field that is set must match.
+pub struct PolicyMatch {
+    /// [Optional] The name of the prefix.
+    #[serde(default)]
+    pub prefix: Option<String>,
+    /// [Optional] The shortname of the connector.
+    #[serde(default)]
+    pub connector: Option<String>,
+    /// [Optional] An address glob, relative to the prefix, as in `protect`.
+    #[serde(default)]
+    pub addr: Option<String>,
+    /// [Optional] Whether the resource's desired file was deleted.
+    #[serde(default)]
+    pub deleted: Option<bool>,
+    /// [Optional] A regex to match against the op_definition of each op.
+    #[serde(default)]
+    pub op_definition: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, DocumentedVariants)]
+/// What a PolicyRule requires of the ops it applies to.
+pub enum PolicyCheck {
+    /// There must be no such ops.
+    Deny,
+    /// There must be no more than this many such ops across the whole plan.
+    MaxOps(usize),
+    /// Every such op must have an op_definition matching this regex.
+    OnlyOps(String),
+}
+
+/// A policy rule that a plan violates.
+#[derive(Clone, Debug, Serialize)]
+pub struct PolicyViolation {
+    /// The name of the violated rule.
+    pub rule: String,
+    /// The resource in violation, or None where the rule applies to the plan as a whole.
+    pub path: Option<PathBuf>,
+    pub message: String,
+}
+
+impl fmt::Display for PolicyViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.path {
+            Some(path) => write!(f, "[{}] {}: {}", self.rule, path.display(), self.message),
+            None => write!(f, "[{}] {}", self.rule, self.message),
+        }
+    }
+}
+
+impl PolicyMatch {
+    fn matches_plan(&self, plan_report: &PlanReport) -> bool {
+        if let Some(prefix) = &self.prefix
+            && plan_report.prefix != Path::new(prefix)
+        {
+            return false;
+        }
+
+        if let Some(connector) = &self.connector
+            && plan_report.connector_def.as_ref().is_none_or(|c| c.shortname != *connector)
+        {
+            return false;
+        }
+
+        if let Some(addr) = &self.addr
+            && !addr_matches_glob(&plan_report.virt_addr, Path::new(addr))
+        {
+            return false;
+        }
+
+        if let Some(deleted) = self.deleted
+            && plan_report.desired_none != deleted
+        {
+            return false;
+        }
+
+        true
+    }
+
+    /// The ops in `plan_report` that this matches.
+    fn matching_ops<'a>(&self, plan_report: &'a PlanReport, op_definition: Option<&Regex>) -> Vec<&'a PlanResponseElement> {
+        if !self.matches_plan(plan_report) {
+            return Vec::new();
+        }
+
+        plan_report
+            .connector_ops
+            .iter()
+            .filter(|op| op_definition.is_none_or(|re| re.is_match(&op.op_definition)))
+            .collect()
+    }
+}
+
+fn compile(rule: &str, pattern: &str) -> anyhow::Result<Regex> {
+    Regex::new(pattern).context(format!("Parsing regex in policy rule {rule}"))
+}
+
+fn describe(op: &PlanResponseElement) -> &str {
+    op.friendly_message
+        .as_deref()
+        .unwrap_or(&op.op_definition)
+        .lines()
+        .next()
+        .unwrap_or_default()
+}
+
+impl AutoschematicPolicyConfig {
+    /// Check `plan_reports` against every rule, returning the violations in rule order.
+    pub fn evaluate(&self, plan_reports: &[PlanReport]) -> anyhow::Result<Vec<PolicyViolation>> {
+        let mut violations = Vec::new();
+
+        for rule in &self.rules {
+            let op_definition = match &rule.matches.op_definition {
+                Some(pattern) => Some(compile(&rule.name, pattern)?),
+                None => None,
+            };
+
+            match &rule.check {
+                PolicyCheck::Deny => {
+                    for plan_report in plan_reports {
+                        for op in rule.matches.matching_ops(plan_report, op_definition.as_ref()) {
+                            violations.push(PolicyViolation {
+                                rule: rule.name.clone(),
+                                path: Some(plan_report.prefix.join(&plan_report.virt_addr)),
+                                message: format!("Denied op: {}", describe(op)),
+                            });
+                        }
+                    }
+                }
+                PolicyCheck::MaxOps(max) => {
+                    let count: usize = plan_reports
+                        .iter()
+                        .map(|plan_report| rule.matches.matching_ops(plan_report, op_definition.as_ref()).len())
+                        .sum();
+
+                    if count > *max {
+                        violations.push(PolicyViolation {
+                            rule: rule.name.clone(),
+                            path: None,
+                            message: format!("The plan has {count} ops, but no more than {max} are allowed"),
+                        });
+                    }
+                }
+                PolicyCheck::OnlyOps(pattern) => {
+                    let allowed = compile(&rule.name, pattern)?;
+                    for plan_report in plan_reports {
+                        for op in rule.matches.matching_ops(plan_report, op_definition.as_ref()) {
+                            if !allowed.is_match(&op.op_definition) {
+                                violations.push(PolicyViolation {
+                                    rule: rule.name.clone(),
+                                    path: Some(plan_report.prefix.join(&plan_report.virt_addr)),
+                                    message: format!("Op not allowed: {}", describe(op)),
+                                });
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(violations)
+    }
+
+    /// Tests if `user` holds one of the `override_roles`, and so may apply a plan in violation of the policy.
+    pub fn allows_override(&self, rbac_config: &AutoschematicRbacConfig, user: &User) -> bool {
+        self.override_roles
+            .iter()
+            .filter_map(|role| rbac_config.roles.get(role))
+            .any(|role| role.users.contains(user))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::{config::AutoschematicConfig, config_rbac::Role, util::RON};
+
+    #[test]
+    fn test_evaluate() {
+        let config: AutoschematicConfig = RON
+            .from_str(
+                r#"AutoschematicConfig(
+                prefixes: {
+                    "prod": Prefix(connectors: [Connector(shortname: "dns", spec: Binary(path: "dns-connector"))]),
+                },
+            )"#,
+            )
+            .unwrap();
+
+        let policy: AutoschematicPolicyConfig = RON
+            .from_str(
+                r#"AutoschematicPolicyConfig(
+                rules: [
+                    PolicyRule(name: "no-prod-deletes", matches: PolicyMatch(prefix: "prod", deleted: true), check: Deny),
+                    PolicyRule(name: "no-drops", matches: PolicyMatch(op_definition: "^Drop"), check: Deny),
+                    PolicyRule(name: "small-changes", check: MaxOps(3)),
+                    PolicyRule(name: "dns-create-only", matches: PolicyMatch(connector: "dns"), check: OnlyOps("^Create")),
+                ],
+            )"#,
+            )
+            .unwrap();
+
+        let op = |op_definition: &str| PlanResponseElement {
+            op_definition: op_definition.to_string(),
+            ..Default::default()
+        };
+
+        let plan_report = |addr: &str, ops: Vec<PlanResponseElement>, desired_none: bool| PlanReport {
+            prefix: PathBuf::from("prod"),
+            connector_def: config.prefixes["prod"].connectors.first().cloned(),
+            virt_addr: PathBuf::from(addr),
+            connector_ops: ops,
+            desired_none,
+            ..Default::default()
+        };
+
+        let violated = |plan_reports: &[PlanReport]| -> Vec<String> {
+            policy.evaluate(plan_reports).unwrap().into_iter().map(|v| v.rule).collect()
+        };
+
+        assert!(violated(&[plan_report("zone/a.ron", vec![op("CreateRecord")], false)]).is_empty());
+
+        assert_eq!(
+            violated(&[plan_report("zone/a.ron", vec![op("DeleteRecord")], true)]),
+            vec!["no-prod-deletes", "dns-create-only"]
+        );
+
+        assert_eq!(
+            violated(&[plan_report("zone/a.ron", vec![op("DropZone")], false)]),
+            vec!["no-drops", "dns-create-only"]
+        );
+
+        assert_eq!(
+            violated(&[
+                plan_report("zone/a.ron", vec![op("CreateRecord"), op("CreateRecord")], false),
+                plan_report("zone/b.ron", vec![op("CreateRecord"), op("CreateRecord")], false),
+            ]),
+            vec!["small-changes"]
+        );
+    }
+
+    #[test]
+    fn test_allows_override() {
+        let admin = User::GithubUser {
+            username: String::from("finn"),
+        };
+        let other = User::GithubUser {
+            username: String::from("jake"),
+        };
+
+        let mut rbac_config = AutoschematicRbacConfig::default();
+        rbac_config.roles.insert(
+            String::from("admin"),
+            Role {
+                users: vec![admin.clone()],
+                ..Default::default()
+            },
+        );
+
+        let policy = AutoschematicPolicyConfig {
+            override_roles: vec![String::from("admin")],
+            ..Default::default()
+        };
+
+        assert!(policy.allows_override(&rbac_config, &admin));
+        assert!(!policy.allows_override(&rbac_config, &other));
+        assert!(!AutoschematicPolicyConfig::default().allows_override(&rbac_config, &admin));
+    }
+}
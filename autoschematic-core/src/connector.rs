@@ -211,6 +211,29 @@ impl OutputMapFile {
         Ok(Self::path(prefix, phy_addr))
     }
 
+    /// List the address of every resource in `prefix` with an output map file,
+    /// whether it holds outputs or links to another address.
+    pub fn list(prefix: &Path) -> Vec<PathBuf> {
+        let dir = PathBuf::from(".autoschematic").join(prefix);
+
+        let mut addrs: Vec<PathBuf> = walkdir::WalkDir::new(&dir)
+            .into_iter()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().is_file())
+            .filter_map(|entry| {
+                let path = entry.path().strip_prefix(&dir).ok()?;
+                let addr_name = path.file_name()?.to_str()?.strip_suffix(".out.json")?;
+                if addr_name.is_empty() {
+                    return None;
+                }
+                Some(path.with_file_name(addr_name))
+            })
+            .collect();
+
+        addrs.sort();
+        addrs
+    }
+
     pub fn delete(prefix: &Path, addr: &Path) -> anyhow::Result<Option<PathBuf>> {
         let path = Self::path(prefix, addr);
         if path.is_file() {
@@ -351,7 +374,32 @@ impl From<&'static str> for GetDocResponse {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+/// OpKind describes what a connector op does to its resource, so that the host
+/// can tell a create from a delete, or an in-place update from a replacement,
+/// without parsing `friendly_message`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OpKind {
+    Create,
+    Update,
+    Delete,
+    /// The resource is deleted and recreated.
+    Replace,
+    /// The op doesn't fit any of the above, or the connector didn't say.
+    #[default]
+    Other,
+}
+
+/// FieldDiff describes a change to a single field of a resource made by a connector op.
+/// `path` is a connector-defined path to the field, like "tags.Name" or "ingress[0].port".
+/// `before` and `after` are the rendered values of the field, or None if the field is absent.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldDiff {
+    pub path: String,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
 /// PlanResponseElement represents the successful result of `Connector.plan(addr, current, desired)``.
 /// Specifically, Connector.plan(...) will return a list of one or more PlanResponseElements representing
 /// a sequence of steps to take such that ideally, Connector.get(addr) == desired after executing
@@ -360,6 +408,23 @@ pub struct PlanResponseElement {
     pub op_definition: String,
     pub writes_outputs: Vec<String>,
     pub friendly_message: Option<String>,
+    /// What this op does to its resource. Defaults to OpKind::Other.
+    #[serde(default)]
+    pub op_kind: OpKind,
+    /// Whether this op may irrecoverably lose data, for instance by deleting or replacing
+    /// the resource, or by shrinking a volume.
+    #[serde(default)]
+    pub destructive: bool,
+    /// [Optional] The field-level changes this op makes.
+    #[serde(default)]
+    pub diffs: Vec<FieldDiff>,
+}
+
+impl PlanResponseElement {
+    /// Whether this op deletes or replaces its resource, or is otherwise marked destructive.
+    pub fn is_destructive(&self) -> bool {
+        self.destructive || matches!(self.op_kind, OpKind::Delete | OpKind::Replace)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
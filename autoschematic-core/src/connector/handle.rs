@@ -26,6 +26,9 @@ pub trait ConnectorHandle: Connector {
     async fn status(&self) -> ConnectorHandleStatus;
 
     async fn kill(&self) -> anyhow::Result<()>;
+
+    /// The contents of the error dump written by the connector process, if it has exited and written one.
+    fn error_dump(&self) -> Option<String>;
 }
 
 #[async_trait]
@@ -37,6 +40,10 @@ impl ConnectorHandle for Arc<dyn ConnectorHandle> {
     async fn kill(&self) -> anyhow::Result<()> {
         ConnectorHandle::kill(self.as_ref()).await
     }
+
+    fn error_dump(&self) -> Option<String> {
+        ConnectorHandle::error_dump(self.as_ref())
+    }
 }
 
 #[async_trait]
@@ -10,6 +10,7 @@ use crate::{
     config::Spec,
     connector::{ConnectorInbox, handle::ConnectorHandle},
     keystore::KeyStore,
+    lockfile::AutoschematicLockfile,
 };
 use anyhow::{Context, bail};
 use rand::{Rng, distr::Alphanumeric};
@@ -45,6 +46,11 @@ pub async fn spawn_connector(
     env: &HashMap<String, String>,
     keystore: Option<Arc<dyn KeyStore>>,
 ) -> Result<(Arc<dyn ConnectorHandle>, ConnectorInbox), anyhow::Error> {
+    // Refuse to launch a connector build other than the one pinned in autoschematic.lock.ron.
+    if let Some(lockfile) = AutoschematicLockfile::load()? {
+        lockfile.verify(prefix, shortname, spec)?;
+    }
+
     let (outbox, inbox) = tokio::sync::broadcast::channel(64);
 
     create_dir_all("/tmp/autoschematic")?;
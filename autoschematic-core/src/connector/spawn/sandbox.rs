@@ -233,6 +233,10 @@ impl ConnectorHandle for SandboxConnectorHandle {
         killpg(self.pid, SIGKILL)?;
         Ok(())
     }
+
+    fn error_dump(&self) -> Option<String> {
+        std::fs::read_to_string(&self.error_dump).ok()
+    }
 }
 
 pub fn pipe_fd_to_outbox(fd: &OwnedFd, outbox: &ConnectorOutbox) {
@@ -263,4 +263,8 @@ impl ConnectorHandle for UnsandboxConnectorHandle {
 
         Ok(())
     }
+
+    fn error_dump(&self) -> Option<String> {
+        std::fs::read_to_string(&self.error_dump).ok()
+    }
 }
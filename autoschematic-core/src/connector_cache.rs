@@ -2,6 +2,7 @@ use std::{
     collections::HashMap,
     path::{Path, PathBuf},
     sync::Arc,
+    time::{Duration, Instant},
 };
 
 use crate::{
@@ -17,7 +18,7 @@ use crate::{
     util::parse_env_file,
 };
 
-use anyhow::Context;
+use anyhow::{Context, bail};
 use dashmap::DashMap;
 use serde::Serialize;
 use tokio::task::JoinSet;
@@ -35,9 +36,101 @@ pub enum InitStatus {
 pub struct TopResponse {
     handle_status: ConnectorHandleStatus,
     init_status: InitStatus,
+    /// How many times the connector has been respawned after its process died.
+    restarts: u32,
+    /// The error dump of the last connector process to die, or the error from the last failed respawn.
+    last_error: Option<String>,
+    /// How long since the connector was last used, in seconds.
+    idle_secs: Option<u64>,
 }
 
-#[derive(Clone, PartialEq, Eq, Hash, Serialize)]
+/// The number of times a connector may be respawned after dying before the cache gives up on it.
+pub const MAX_CONNECTOR_RESTARTS: u32 = 5;
+
+/// A connector that dies this long after it was last respawned is taken to have recovered,
+/// and starts over with a full budget of restarts.
+pub const CONNECTOR_HEALTHY_AFTER: Duration = Duration::from_secs(10 * 60);
+
+/// How long to wait before the `restarts`th respawn of a connector, measured from the previous one.
+pub fn restart_backoff(restarts: u32) -> Duration {
+    if restarts == 0 {
+        Duration::ZERO
+    } else {
+        (Duration::from_millis(500) * 2u32.saturating_pow(restarts - 1)).min(Duration::from_secs(30))
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct RestartState {
+    restarts: u32,
+    last_error: Option<String>,
+    last_restart: Option<Instant>,
+}
+
+impl RestartState {
+    /// The restarts that count against the budget at `now`: none, if the connector stayed up
+    /// for CONNECTOR_HEALTHY_AFTER since it was last respawned.
+    fn recent_restarts(&self, now: Instant) -> u32 {
+        match self.last_restart {
+            Some(last_restart) if now.duration_since(last_restart) >= CONNECTOR_HEALTHY_AFTER => 0,
+            _ => self.restarts,
+        }
+    }
+}
+
+/// Merge the environment for a connector from its prefix's and its own env files and env maps,
+/// in increasing order of precedence. env:// passthroughs are left unresolved.
+pub fn connector_env(
+    prefix_def: &config::Prefix,
+    connector_def: &config::Connector,
+) -> anyhow::Result<HashMap<String, String>> {
+    let mut env = HashMap::new();
+
+    if let Some(ref env_file) = prefix_def.env_file {
+        for (k, v) in parse_env_file(&std::fs::read_to_string(env_file).context(format!("Reading env file {}", env_file))?) {
+            env.insert(k, v);
+        }
+    }
+
+    for (k, v) in &prefix_def.env {
+        env.insert(k.into(), v.into());
+    }
+
+    if let Some(ref env_file) = connector_def.env_file {
+        for (k, v) in parse_env_file(&std::fs::read_to_string(env_file).context(format!("Reading env file {}", env_file))?) {
+            env.insert(k, v);
+        }
+    }
+
+    for (k, v) in &connector_def.env {
+        env.insert(k.into(), v.into());
+    }
+
+    Ok(env)
+}
+
+/// When a cached connector was last used, and when it may be evicted, as set by its config::Connector.
+#[derive(Debug, Clone)]
+struct Residency {
+    last_used: Instant,
+    idle_timeout: Option<Duration>,
+    keep_resident: bool,
+}
+
+/// Read the limit on the total memory of resident connectors, in bytes, from
+/// AUTOSCHEMATIC_CONNECTOR_MEMORY_LIMIT, which is given in MiB.
+pub fn max_resident_memory_from_env() -> Option<u64> {
+    let limit = std::env::var("AUTOSCHEMATIC_CONNECTOR_MEMORY_LIMIT").ok()?;
+    match limit.parse::<u64>() {
+        Ok(mib) => Some(mib.saturating_mul(1024 * 1024)),
+        Err(e) => {
+            tracing::error!("Ignoring AUTOSCHEMATIC_CONNECTOR_MEMORY_LIMIT={}: {}", limit, e);
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
 pub struct ConnectorCacheKey {
     pub prefix: PathBuf,
     pub shortname: String,
@@ -53,6 +146,14 @@ pub struct ConnectorCache {
     /// static. Since filter() is the most common call, this can speed up workflows by
     /// avoiding calling out to the connectors so many times.
     filter_cache: Arc<DashMap<ConnectorCacheKey, HashMap<PathBuf, FilterResponse>>>,
+    /// Connectors that have died at least once, and so are respawned with backoff.
+    restart_state: Arc<DashMap<ConnectorCacheKey, RestartState>>,
+    /// Held while a connector is looked up and, if need be, spawned or respawned,
+    /// so that concurrent callers share the one process rather than each spawning their own.
+    spawn_locks: Arc<DashMap<ConnectorCacheKey, Arc<tokio::sync::Mutex<()>>>>,
+    residency: Arc<DashMap<ConnectorCacheKey, Residency>>,
+    /// If set, the least recently used connectors are evicted until the total memory of those remaining is under this many bytes.
+    max_resident_memory: Option<u64>,
     // TODO add doc_cache
     // binary_cache: BinaryCache,
 }
@@ -60,6 +161,11 @@ pub struct ConnectorCache {
 /// A ConnectorCache represents a handle to multiple Connector instances. The server, CLI, and LSP
 /// implementations all use a ConnectorCache to initialize connectors on-demand.
 impl ConnectorCache {
+    pub fn with_max_resident_memory(mut self, max_resident_memory: Option<u64>) -> Self {
+        self.max_resident_memory = max_resident_memory;
+        self
+    }
+
     pub async fn top(&self) -> HashMap<ConnectorCacheKey, TopResponse> {
         let mut res = HashMap::new();
 
@@ -73,11 +179,42 @@ impl ConnectorCache {
                     None => InitStatus::Initializing,
                 };
 
+                let restart_state = self.restart_state.get(&key).map(|kv| kv.value().clone()).unwrap_or_default();
+
+                let handle_status = connector.status().await;
+
+                // A connector that has died, but hasn't been respawned yet, may have left an error dump behind.
+                let last_error = match handle_status {
+                    ConnectorHandleStatus::Dead => connector.error_dump().or(restart_state.last_error),
+                    ConnectorHandleStatus::Alive { .. } => restart_state.last_error,
+                };
+
+                let idle_secs = self.residency.get(&key).map(|kv| kv.last_used.elapsed().as_secs());
+
                 res.insert(
                     key,
                     TopResponse {
-                        handle_status: connector.status().await,
+                        handle_status,
                         init_status,
+                        restarts: restart_state.restarts,
+                        last_error,
+                        idle_secs,
+                    },
+                );
+            }
+        }
+
+        // Connectors that died and could not be respawned are no longer in the cache, but are still worth reporting.
+        for kv in self.restart_state.iter() {
+            if !res.contains_key(kv.key()) {
+                res.insert(
+                    kv.key().clone(),
+                    TopResponse {
+                        handle_status: ConnectorHandleStatus::Dead,
+                        init_status: InitStatus::Offline,
+                        restarts: kv.value().restarts,
+                        last_error: kv.value().last_error.clone(),
+                        idle_secs: None,
                     },
                 );
             }
@@ -94,6 +231,7 @@ impl ConnectorCache {
 
         if let Some(entry) = self.cache.get(&key) {
             let (connector, inbox) = &*entry;
+            self.touch(&key);
             Some((connector.clone(), inbox.resubscribe()))
         } else {
             None
@@ -119,29 +257,47 @@ impl ConnectorCache {
 
         let spec = &connector_def.spec;
 
-        let mut env = HashMap::new();
-
-        if let Some(ref env_file) = prefix_def.env_file {
-            for (k, v) in parse_env_file(&std::fs::read_to_string(env_file).context(format!("Reading env file {}", env_file))?)
-            {
-                env.insert(k, v);
-            }
-        }
-
-        for (k, v) in &prefix_def.env {
-            env.insert(k.into(), v.into());
-        }
-
-        if let Some(ref env_file) = connector_def.env_file {
-            for (k, v) in parse_env_file(&std::fs::read_to_string(env_file).context(format!("Reading env file {}", env_file))?)
-            {
-                env.insert(k, v);
+        let env = connector_env(prefix_def, connector_def)?;
+
+        let spawn_lock = self.spawn_locks.entry(key.clone()).or_default().clone();
+        let _spawn_guard = spawn_lock.lock().await;
+
+        // If the connector's process has died, drop it from the cache so that it is respawned below.
+        // A connector that was initialized before it died is initialized again after respawning.
+        let mut do_init = do_init;
+        if let Some(connector) = self.cache.get(&key).map(|entry| entry.0.clone())
+            && let ConnectorHandleStatus::Dead = connector.status().await
+        {
+            let last_error = connector
+                .error_dump()
+                .unwrap_or(String::from("Connector process exited without any error dump!"));
+
+            tracing::error!(
+                "In prefix {}: connector {} died: {}",
+                prefix,
+                connector_def.shortname,
+                last_error
+            );
+
+            self.restart_state.entry(key.clone()).or_default().last_error = Some(last_error);
+            self.cache.remove(&key);
+            self.filter_cache.remove(&key);
+
+            if let Some((_, InitStatus::Running)) = self.init_status.remove(&key) {
+                do_init = true;
             }
         }
 
-        for (k, v) in &connector_def.env {
-            env.insert(k.into(), v.into());
-        }
+        self.residency.insert(
+            key.clone(),
+            Residency {
+                last_used: Instant::now(),
+                idle_timeout: connector_def
+                    .idle_timeout
+                    .map(|minutes| Duration::from_secs(minutes.saturating_mul(60))),
+                keep_resident: connector_def.keep_resident,
+            },
+        );
 
         if let Some((connector, inbox)) = self.cache.get(&key).map(|entry| {
             let (connector, inbox) = &*entry;
@@ -179,13 +335,26 @@ impl ConnectorCache {
 
             Ok((connector.clone(), inbox.resubscribe()))
         } else {
+            self.wait_for_restart(&key).await?;
+
+            // Make room for the new connector.
+            self.evict().await;
+
             self.init_status.insert(key.clone(), InitStatus::Spawning);
             // In order for the first process that invokes connector_init to receive the earliest messages from the inbox,
             //  we need to pass the original inbox, and not the resubscribed copy.
             // Hence the song and dance below with the Arc and resubscribe().
-            let (connector, inbox) = spawn_connector(&connector_def.shortname, spec, &PathBuf::from(prefix), &env, keystore)
-                .await
-                .context("spawn_connector()")?;
+            let (connector, inbox) =
+                match spawn_connector(&connector_def.shortname, spec, &PathBuf::from(prefix), &env, keystore).await {
+                    Ok(spawned) => spawned,
+                    Err(e) => {
+                        if let Some(mut restart_state) = self.restart_state.get_mut(&key) {
+                            restart_state.last_error = Some(format!("{:#}", e));
+                        }
+                        self.init_status.insert(key.clone(), InitStatus::Error(format!("{:#?}", e)));
+                        return Err(e.context("spawn_connector()").into());
+                    }
+                };
 
             check_connector_host_version_match(&connector_def.shortname, &connector).await?;
 
@@ -213,6 +382,52 @@ impl ConnectorCache {
         // }
     }
 
+    /// If the connector at `key` has died before, wait out its backoff before it is respawned,
+    /// or fail if it has used up its budget of restarts since it was last healthy.
+    async fn wait_for_restart(&self, key: &ConnectorCacheKey) -> anyhow::Result<()> {
+        let Some(restart_state) = self.restart_state.get(key).map(|kv| kv.value().clone()) else {
+            return Ok(());
+        };
+
+        let recent_restarts = restart_state.recent_restarts(Instant::now());
+
+        if recent_restarts >= MAX_CONNECTOR_RESTARTS {
+            bail!(
+                "In prefix {}: connector {} has already been respawned {} times, not respawning it again. Last error: {}",
+                key.prefix.display(),
+                key.shortname,
+                recent_restarts,
+                restart_state.last_error.unwrap_or_default()
+            );
+        }
+
+        let restarts = recent_restarts + 1;
+        let backoff = restart_backoff(restarts);
+        let elapsed = restart_state
+            .last_restart
+            .map(|last_restart| last_restart.elapsed())
+            .unwrap_or(backoff);
+
+        if let Some(remaining) = backoff.checked_sub(elapsed) {
+            tokio::time::sleep(remaining).await;
+        }
+
+        tracing::warn!(
+            "In prefix {}: respawning connector {} (restart {} of {})",
+            key.prefix.display(),
+            key.shortname,
+            restarts,
+            MAX_CONNECTOR_RESTARTS
+        );
+
+        if let Some(mut restart_state) = self.restart_state.get_mut(key) {
+            restart_state.restarts = restarts;
+            restart_state.last_restart = Some(Instant::now());
+        }
+
+        Ok(())
+    }
+
     pub async fn init_connector(&self, name: &str, prefix: &Path) -> Option<anyhow::Result<()>> {
         let key = ConnectorCacheKey {
             shortname: name.into(),
@@ -221,6 +436,7 @@ impl ConnectorCache {
 
         if let Some(entry) = self.cache.get(&key) {
             let (connector, _inbox) = &*entry;
+            self.touch(&key);
             self.clear_filter_cache(name, prefix).await;
             Some(connector.init().await)
         } else {
@@ -242,6 +458,7 @@ impl ConnectorCache {
         if let Some(value) = self.filter_cache.get(&key).and_then(|cache| cache.get(addr).copied()) {
             Ok(value)
         } else if let Some(connector) = self.cache.get(&key).map(|entry| entry.0.clone()) {
+            self.touch(&key);
             let res = connector.filter(addr).await?;
             self.filter_cache.entry(key.clone()).or_default().insert(addr.into(), res);
             Ok(res)
@@ -269,6 +486,126 @@ impl ConnectorCache {
         Ok(FilterResponse::None)
     }
 
+    fn touch(&self, key: &ConnectorCacheKey) {
+        if let Some(mut residency) = self.residency.get_mut(key) {
+            residency.last_used = Instant::now();
+        }
+    }
+
+    /// Kill and drop the connectors that have gone unused for longer than their idle_timeout.
+    /// Then, if the cache has a memory limit, kill and drop the least recently used connectors,
+    /// other than those set to keep_resident, until the total memory of the rest is under it.
+    /// Connectors that are still held outside the cache are in use, and are never evicted.
+    /// Returns the keys of the evicted connectors.
+    pub async fn evict(&self) -> Vec<ConnectorCacheKey> {
+        // Only the cache itself holds connectors that are not in use.
+        let idle: Vec<(ConnectorCacheKey, Arc<dyn ConnectorHandle>)> = self
+            .cache
+            .iter()
+            .filter(|kv| Arc::strong_count(&kv.value().0) == 1)
+            .map(|kv| (kv.key().clone(), kv.value().0.clone()))
+            .collect();
+
+        let mut candidates = Vec::new();
+        for (key, connector) in idle {
+            if let Some(residency) = self.residency.get(&key).map(|kv| kv.value().clone()) {
+                candidates.push((key, connector, residency));
+            }
+        }
+
+        let mut evicted = Vec::new();
+
+        candidates.retain(|(key, connector, residency)| {
+            if residency
+                .idle_timeout
+                .is_some_and(|idle_timeout| residency.last_used.elapsed() >= idle_timeout)
+            {
+                if self.remove_unused(key, connector) {
+                    evicted.push((key.clone(), connector.clone()));
+                }
+                false
+            } else {
+                true
+            }
+        });
+
+        if let Some(max_resident_memory) = self.max_resident_memory {
+            let connectors: Vec<(ConnectorCacheKey, Arc<dyn ConnectorHandle>)> =
+                self.cache.iter().map(|kv| (kv.key().clone(), kv.value().0.clone())).collect();
+
+            let mut memory = HashMap::new();
+            for (key, connector) in connectors {
+                if let ConnectorHandleStatus::Alive { memory: bytes, .. } = connector.status().await {
+                    memory.insert(key, bytes);
+                }
+            }
+
+            let mut total: u64 = memory.values().sum();
+
+            candidates.retain(|(_, _, residency)| !residency.keep_resident);
+            candidates.sort_by_key(|(_, _, residency)| residency.last_used);
+
+            for (key, connector, _) in candidates {
+                if total <= max_resident_memory {
+                    break;
+                }
+
+                if self.remove_unused(&key, &connector) {
+                    total = total.saturating_sub(memory.get(&key).copied().unwrap_or_default());
+                    evicted.push((key, connector));
+                }
+            }
+        }
+
+        for (key, connector) in &evicted {
+            tracing::info!("Evicting connector {} in prefix {}", key.shortname, key.prefix.display());
+            if let Err(e) = connector.kill().await {
+                tracing::warn!(
+                    "Failed to kill evicted connector {} in prefix {}: {}",
+                    key.shortname,
+                    key.prefix.display(),
+                    e
+                );
+            }
+        }
+
+        evicted.into_iter().map(|(key, _)| key).collect()
+    }
+
+    /// Remove `connector` from the cache, unless it has been replaced or is now in use.
+    /// The filter cache is kept, since filter() results are static.
+    fn remove_unused(&self, key: &ConnectorCacheKey, connector: &Arc<dyn ConnectorHandle>) -> bool {
+        // The cache and the caller hold one reference each.
+        let removed = self
+            .cache
+            .remove_if(key, |_, (cached, _)| {
+                Arc::ptr_eq(cached, connector) && Arc::strong_count(cached) == 2
+            })
+            .is_some();
+
+        if removed {
+            self.init_status.remove(key);
+            self.residency.remove(key);
+        }
+
+        removed
+    }
+
+    /// Evict connectors every `period` for as long as the cache is alive. See ConnectorCache::evict().
+    pub fn spawn_evictor(self: &Arc<Self>, period: Duration) -> tokio::task::JoinHandle<()> {
+        let cache = Arc::downgrade(self);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            loop {
+                interval.tick().await;
+                let Some(cache) = cache.upgrade() else {
+                    break;
+                };
+                cache.evict().await;
+            }
+        })
+    }
+
     pub async fn clear_filter_cache(&self, name: &str, prefix: &Path) {
         let key = ConnectorCacheKey {
             shortname: name.into(),
@@ -297,6 +634,8 @@ impl ConnectorCache {
 
         self.cache.clear();
         self.filter_cache.clear();
+        self.restart_state.clear();
+        self.residency.clear();
     }
 }
 
@@ -363,3 +702,177 @@ impl ConnectorCache {
 //         Ok(FilterResponse::None)
 //     }
 // }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use async_trait::async_trait;
+
+    use super::*;
+    use crate::connector::{ConnectorOutbox, GetResourceResponse, OpExecResponse, PlanResponseElement};
+
+    /// A connector that is always alive with a fixed memory footprint, and records whether it was killed.
+    struct FakeConnector {
+        memory: u64,
+        killed: Arc<AtomicBool>,
+    }
+
+    #[async_trait]
+    impl Connector for FakeConnector {
+        async fn new(_name: &str, _prefix: &Path, _outbox: ConnectorOutbox) -> anyhow::Result<Arc<dyn Connector>> {
+            bail!("FakeConnector::new() is a stub!")
+        }
+
+        async fn init(&self) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        async fn filter(&self, _addr: &Path) -> anyhow::Result<FilterResponse> {
+            Ok(FilterResponse::None)
+        }
+
+        async fn list(&self, _subpath: &Path) -> anyhow::Result<Vec<PathBuf>> {
+            Ok(Vec::new())
+        }
+
+        async fn get(&self, _addr: &Path) -> anyhow::Result<Option<GetResourceResponse>> {
+            Ok(None)
+        }
+
+        async fn plan(
+            &self,
+            _addr: &Path,
+            _current: Option<Vec<u8>>,
+            _desired: Option<Vec<u8>>,
+        ) -> anyhow::Result<Vec<PlanResponseElement>> {
+            Ok(Vec::new())
+        }
+
+        async fn op_exec(&self, _addr: &Path, _op: &str) -> anyhow::Result<OpExecResponse> {
+            bail!("FakeConnector::op_exec() is a stub!")
+        }
+    }
+
+    #[async_trait]
+    impl ConnectorHandle for FakeConnector {
+        async fn status(&self) -> ConnectorHandleStatus {
+            ConnectorHandleStatus::Alive {
+                memory: self.memory,
+                cpu_usage: 0.0,
+            }
+        }
+
+        async fn kill(&self) -> anyhow::Result<()> {
+            self.killed.store(true, Ordering::SeqCst);
+            Ok(())
+        }
+
+        fn error_dump(&self) -> Option<String> {
+            None
+        }
+    }
+
+    /// Put a FakeConnector named `shortname` in the cache, returning its key and whether it has been killed.
+    fn insert_fake(
+        cache: &ConnectorCache,
+        shortname: &str,
+        memory: u64,
+        residency: Residency,
+    ) -> (ConnectorCacheKey, Arc<AtomicBool>) {
+        let key = ConnectorCacheKey {
+            prefix: PathBuf::from("main"),
+            shortname: shortname.into(),
+        };
+        let killed = Arc::new(AtomicBool::new(false));
+        let connector: Arc<dyn ConnectorHandle> = Arc::new(FakeConnector {
+            memory,
+            killed: killed.clone(),
+        });
+        let (_outbox, inbox) = tokio::sync::broadcast::channel(1);
+
+        cache.cache.insert(key.clone(), (connector, inbox));
+        cache.init_status.insert(key.clone(), InitStatus::Running);
+        cache.residency.insert(key.clone(), residency);
+        (key, killed)
+    }
+
+    fn residency(last_used: Instant, idle_timeout: Option<Duration>, keep_resident: bool) -> Residency {
+        Residency {
+            last_used,
+            idle_timeout,
+            keep_resident,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_evict_idle() {
+        let cache = ConnectorCache::default();
+        let now = Instant::now();
+
+        let (idle, idle_killed) = insert_fake(&cache, "idle", 100, residency(now, Some(Duration::ZERO), false));
+        let (_, active_killed) = insert_fake(&cache, "active", 100, residency(now, Some(Duration::from_secs(60)), false));
+        let (held, held_killed) = insert_fake(&cache, "held", 100, residency(now, Some(Duration::ZERO), false));
+
+        // A connector that's still held outside the cache is in use, however long it has been idle.
+        let _in_use = cache.cache.get(&held).map(|kv| kv.0.clone());
+
+        assert_eq!(cache.evict().await, vec![idle.clone()]);
+        assert!(idle_killed.load(Ordering::SeqCst));
+        assert!(!active_killed.load(Ordering::SeqCst));
+        assert!(!held_killed.load(Ordering::SeqCst));
+
+        assert!(!cache.cache.contains_key(&idle));
+        assert!(!cache.residency.contains_key(&idle));
+        assert!(cache.cache.contains_key(&held));
+    }
+
+    #[tokio::test]
+    async fn test_evict_lru() {
+        let cache = ConnectorCache::default().with_max_resident_memory(Some(250));
+        let now = Instant::now();
+        let used = |secs: u64| now + Duration::from_secs(secs);
+
+        // Evicted from the least recently used, skipping the resident one, until 250 bytes or fewer remain.
+        let (_, resident_killed) = insert_fake(&cache, "resident", 100, residency(used(0), None, true));
+        let (oldest, _) = insert_fake(&cache, "oldest", 100, residency(used(1), None, false));
+        let (older, _) = insert_fake(&cache, "older", 100, residency(used(2), None, false));
+        let (newest, newest_killed) = insert_fake(&cache, "newest", 100, residency(used(3), None, false));
+
+        assert_eq!(cache.evict().await, vec![oldest, older]);
+        assert!(!resident_killed.load(Ordering::SeqCst));
+        assert!(!newest_killed.load(Ordering::SeqCst));
+        assert!(cache.cache.contains_key(&newest));
+
+        // Under the limit, nothing more is evicted.
+        assert!(cache.evict().await.is_empty());
+    }
+
+    #[test]
+    fn test_restart_backoff() {
+        assert_eq!(restart_backoff(0), Duration::ZERO);
+        assert_eq!(restart_backoff(1), Duration::from_millis(500));
+        assert_eq!(restart_backoff(2), Duration::from_secs(1));
+        assert_eq!(restart_backoff(4), Duration::from_secs(4));
+        assert_eq!(restart_backoff(MAX_CONNECTOR_RESTARTS), Duration::from_secs(8));
+        // Capped, and doesn't overflow.
+        assert_eq!(restart_backoff(64), Duration::from_secs(30));
+    }
+
+    #[test]
+    fn test_recent_restarts() {
+        let last_restart = Instant::now();
+        let restart_state = RestartState {
+            restarts: MAX_CONNECTOR_RESTARTS,
+            last_error: None,
+            last_restart: Some(last_restart),
+        };
+
+        assert_eq!(
+            restart_state.recent_restarts(last_restart + Duration::from_secs(5)),
+            MAX_CONNECTOR_RESTARTS
+        );
+        assert_eq!(restart_state.recent_restarts(last_restart + CONNECTOR_HEALTHY_AFTER), 0);
+        assert_eq!(RestartState::default().recent_restarts(last_restart), 0);
+    }
+}
@@ -5,7 +5,8 @@ use std::{
 
 use anyhow::{Context, bail};
 use git2::{
-    Cred, FetchOptions, IndexAddOption, PushOptions, RemoteCallbacks, Repository, Status, StatusOptions,
+    Cred, Delta, DiffFindOptions, FetchOptions, IndexAddOption, PushOptions, RemoteCallbacks, Repository, Status,
+    StatusOptions, Tree,
     build::{CheckoutBuilder, RepoBuilder},
 };
 use secrecy::{ExposeSecret, SecretBox};
@@ -140,6 +141,18 @@ pub fn git_add(repo_path: &Path, path: &Path) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Remove `path` from the index, as `git rm --cached` would.
+pub fn git_rm(repo_path: &Path, path: &Path) -> anyhow::Result<()> {
+    let Ok(repository) = Repository::open(repo_path) else {
+        bail!("No repository at {}", &repo_path.to_str().unwrap_or_default())
+    };
+
+    let mut index = repository.index()?;
+    index.remove_all([path], None)?;
+    index.write()?;
+    Ok(())
+}
+
 pub fn git_commit(repo_path: &Path, username: &str, email: &str, message: &str) -> anyhow::Result<()> {
     let Ok(repository) = Repository::open(repo_path) else {
         bail!("No repository at {}", &repo_path.to_str().unwrap_or_default())
@@ -276,3 +289,154 @@ pub fn get_staged_files() -> Result<Vec<PathBuf>, git2::Error> {
     }
     Ok(staged)
 }
+
+/// A file changed between two commits, along with its contents at the later commit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangedFile {
+    pub path: PathBuf,
+    /// The contents of the file at the head commit, or None if it was deleted.
+    pub contents: Option<Vec<u8>>,
+    /// The contents of the file at the base commit, or None if it was created.
+    pub previous_contents: Option<Vec<u8>>,
+}
+
+/// Read the contents of the file at `path` in the HEAD commit, or None if it doesn't exist there.
+pub fn read_head_file(path: &Path) -> anyhow::Result<Option<Vec<u8>>> {
+    let repo = Repository::discover(".")?;
+    let Ok(head) = repo.head() else {
+        return Ok(None);
+    };
+    let head_tree = head.peel_to_tree()?;
+
+    let Ok(entry) = head_tree.get_path(path) else {
+        return Ok(None);
+    };
+
+    Ok(Some(repo.find_blob(entry.id())?.content().to_vec()))
+}
+
+/// List the files changed between the commits at `base_ref` and `head_ref` (or HEAD),
+/// including deletions and renames. A rename is reported as a deletion of the old path
+/// and a creation of the new one.
+/// The result is ordered for apply in the same manner as the server's `sort_objects_by_apply_order`.
+pub fn get_changed_files(base_ref: &str, head_ref: Option<&str>) -> anyhow::Result<Vec<ChangedFile>> {
+    let repo = Repository::discover(".")?;
+
+    let base_tree = repo
+        .revparse_single(base_ref)
+        .and_then(|o| o.peel_to_tree())
+        .context(format!("Resolving base ref {base_ref}"))?;
+
+    let head_ref = head_ref.unwrap_or("HEAD");
+    let head_tree = repo
+        .revparse_single(head_ref)
+        .and_then(|o| o.peel_to_tree())
+        .context(format!("Resolving head ref {head_ref}"))?;
+
+    let mut diff = repo.diff_tree_to_tree(Some(&base_tree), Some(&head_tree), None)?;
+    diff.find_similar(Some(DiffFindOptions::new().renames(true)))?;
+
+    let read_blob = |tree: &Tree, path: &Path| -> anyhow::Result<Vec<u8>> {
+        let entry = tree.get_path(path)?;
+        Ok(repo.find_blob(entry.id())?.content().to_vec())
+    };
+
+    let mut changed_files = Vec::new();
+    for delta in diff.deltas() {
+        let old_path = delta.old_file().path();
+        let new_path = delta.new_file().path();
+
+        match (delta.status(), old_path, new_path) {
+            (Delta::Added | Delta::Copied, _, Some(new_path)) => {
+                changed_files.push(ChangedFile {
+                    path: new_path.to_path_buf(),
+                    contents: Some(read_blob(&head_tree, new_path)?),
+                    previous_contents: None,
+                });
+            }
+            (Delta::Modified | Delta::Typechange, Some(old_path), Some(new_path)) => {
+                changed_files.push(ChangedFile {
+                    path: new_path.to_path_buf(),
+                    contents: Some(read_blob(&head_tree, new_path)?),
+                    previous_contents: read_blob(&base_tree, old_path).ok(),
+                });
+            }
+            (Delta::Deleted, Some(old_path), _) => {
+                changed_files.push(ChangedFile {
+                    path: old_path.to_path_buf(),
+                    contents: None,
+                    previous_contents: Some(read_blob(&base_tree, old_path)?),
+                });
+            }
+            (Delta::Renamed, Some(old_path), Some(new_path)) => {
+                changed_files.push(ChangedFile {
+                    path: old_path.to_path_buf(),
+                    contents: None,
+                    previous_contents: Some(read_blob(&base_tree, old_path)?),
+                });
+                changed_files.push(ChangedFile {
+                    path: new_path.to_path_buf(),
+                    contents: Some(read_blob(&head_tree, new_path)?),
+                    previous_contents: None,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    sort_changed_files_by_apply_order(&mut changed_files);
+
+    Ok(changed_files)
+}
+
+/// Ordering rules, as for the server's `sort_objects_by_apply_order`:
+/// Create/Modify always comes before Delete.
+/// Create/Modify are sorted shortest path first
+/// Delete are sorted longest path first
+pub fn sort_changed_files_by_apply_order(changed_files: &mut [ChangedFile]) {
+    changed_files.sort_by_key(|f| {
+        let depth = f.path.components().count() as isize;
+        match f.contents {
+            Some(_) => (false, depth),
+            None => (true, -depth),
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sort_changed_files_by_apply_order() {
+        let changed_file = |path: &str, deleted: bool| ChangedFile {
+            path: PathBuf::from(path),
+            contents: if deleted { None } else { Some(Vec::new()) },
+            previous_contents: None,
+        };
+
+        let mut changed_files = vec![
+            changed_file("a/b/c/deleted.ron", true),
+            changed_file("a/b/c/created.ron", false),
+            changed_file("a/deleted.ron", true),
+            changed_file("a/created.ron", false),
+            changed_file("a/b/deleted.ron", true),
+            changed_file("a/b/created.ron", false),
+        ];
+
+        sort_changed_files_by_apply_order(&mut changed_files);
+
+        let paths: Vec<&str> = changed_files.iter().map(|f| f.path.to_str().unwrap()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "a/created.ron",
+                "a/b/created.ron",
+                "a/b/c/created.ron",
+                "a/b/c/deleted.ron",
+                "a/b/deleted.ron",
+                "a/deleted.ron",
+            ]
+        );
+    }
+}
@@ -41,6 +41,76 @@ pub fn addr_matches_filter(addr: &Path, filter: &Path) -> bool {
     true
 }
 
+/// Like `addr_matches_filter`, but each component of `pattern` may also contain `*` and `?` wildcards
+/// that match within that component, and a `**` component matches any number of components.
+/// As with `addr_matches_filter`, a pattern that matches a parent directory of `addr` matches `addr`.
+/// For example:
+///
+/// addr_matches_glob("aws/rds/us-east-1/db/prod-main.ron", "aws/rds/*/db/prod-*") -> true
+///
+/// addr_matches_glob("aws/rds/us-east-1/db/prod-main.ron", "**/prod-*.ron") -> true
+///
+/// addr_matches_glob("aws/rds/us-east-1/db/staging.ron", "**/prod-*.ron") -> false
+pub fn addr_matches_glob(addr: &Path, pattern: &Path) -> bool {
+    let Some(addr_components) = normal_components(addr) else {
+        return false;
+    };
+    let Some(pattern_components) = normal_components(pattern) else {
+        return false;
+    };
+
+    components_match_glob(&addr_components, &pattern_components)
+}
+
+fn normal_components(path: &Path) -> Option<Vec<&str>> {
+    path.components()
+        .filter(|c| *c != Component::CurDir)
+        .map(|c| match c {
+            Component::Normal(c) => c.to_str(),
+            _ => None,
+        })
+        .collect()
+}
+
+fn components_match_glob(addr: &[&str], pattern: &[&str]) -> bool {
+    match pattern.split_first() {
+        None => true,
+        Some((&"**", pattern_rest)) => (0..=addr.len()).any(|i| components_match_glob(&addr[i..], pattern_rest)),
+        Some((pattern_first, pattern_rest)) => match addr.split_first() {
+            Some((addr_first, addr_rest)) => {
+                component_matches_glob(addr_first, pattern_first) && components_match_glob(addr_rest, pattern_rest)
+            }
+            None => false,
+        },
+    }
+}
+
+fn component_matches_glob(component: &str, pattern: &str) -> bool {
+    let component: Vec<char> = component.chars().collect();
+    let pattern: Vec<char> = pattern.chars().collect();
+
+    // Greedy wildcard matching, backtracking to the most recent '*'.
+    let (mut c, mut p) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while c < component.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == component[c]) {
+            c += 1;
+            p += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, c));
+            p += 1;
+        } else if let Some((star_p, star_c)) = star {
+            p = star_p + 1;
+            c = star_c + 1;
+            star = Some((star_p, star_c + 1));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|ch| *ch == '*')
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -122,4 +192,17 @@ mod tests {
         let filter = Path::new("*/*/user/jon.ron");
         assert!(addr_matches_filter(addr, filter));
     }
+
+    #[test]
+    fn test_addr_matches_glob() {
+        let addr = Path::new("aws/rds/us-east-1/db/prod-main.ron");
+        assert!(addr_matches_glob(addr, Path::new("aws/rds")));
+        assert!(addr_matches_glob(addr, Path::new("./aws/rds/*/db/prod-*")));
+        assert!(addr_matches_glob(addr, Path::new("**/prod-*.ron")));
+        assert!(addr_matches_glob(addr, Path::new("aws/**/db")));
+        assert!(addr_matches_glob(addr, Path::new("aws/rds/us-east-?/db")));
+        assert!(!addr_matches_glob(addr, Path::new("**/staging-*.ron")));
+        assert!(!addr_matches_glob(addr, Path::new("aws/rds/eu-*")));
+        assert!(!addr_matches_glob(Path::new("aws/rds"), Path::new("aws/rds/us-east-1")));
+    }
 }
@@ -0,0 +1,739 @@
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::bail;
+use serde::Serialize;
+
+use crate::{
+    bundle::BundleMapFile,
+    config::AutoschematicConfig,
+    connector::{FilterResponse, VirtToPhyResponse},
+    connector_cache::ConnectorCache,
+    git_util::{ChangedFile, read_head_file},
+    keystore::KeyStore,
+    report::PlanReport,
+    template::{ReadOutput, descend_get_read_outputs, get_read_outputs},
+    util::{list_prefix_addrs, split_prefix_addr},
+};
+
+/// A resource, identified by its prefix and virtual address.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+pub struct ResourceNode {
+    pub prefix: PathBuf,
+    pub addr: PathBuf,
+}
+
+impl ResourceNode {
+    pub fn new(prefix: &Path, addr: &Path) -> Self {
+        Self {
+            prefix: prefix.to_path_buf(),
+            addr: addr.to_path_buf(),
+        }
+    }
+
+    /// The path of this resource's file relative to the repository root.
+    pub fn path(&self) -> PathBuf {
+        self.prefix.join(&self.addr)
+    }
+
+    fn depth(&self) -> usize {
+        self.path().components().count()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum EdgeKind {
+    /// The dependent's body reads `out://<dependency>[key]`.
+    ReadsOutput { key: String },
+    /// The dependent's connector deferred resolving its physical address on `out://<dependency>[key]`.
+    Deferred { key: String },
+    /// The dependent was unpacked from the dependency, which is a bundle.
+    ChildOf,
+    /// The dependent depends on the dependency through other resources.
+    Transitive,
+}
+
+/// An edge in the resource graph: `from` must be applied before `to`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ResourceEdge {
+    pub from: ResourceNode,
+    pub to: ResourceNode,
+    #[serde(flatten)]
+    pub kind: EdgeKind,
+}
+
+/// The dependency graph between resources, built from their out:// references,
+/// deferred physical addresses and bundle links.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ResourceGraph {
+    pub nodes: BTreeSet<ResourceNode>,
+    pub edges: Vec<ResourceEdge>,
+    /// The (from, to, kind) of each of `edges`, so that add_edge can skip duplicates without a linear scan.
+    #[serde(skip)]
+    edge_set: HashSet<(ResourceNode, ResourceNode, EdgeKind)>,
+}
+
+impl ResourceGraph {
+    pub fn add_node(&mut self, node: ResourceNode) {
+        self.nodes.insert(node);
+    }
+
+    pub fn add_edge(&mut self, from: ResourceNode, to: ResourceNode, kind: EdgeKind) {
+        if !self.edge_set.insert((from.clone(), to.clone(), kind.clone())) {
+            return;
+        }
+        self.nodes.insert(from.clone());
+        self.nodes.insert(to.clone());
+        self.edges.push(ResourceEdge { from, to, kind });
+    }
+
+    /// Add the resource at `prefix`/`addr` with the given body, along with edges for each out:// reference
+    /// in the body and for its bundle link, if any.
+    pub fn add_resource(&mut self, prefix: &Path, addr: &Path, body: Option<&str>) -> anyhow::Result<()> {
+        let node = ResourceNode::new(prefix, addr);
+        self.add_node(node.clone());
+
+        if let Some(body) = body {
+            // Not every resource file is RON, so fall back to a plain regex scan if it doesn't parse.
+            let read_outputs: Vec<ReadOutput> = match descend_get_read_outputs(body) {
+                Ok(report) => report.reads_outputs.into_iter().map(|(_, read_output)| read_output).collect(),
+                Err(_) => get_read_outputs(body),
+            };
+
+            for read_output in read_outputs {
+                self.add_edge(
+                    ResourceNode::new(prefix, &read_output.addr),
+                    node.clone(),
+                    EdgeKind::ReadsOutput { key: read_output.key },
+                );
+            }
+        }
+
+        if let Some(BundleMapFile::ChildOf { parent }) = BundleMapFile::read(prefix, addr)? {
+            self.add_edge(ResourceNode::new(prefix, &parent), node, EdgeKind::ChildOf);
+        }
+
+        Ok(())
+    }
+
+    /// Add edges for a resource whose physical address was deferred on `read_outputs`.
+    pub fn add_deferred(&mut self, prefix: &Path, addr: &Path, read_outputs: &[ReadOutput]) {
+        let node = ResourceNode::new(prefix, addr);
+        for read_output in read_outputs {
+            self.add_edge(
+                ResourceNode::new(prefix, &read_output.addr),
+                node.clone(),
+                EdgeKind::Deferred {
+                    key: read_output.key.clone(),
+                },
+            );
+        }
+    }
+
+    /// Remove all edges from the dependencies of `node` to `node`.
+    pub fn remove_dependencies(&mut self, node: &ResourceNode) {
+        self.edges.retain(|e| e.to != *node);
+        self.edge_set.retain(|(_, to, _)| to != node);
+    }
+
+    /// The nodes reachable from `node` by following edges from dependency to dependent, excluding `node` itself
+    /// unless it lies on a cycle.
+    pub fn descendants(&self, node: &ResourceNode) -> HashSet<ResourceNode> {
+        let adjacency = self.adjacency();
+
+        let mut seen: HashSet<&ResourceNode> = HashSet::new();
+        let mut stack: Vec<&ResourceNode> = adjacency.get(node).into_iter().flatten().copied().collect();
+        while let Some(next) = stack.pop() {
+            if seen.insert(next) {
+                stack.extend(adjacency.get(next).into_iter().flatten().copied());
+            }
+        }
+
+        seen.into_iter().cloned().collect()
+    }
+
+    /// The resources that `node` depends on.
+    pub fn dependencies<'a>(&'a self, node: &'a ResourceNode) -> impl Iterator<Item = &'a ResourceEdge> + 'a {
+        self.edges.iter().filter(move |e| e.to == *node)
+    }
+
+    /// The resources that depend on `node`.
+    pub fn dependents<'a>(&'a self, node: &'a ResourceNode) -> impl Iterator<Item = &'a ResourceEdge> + 'a {
+        self.edges.iter().filter(move |e| e.from == *node)
+    }
+
+    fn adjacency(&self) -> HashMap<&ResourceNode, BTreeSet<&ResourceNode>> {
+        let mut adjacency: HashMap<&ResourceNode, BTreeSet<&ResourceNode>> = HashMap::new();
+        for edge in &self.edges {
+            adjacency.entry(&edge.from).or_default().insert(&edge.to);
+        }
+        adjacency
+    }
+
+    /// Find a cycle in the graph, if there is one.
+    /// The returned cycle starts and ends with the same node.
+    pub fn find_cycle(&self) -> Option<Vec<ResourceNode>> {
+        let adjacency = self.adjacency();
+
+        let mut done: HashSet<&ResourceNode> = HashSet::new();
+
+        for root in &self.nodes {
+            if done.contains(root) {
+                continue;
+            }
+
+            // Iterative DFS, where `stack` holds the current path along with each node's remaining children.
+            let mut stack: Vec<(&ResourceNode, Vec<&ResourceNode>)> = vec![(
+                root,
+                adjacency.get(root).map(|c| c.iter().copied().collect()).unwrap_or_default(),
+            )];
+            let mut on_path: HashSet<&ResourceNode> = HashSet::from([root]);
+
+            while let Some((node, children)) = stack.last_mut() {
+                let node = *node;
+                let Some(child) = children.pop() else {
+                    on_path.remove(node);
+                    done.insert(node);
+                    stack.pop();
+                    continue;
+                };
+
+                if on_path.contains(child) {
+                    let start = stack.iter().position(|(n, _)| *n == child).unwrap_or_default();
+                    let mut cycle: Vec<ResourceNode> = stack[start..].iter().map(|(n, _)| (*n).clone()).collect();
+                    cycle.push(child.clone());
+                    return Some(cycle);
+                }
+
+                if !done.contains(child) {
+                    on_path.insert(child);
+                    stack.push((
+                        child,
+                        adjacency.get(child).map(|c| c.iter().copied().collect()).unwrap_or_default(),
+                    ));
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Order the nodes so that each resource comes after all of the resources it depends on.
+    /// Where the order is otherwise unconstrained, shallower paths come first.
+    /// Fails if the graph contains a cycle.
+    pub fn topological_order(&self) -> anyhow::Result<Vec<ResourceNode>> {
+        let adjacency = self.adjacency();
+
+        let mut in_degree: HashMap<&ResourceNode, usize> = self.nodes.iter().map(|n| (n, 0)).collect();
+        for children in adjacency.values() {
+            for child in children {
+                *in_degree.entry(child).or_default() += 1;
+            }
+        }
+
+        let mut ready: BTreeSet<(usize, &ResourceNode)> = in_degree
+            .iter()
+            .filter(|(_, degree)| **degree == 0)
+            .map(|(node, _)| (node.depth(), *node))
+            .collect();
+
+        let mut order = Vec::with_capacity(self.nodes.len());
+        while let Some((_, node)) = ready.pop_first() {
+            order.push(node.clone());
+
+            for child in adjacency.get(node).into_iter().flatten() {
+                let degree = in_degree.get_mut(child).unwrap();
+                *degree -= 1;
+                if *degree == 0 {
+                    ready.insert((child.depth(), child));
+                }
+            }
+        }
+
+        if order.len() < self.nodes.len() {
+            let cycle = self.find_cycle().unwrap_or_default();
+            bail!(
+                "Dependency cycle between resources: {}",
+                cycle
+                    .iter()
+                    .map(|n| n.path().display().to_string())
+                    .collect::<Vec<String>>()
+                    .join(" -> ")
+            );
+        }
+
+        Ok(order)
+    }
+
+    /// Render the graph in Graphviz DOT format.
+    pub fn to_dot(&self) -> String {
+        let quote = |node: &ResourceNode| format!("\"{}\"", node.path().display().to_string().replace('"', "\\\""));
+
+        let mut dot = String::from("digraph autoschematic {\n    rankdir=LR;\n");
+
+        for node in &self.nodes {
+            dot.push_str(&format!("    {};\n", quote(node)));
+        }
+
+        for edge in &self.edges {
+            let attrs = match &edge.kind {
+                EdgeKind::ReadsOutput { key } => format!("label=\"{}\"", key.replace('"', "\\\"")),
+                EdgeKind::Deferred { key } => format!("label=\"{}\", style=dashed", key.replace('"', "\\\"")),
+                EdgeKind::ChildOf => String::from("label=\"bundle\", style=dotted"),
+                EdgeKind::Transitive => String::from("style=dotted"),
+            };
+            dot.push_str(&format!("    {} -> {} [{}];\n", quote(&edge.from), quote(&edge.to), attrs));
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// Build the resource graph from the files on disk, for every prefix or a single prefix by name.
+/// This covers out:// references and bundle links, but not deferred physical addresses,
+/// which require querying connectors; see `add_deferred_edges`.
+pub fn build_graph(config: &AutoschematicConfig, prefix_filter: Option<&str>) -> anyhow::Result<ResourceGraph> {
+    let mut graph = ResourceGraph::default();
+
+    for prefix_name in config.prefixes.keys() {
+        if prefix_filter.is_some_and(|f| f != prefix_name) {
+            continue;
+        }
+
+        let prefix = PathBuf::from(prefix_name);
+        for addr in list_prefix_addrs(&prefix) {
+            let body = std::fs::read_to_string(prefix.join(&addr)).ok();
+            graph.add_resource(&prefix, &addr, body.as_deref())?;
+        }
+    }
+
+    Ok(graph)
+}
+
+/// Query connectors for each resource file in the graph and add edges for those whose
+/// physical address is deferred on the outputs of other resources.
+pub async fn add_deferred_edges(
+    graph: &mut ResourceGraph,
+    config: &AutoschematicConfig,
+    connector_cache: &ConnectorCache,
+    keystore: Option<Arc<dyn KeyStore>>,
+    connector_filter: Option<&str>,
+) -> anyhow::Result<()> {
+    let nodes: Vec<ResourceNode> = graph.nodes.iter().filter(|n| n.path().is_file()).cloned().collect();
+
+    for node in nodes {
+        let Some(prefix_name) = node.prefix.to_str() else {
+            continue;
+        };
+        let Some(prefix_def) = config.prefixes.get(prefix_name) else {
+            continue;
+        };
+
+        for connector_def in &prefix_def.connectors {
+            if connector_filter.is_some_and(|f| f != connector_def.shortname) {
+                continue;
+            }
+
+            if connector_cache
+                .filter_cached(&connector_def.shortname, &node.prefix, &node.addr)
+                .await?
+                != FilterResponse::Resource
+            {
+                continue;
+            }
+
+            let (connector, _inbox) = connector_cache
+                .get_or_spawn_connector(config, prefix_name, connector_def, keystore.clone(), true)
+                .await?;
+
+            if let VirtToPhyResponse::Deferred(read_outputs) = connector.addr_virt_to_phy(&node.addr).await? {
+                graph.add_deferred(&node.prefix, &node.addr, &read_outputs);
+            }
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+/// Sort `changed_files` so that each resource is applied after the resources it reads outputs from,
+/// and deleted after the resources that read outputs from it.
+/// Created and modified files are ordered by their new contents, deleted files by their previous contents,
+/// and every other resource in the prefixes of `changed_files` by its contents on disk.
+/// Only a dependency cycle between changed files is an error; cycles elsewhere are ignored.
+/// As before, creations and modifications always come before deletions.
+pub fn sort_changed_files_by_dependencies(
+    config: &AutoschematicConfig,
+    changed_files: &mut [ChangedFile],
+) -> anyhow::Result<()> {
+    let mut changed_nodes = HashMap::new();
+    for changed_file in changed_files.iter() {
+        if let Some((prefix, addr)) = split_prefix_addr(config, &changed_file.path) {
+            changed_nodes.insert(changed_file.path.clone(), ResourceNode::new(&prefix, &addr));
+        }
+    }
+
+    let prefixes: BTreeSet<&Path> = changed_nodes.values().map(|n| n.prefix.as_path()).collect();
+
+    let mut graph = ResourceGraph::default();
+    for prefix in prefixes {
+        for addr in list_prefix_addrs(prefix) {
+            let body = std::fs::read_to_string(prefix.join(&addr)).ok();
+            // An unreadable bundle map elsewhere in the prefix shouldn't block unrelated changes.
+            if let Err(e) = graph.add_resource(prefix, &addr, body.as_deref()) {
+                tracing::warn!("Skipping {} in dependency graph: {:#}", prefix.join(&addr).display(), e);
+            }
+        }
+    }
+
+    for changed_file in changed_files.iter() {
+        let Some(node) = changed_nodes.get(&changed_file.path) else {
+            continue;
+        };
+
+        let body = changed_file.contents.as_ref().or(changed_file.previous_contents.as_ref());
+
+        graph.remove_dependencies(node);
+        graph.add_resource(&node.prefix, &node.addr, body.map(|b| String::from_utf8_lossy(b)).as_deref())?;
+    }
+
+    // Reduce the graph to the changed nodes, with an edge wherever one changed node reaches another.
+    let changed_set: HashSet<&ResourceNode> = changed_nodes.values().collect();
+    let mut changed_graph = ResourceGraph::default();
+    for node in &changed_set {
+        changed_graph.add_node((*node).clone());
+        for descendant in graph.descendants(node) {
+            if changed_set.contains(&descendant) {
+                changed_graph.add_edge((*node).clone(), descendant, EdgeKind::Transitive);
+            }
+        }
+    }
+
+    let index: HashMap<ResourceNode, usize> = changed_graph
+        .topological_order()?
+        .into_iter()
+        .enumerate()
+        .map(|(i, node)| (node, i))
+        .collect();
+
+    changed_files.sort_by_key(|f| {
+        let position = changed_nodes
+            .get(&f.path)
+            .and_then(|n| index.get(n))
+            .copied()
+            .unwrap_or_default() as isize;
+        match f.contents {
+            Some(_) => (false, position),
+            None => (true, -position),
+        }
+    });
+
+    Ok(())
+}
+
+/// For each of `plan_reports`, the indices of the other reports in `plan_reports` for the resources it depends on,
+/// through the out:// references in its body, its bundle link, or its missing outputs.
+/// A plan's body is its file on disk, or for a deletion, its previous contents at HEAD.
+/// Where the dependency is being deleted, the edge is reversed, so that its dependents are applied
+/// (and so destroyed or detached) first.
+pub fn plan_report_dependencies(plan_reports: &[PlanReport]) -> anyhow::Result<Vec<Vec<usize>>> {
+    let bodies: Vec<Option<String>> = plan_reports
+        .iter()
+        .map(|plan_report| {
+            let path = plan_report.prefix.join(&plan_report.virt_addr);
+            std::fs::read_to_string(&path).ok().or_else(|| {
+                read_head_file(&path)
+                    .ok()
+                    .flatten()
+                    .map(|b| String::from_utf8_lossy(&b).into_owned())
+            })
+        })
+        .collect();
+
+    plan_report_dependencies_with_bodies(plan_reports, &bodies)
+}
+
+fn plan_report_dependencies_with_bodies(
+    plan_reports: &[PlanReport],
+    bodies: &[Option<String>],
+) -> anyhow::Result<Vec<Vec<usize>>> {
+    let mut graph = ResourceGraph::default();
+    let mut index: HashMap<ResourceNode, Vec<usize>> = HashMap::new();
+
+    for (plan_report, body) in plan_reports.iter().zip(bodies) {
+        graph.add_resource(&plan_report.prefix, &plan_report.virt_addr, body.as_deref())?;
+        graph.add_deferred(&plan_report.prefix, &plan_report.virt_addr, &plan_report.missing_outputs);
+    }
+
+    for (i, plan_report) in plan_reports.iter().enumerate() {
+        index
+            .entry(ResourceNode::new(&plan_report.prefix, &plan_report.virt_addr))
+            .or_default()
+            .push(i);
+    }
+
+    let mut dependencies: Vec<Vec<usize>> = vec![Vec::new(); plan_reports.len()];
+    for edge in &graph.edges {
+        let (Some(from), Some(to)) = (index.get(&edge.from), index.get(&edge.to)) else {
+            continue;
+        };
+
+        for &i in from {
+            for &j in to {
+                if i == j {
+                    continue;
+                }
+                if plan_reports[i].desired_none {
+                    dependencies[i].push(j);
+                } else {
+                    dependencies[j].push(i);
+                }
+            }
+        }
+    }
+
+    for dependencies in &mut dependencies {
+        dependencies.sort();
+        dependencies.dedup();
+    }
+
+    Ok(dependencies)
+}
+
+/// Sort `plan_reports` so that applying them one at a time applies each after the reports it depends on,
+/// as given by plan_report_dependencies(). Where the order is otherwise unconstrained, it is kept.
+pub fn sort_plan_reports_for_apply(plan_reports: &mut Vec<PlanReport>) -> anyhow::Result<()> {
+    let dependencies = plan_report_dependencies(plan_reports)?;
+
+    let mut placed = vec![false; plan_reports.len()];
+    let mut order = Vec::with_capacity(plan_reports.len());
+
+    while order.len() < plan_reports.len() {
+        let Some(next) = (0..plan_reports.len()).find(|&i| !placed[i] && dependencies[i].iter().all(|&j| placed[j])) else {
+            let cycle: Vec<String> = (0..plan_reports.len())
+                .filter(|&i| !placed[i])
+                .map(|i| plan_reports[i].prefix.join(&plan_reports[i].virt_addr).display().to_string())
+                .collect();
+            bail!("Dependency cycle between planned resources: {}", cycle.join(", "));
+        };
+
+        placed[next] = true;
+        order.push(next);
+    }
+
+    let mut reports: Vec<Option<PlanReport>> = plan_reports.drain(..).map(Some).collect();
+    plan_reports.extend(order.into_iter().filter_map(|i| reports[i].take()));
+
+    Ok(())
+}
+
+/// Sort `plan_reports` for destroying their resources, such that each resource is destroyed before
+/// the resources it reads outputs from or was unpacked from, by the out:// references in its file on disk.
+/// Where the order is otherwise unconstrained, deeper paths come first.
+pub fn sort_plan_reports_for_destroy(plan_reports: &mut [PlanReport]) -> anyhow::Result<()> {
+    let mut graph = ResourceGraph::default();
+    for plan_report in plan_reports.iter() {
+        let body = std::fs::read_to_string(plan_report.prefix.join(&plan_report.virt_addr)).ok();
+        graph.add_resource(&plan_report.prefix, &plan_report.virt_addr, body.as_deref())?;
+    }
+
+    sort_by_reverse_order(&graph, plan_reports)
+}
+
+fn sort_by_reverse_order(graph: &ResourceGraph, plan_reports: &mut [PlanReport]) -> anyhow::Result<()> {
+    let index: HashMap<ResourceNode, usize> = graph
+        .topological_order()?
+        .into_iter()
+        .enumerate()
+        .map(|(i, node)| (node, i))
+        .collect();
+
+    plan_reports.sort_by_key(|p| {
+        std::cmp::Reverse(
+            index
+                .get(&ResourceNode::new(&p.prefix, &p.virt_addr))
+                .copied()
+                .unwrap_or_default(),
+        )
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(addr: &str) -> ResourceNode {
+        ResourceNode::new(Path::new("main"), Path::new(addr))
+    }
+
+    fn reads(graph: &mut ResourceGraph, from: &str, to: &str) {
+        graph.add_edge(node(from), node(to), EdgeKind::ReadsOutput { key: String::from("id") });
+    }
+
+    #[test]
+    fn test_topological_order() {
+        let mut graph = ResourceGraph::default();
+        graph.add_node(node("a/b/c/unrelated.ron"));
+        graph.add_node(node("unrelated.ron"));
+        reads(&mut graph, "a/b/c/vpc.ron", "subnet.ron");
+        reads(&mut graph, "subnet.ron", "a/instance.ron");
+        reads(&mut graph, "a/b/c/vpc.ron", "a/instance.ron");
+
+        let order: Vec<PathBuf> = graph.topological_order().unwrap().into_iter().map(|n| n.addr).collect();
+        assert_eq!(
+            order,
+            vec![
+                PathBuf::from("unrelated.ron"),
+                PathBuf::from("a/b/c/unrelated.ron"),
+                PathBuf::from("a/b/c/vpc.ron"),
+                PathBuf::from("subnet.ron"),
+                PathBuf::from("a/instance.ron"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_cycle() {
+        let mut graph = ResourceGraph::default();
+        reads(&mut graph, "a.ron", "b.ron");
+        reads(&mut graph, "b.ron", "c.ron");
+        assert!(graph.find_cycle().is_none());
+
+        reads(&mut graph, "c.ron", "a.ron");
+        let cycle = graph.find_cycle().unwrap();
+        assert_eq!(cycle.first(), cycle.last());
+        assert_eq!(cycle.len(), 4);
+        assert!(graph.topological_order().is_err());
+    }
+
+    #[test]
+    fn test_plan_report_dependencies() {
+        let plan_report = |addr: &str, missing: &[&str]| PlanReport {
+            prefix: PathBuf::from("nonexistent-prefix"),
+            virt_addr: PathBuf::from(addr),
+            missing_outputs: missing
+                .iter()
+                .map(|m| ReadOutput {
+                    addr: PathBuf::from(m),
+                    key: String::from("id"),
+                })
+                .collect(),
+            ..Default::default()
+        };
+
+        let plan_reports = vec![
+            plan_report("vpc.ron", &[]),
+            plan_report("subnet.ron", &["vpc.ron"]),
+            plan_report("instance.ron", &["subnet.ron", "vpc.ron", "elsewhere.ron"]),
+            plan_report("unrelated.ron", &[]),
+        ];
+
+        assert_eq!(
+            plan_report_dependencies(&plan_reports).unwrap(),
+            vec![vec![], vec![0], vec![0, 1], vec![]]
+        );
+    }
+
+    #[test]
+    fn test_sort_for_apply() {
+        let plan_report = |addr: &str, missing: &[&str]| PlanReport {
+            prefix: PathBuf::from("nonexistent-prefix"),
+            virt_addr: PathBuf::from(addr),
+            missing_outputs: missing
+                .iter()
+                .map(|m| ReadOutput {
+                    addr: PathBuf::from(m),
+                    key: String::from("id"),
+                })
+                .collect(),
+            ..Default::default()
+        };
+
+        let mut plan_reports = vec![
+            plan_report("instance.ron", &["subnet.ron"]),
+            plan_report("unrelated.ron", &[]),
+            plan_report("subnet.ron", &["vpc.ron"]),
+            plan_report("vpc.ron", &[]),
+        ];
+
+        sort_plan_reports_for_apply(&mut plan_reports).unwrap();
+
+        let order: Vec<PathBuf> = plan_reports.into_iter().map(|p| p.virt_addr).collect();
+        assert_eq!(
+            order,
+            vec![
+                PathBuf::from("unrelated.ron"),
+                PathBuf::from("vpc.ron"),
+                PathBuf::from("subnet.ron"),
+                PathBuf::from("instance.ron"),
+            ]
+        );
+
+        let mut plan_reports = vec![plan_report("a.ron", &["b.ron"]), plan_report("b.ron", &["a.ron"])];
+        assert!(sort_plan_reports_for_apply(&mut plan_reports).is_err());
+    }
+
+    #[test]
+    fn test_plan_report_dependencies_for_deletions() {
+        let plan_report = |addr: &str| PlanReport {
+            prefix: PathBuf::from("nonexistent-prefix"),
+            virt_addr: PathBuf::from(addr),
+            desired_none: true,
+            ..Default::default()
+        };
+
+        let plan_reports = vec![
+            plan_report("vpc.ron"),
+            plan_report("subnet.ron"),
+            plan_report("unrelated.ron"),
+        ];
+        let bodies = vec![
+            Some(String::from("Vpc()")),
+            Some(String::from("Subnet(vpc_id: \"out://vpc.ron[vpc_id]\")")),
+            None,
+        ];
+
+        // The VPC is deleted after the subnet that reads its outputs.
+        assert_eq!(
+            plan_report_dependencies_with_bodies(&plan_reports, &bodies).unwrap(),
+            vec![vec![1], vec![], vec![]]
+        );
+    }
+
+    #[test]
+    fn test_sort_for_destroy() {
+        let mut graph = ResourceGraph::default();
+        reads(&mut graph, "vpc.ron", "subnet.ron");
+        reads(&mut graph, "subnet.ron", "instance.ron");
+        graph.add_node(node("unrelated.ron"));
+
+        let mut plan_reports: Vec<PlanReport> = ["vpc.ron", "unrelated.ron", "subnet.ron", "instance.ron"]
+            .iter()
+            .map(|addr| PlanReport {
+                prefix: PathBuf::from("main"),
+                virt_addr: PathBuf::from(addr),
+                ..Default::default()
+            })
+            .collect();
+
+        sort_by_reverse_order(&graph, &mut plan_reports).unwrap();
+
+        let order: Vec<PathBuf> = plan_reports.into_iter().map(|p| p.virt_addr).collect();
+        assert_eq!(
+            order,
+            vec![
+                PathBuf::from("instance.ron"),
+                PathBuf::from("subnet.ron"),
+                PathBuf::from("vpc.ron"),
+                PathBuf::from("unrelated.ron"),
+            ]
+        );
+    }
+}
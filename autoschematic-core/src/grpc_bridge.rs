@@ -119,6 +119,17 @@ impl GrpcConnector for GrpcConnectorServer {
                 op_definition: op.op_definition,
                 writes_outputs: op.writes_outputs,
                 friendly_message: op.friendly_message.unwrap_or_default(),
+                op_kind: proto::OpKind::from(op.op_kind).into(),
+                destructive: op.destructive,
+                diffs: op
+                    .diffs
+                    .into_iter()
+                    .map(|d| proto::FieldDiff {
+                        path: d.path,
+                        before: d.before.unwrap_or_default(),
+                        after: d.after.unwrap_or_default(),
+                    })
+                    .collect(),
             })
             .collect();
         Ok(Response::new(PlanResponse { ops: ops_proto }))
@@ -358,6 +369,30 @@ impl GrpcConnector for GrpcConnectorServer {
     }
 }
 
+impl From<connector::OpKind> for proto::OpKind {
+    fn from(value: connector::OpKind) -> Self {
+        match value {
+            connector::OpKind::Create => proto::OpKind::Create,
+            connector::OpKind::Update => proto::OpKind::Update,
+            connector::OpKind::Delete => proto::OpKind::Delete,
+            connector::OpKind::Replace => proto::OpKind::Replace,
+            connector::OpKind::Other => proto::OpKind::Other,
+        }
+    }
+}
+
+impl From<proto::OpKind> for connector::OpKind {
+    fn from(value: proto::OpKind) -> Self {
+        match value {
+            proto::OpKind::Create => connector::OpKind::Create,
+            proto::OpKind::Update => connector::OpKind::Update,
+            proto::OpKind::Delete => connector::OpKind::Delete,
+            proto::OpKind::Replace => connector::OpKind::Replace,
+            proto::OpKind::Other => connector::OpKind::Other,
+        }
+    }
+}
+
 /// Launch the gRPC server over a Unix-domain socket
 pub async fn launch_server<C: Connector>(name: &str, prefix: &Path, socket: &Path, outbox: ConnectorOutbox) -> Result<()> {
     let conn_impl = C::new(name, prefix, outbox).await.context("Failed to initialize connector")?;
@@ -466,6 +501,7 @@ impl Connector for GrpcConnectorClient {
             .ops
             .into_iter()
             .map(|o| connector::PlanResponseElement {
+                op_kind: o.op_kind().into(),
                 op_definition: o.op_definition,
                 writes_outputs: o.writes_outputs,
                 friendly_message: if o.friendly_message.is_empty() {
@@ -473,6 +509,16 @@ impl Connector for GrpcConnectorClient {
                 } else {
                     Some(o.friendly_message)
                 },
+                destructive: o.destructive,
+                diffs: o
+                    .diffs
+                    .into_iter()
+                    .map(|d| connector::FieldDiff {
+                        path: d.path,
+                        before: if d.before.is_empty() { None } else { Some(d.before) },
+                        after: if d.after.is_empty() { None } else { Some(d.after) },
+                    })
+                    .collect(),
             })
             .collect())
     }
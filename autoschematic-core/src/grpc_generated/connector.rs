@@ -1,5 +1,4 @@
 // This file is @generated by prost-build.
-/// / A generic empty message (was google.protobuf.Empty)
 #[derive(Clone, Copy, PartialEq, Eq, Hash, ::prost::Message)]
 pub struct Empty {}
 #[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
@@ -7,7 +6,7 @@ pub struct VersionResponse {
     #[prost(string, tag = "1")]
     pub version: ::prost::alloc::string::String,
 }
-/// / --- Filter ---
+/// / Connector::filter
 #[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
 pub struct FilterRequest {
     #[prost(string, tag = "1")]
@@ -18,7 +17,7 @@ pub struct FilterResponse {
     #[prost(uint32, tag = "1")]
     pub bitmask: u32,
 }
-/// / --- Listing ---
+/// / Connector::list
 #[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
 pub struct ListRequest {
     #[prost(string, tag = "1")]
@@ -29,7 +28,7 @@ pub struct ListResponse {
     #[prost(string, repeated, tag = "1")]
     pub addrs: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
 }
-/// / --- Get resource ---
+/// / Connector::get
 #[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
 pub struct GetRequest {
     #[prost(string, tag = "1")]
@@ -37,18 +36,20 @@ pub struct GetRequest {
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct GetResponse {
-    /// / false ⇒ no resource at `addr`; other fields ignored
+    /// / false => no resource at `addr`; other fields ignored
     #[prost(bool, tag = "1")]
     pub exists: bool,
     #[prost(bytes = "vec", tag = "2")]
     pub resource_definition: ::prost::alloc::vec::Vec<u8>,
-    #[prost(map = "string, string", tag = "3")]
+    #[prost(string, tag = "3")]
+    pub virt_addr: ::prost::alloc::string::String,
+    #[prost(map = "string, string", tag = "4")]
     pub outputs: ::std::collections::HashMap<
         ::prost::alloc::string::String,
         ::prost::alloc::string::String,
     >,
 }
-/// / --- Plan ---
+/// / Connector::plan
 #[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
 pub struct PlanRequest {
     #[prost(string, tag = "1")]
@@ -61,6 +62,17 @@ pub struct PlanRequest {
     pub desired: ::prost::alloc::vec::Vec<u8>,
 }
 #[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct FieldDiff {
+    #[prost(string, tag = "1")]
+    pub path: ::prost::alloc::string::String,
+    /// empty means “none”
+    #[prost(string, tag = "2")]
+    pub before: ::prost::alloc::string::String,
+    /// empty means “none”
+    #[prost(string, tag = "3")]
+    pub after: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct PlanResponseElement {
     #[prost(string, tag = "1")]
     pub op_definition: ::prost::alloc::string::String,
@@ -68,12 +80,19 @@ pub struct PlanResponseElement {
     pub writes_outputs: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
     #[prost(string, tag = "3")]
     pub friendly_message: ::prost::alloc::string::String,
+    #[prost(enumeration = "OpKind", tag = "4")]
+    pub op_kind: i32,
+    #[prost(bool, tag = "5")]
+    pub destructive: bool,
+    #[prost(message, repeated, tag = "6")]
+    pub diffs: ::prost::alloc::vec::Vec<FieldDiff>,
 }
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct PlanResponse {
     #[prost(message, repeated, tag = "1")]
     pub ops: ::prost::alloc::vec::Vec<PlanResponseElement>,
 }
+/// / Connector::op_exec
 #[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
 pub struct OpExecRequest {
     #[prost(string, tag = "1")]
@@ -91,6 +110,7 @@ pub struct OpExecResponse {
     #[prost(string, tag = "2")]
     pub friendly_message: ::prost::alloc::string::String,
 }
+/// / Connector::addr_phy_to_virt
 #[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
 pub struct AddrPhyToVirtRequest {
     #[prost(string, tag = "1")]
@@ -103,7 +123,7 @@ pub struct AddrPhyToVirtResponse {
     #[prost(string, tag = "2")]
     pub virt_addr: ::prost::alloc::string::String,
 }
-/// / --- Virt‐to‐phy mapping ---
+/// / Connector::addr_virt_to_phy
 #[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
 pub struct AddrVirtToPhyRequest {
     #[prost(string, tag = "1")]
@@ -145,13 +165,13 @@ pub mod addr_virt_to_phy_response {
         Null(super::Path),
     }
 }
-/// / --- Subpaths (parallelism hints) ---
+/// / Connector::subpaths
 #[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
 pub struct SubpathsResponse {
     #[prost(string, repeated, tag = "1")]
     pub subpaths: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
 }
-/// / --- Skeletons (templates) ---
+/// / Connector::get_skeletons
 #[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
 pub struct Skeleton {
     #[prost(string, tag = "1")]
@@ -164,7 +184,7 @@ pub struct GetSkeletonsResponse {
     #[prost(message, repeated, tag = "1")]
     pub skeletons: ::prost::alloc::vec::Vec<Skeleton>,
 }
-/// / --- Docstrings ---
+/// / Connector::get_docstring
 #[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
 pub struct StructIdent {
     #[prost(string, tag = "1")]
@@ -178,8 +198,15 @@ pub struct FieldIdent {
     pub name: ::prost::alloc::string::String,
 }
 #[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct EnumVariantIdent {
+    #[prost(string, tag = "1")]
+    pub parent: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub name: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
 pub struct DocIdent {
-    #[prost(oneof = "doc_ident::Ident", tags = "1, 2")]
+    #[prost(oneof = "doc_ident::Ident", tags = "1, 2, 3")]
     pub ident: ::core::option::Option<doc_ident::Ident>,
 }
 /// Nested message and enum types in `DocIdent`.
@@ -190,6 +217,8 @@ pub mod doc_ident {
         Struct(super::StructIdent),
         #[prost(message, tag = "2")]
         Field(super::FieldIdent),
+        #[prost(message, tag = "3")]
+        EnumVariant(super::EnumVariantIdent),
     }
 }
 #[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
@@ -204,9 +233,13 @@ pub struct GetDocResponse {
     #[prost(bool, tag = "1")]
     pub has_doc: bool,
     #[prost(string, tag = "2")]
+    pub r#type: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
     pub markdown: ::prost::alloc::string::String,
+    #[prost(string, repeated, tag = "4")]
+    pub fields: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
 }
-/// / --- Equality check ---
+/// / Connector::eq
 #[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
 pub struct EqRequest {
     #[prost(string, tag = "1")]
@@ -221,7 +254,7 @@ pub struct EqResponse {
     #[prost(bool, tag = "1")]
     pub equal: bool,
 }
-/// / --- Diagnostics ---
+/// / Connector::diag
 #[derive(Clone, Copy, PartialEq, Eq, Hash, ::prost::Message)]
 pub struct DiagnosticPosition {
     #[prost(uint32, tag = "1")]
@@ -257,7 +290,40 @@ pub struct DiagResponse {
     #[prost(message, repeated, tag = "1")]
     pub diagnostics: ::prost::alloc::vec::Vec<Diagnostic>,
 }
-/// / --- Unbundle bundles into resources ---
+/// / Connector::task_exec
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct TaskExecRequest {
+    #[prost(string, tag = "1")]
+    pub addr: ::prost::alloc::string::String,
+    #[prost(bytes = "vec", tag = "2")]
+    pub body: ::prost::alloc::vec::Vec<u8>,
+    #[prost(bytes = "vec", tag = "3")]
+    pub arg: ::prost::alloc::vec::Vec<u8>,
+    #[prost(bytes = "vec", tag = "4")]
+    pub state: ::prost::alloc::vec::Vec<u8>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TaskExecResponse {
+    #[prost(bytes = "vec", tag = "1")]
+    pub next_state: ::prost::alloc::vec::Vec<u8>,
+    #[prost(string, repeated, tag = "2")]
+    pub modified_files: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    #[prost(map = "string, string", tag = "3")]
+    pub outputs: ::std::collections::HashMap<
+        ::prost::alloc::string::String,
+        ::prost::alloc::string::String,
+    >,
+    #[prost(map = "string, string", tag = "4")]
+    pub secrets: ::std::collections::HashMap<
+        ::prost::alloc::string::String,
+        ::prost::alloc::string::String,
+    >,
+    #[prost(string, tag = "5")]
+    pub friendly_message: ::prost::alloc::string::String,
+    #[prost(uint64, tag = "6")]
+    pub delay_until: u64,
+}
+/// / Connector::unbundle
 #[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
 pub struct UnbundleRequest {
     #[prost(string, tag = "1")]
@@ -315,6 +381,41 @@ impl FilterResponseType {
         }
     }
 }
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum OpKind {
+    Other = 0,
+    Create = 1,
+    Update = 2,
+    Delete = 3,
+    Replace = 4,
+}
+impl OpKind {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::Other => "OTHER",
+            Self::Create => "CREATE",
+            Self::Update => "UPDATE",
+            Self::Delete => "DELETE",
+            Self::Replace => "REPLACE",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "OTHER" => Some(Self::Other),
+            "CREATE" => Some(Self::Create),
+            "UPDATE" => Some(Self::Update),
+            "DELETE" => Some(Self::Delete),
+            "REPLACE" => Some(Self::Replace),
+            _ => None,
+        }
+    }
+}
 /// Generated client implementations.
 pub mod connector_client {
     #![allow(
@@ -326,7 +427,6 @@ pub mod connector_client {
     )]
     use tonic::codegen::*;
     use tonic::codegen::http::Uri;
-    /// / --- The Connector gRPC service ---
     #[derive(Debug, Clone)]
     pub struct ConnectorClient<T> {
         inner: tonic::client::Grpc<T>,
@@ -698,6 +798,30 @@ pub mod connector_client {
             req.extensions_mut().insert(GrpcMethod::new("connector.Connector", "Diag"));
             self.inner.unary(req, path, codec).await
         }
+        pub async fn task_exec(
+            &mut self,
+            request: impl tonic::IntoRequest<super::TaskExecRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::TaskExecResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic_prost::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/connector.Connector/TaskExec",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("connector.Connector", "TaskExec"));
+            self.inner.unary(req, path, codec).await
+        }
         pub async fn unbundle(
             &mut self,
             request: impl tonic::IntoRequest<super::UnbundleRequest>,
@@ -805,6 +929,13 @@ pub mod connector_server {
             &self,
             request: tonic::Request<super::DiagRequest>,
         ) -> std::result::Result<tonic::Response<super::DiagResponse>, tonic::Status>;
+        async fn task_exec(
+            &self,
+            request: tonic::Request<super::TaskExecRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::TaskExecResponse>,
+            tonic::Status,
+        >;
         async fn unbundle(
             &self,
             request: tonic::Request<super::UnbundleRequest>,
@@ -813,7 +944,6 @@ pub mod connector_server {
             tonic::Status,
         >;
     }
-    /// / --- The Connector gRPC service ---
     #[derive(Debug)]
     pub struct ConnectorServer<T> {
         inner: Arc<T>,
@@ -1496,6 +1626,51 @@ pub mod connector_server {
                     };
                     Box::pin(fut)
                 }
+                "/connector.Connector/TaskExec" => {
+                    #[allow(non_camel_case_types)]
+                    struct TaskExecSvc<T: Connector>(pub Arc<T>);
+                    impl<
+                        T: Connector,
+                    > tonic::server::UnaryService<super::TaskExecRequest>
+                    for TaskExecSvc<T> {
+                        type Response = super::TaskExecResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::TaskExecRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Connector>::task_exec(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let method = TaskExecSvc(inner);
+                        let codec = tonic_prost::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 "/connector.Connector/Unbundle" => {
                     #[allow(non_camel_case_types)]
                     struct UnbundleSvc<T: Connector>(pub Arc<T>);
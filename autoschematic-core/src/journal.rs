@@ -0,0 +1,461 @@
+use std::{
+    collections::HashMap,
+    fs::{File, OpenOptions},
+    io::{BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{Context, bail};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    connector::OpExecResponse,
+    report::{PlanReport, SavedPlan},
+};
+
+/// The git blob hash of each file, or None where it did not exist.
+pub type FileHashes = HashMap<PathBuf, Option<String>>;
+
+/// An entry in an apply journal. Each entry is written as one line of JSON.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum JournalEntry {
+    /// The apply run started, with the plans it intends to apply and the files they were planned against.
+    Started {
+        plan_reports: Vec<PlanReport>,
+        #[serde(default)]
+        file_hashes: FileHashes,
+    },
+    /// A plan was added to the run after it started, such as a deferred plan that was planned again.
+    Planned {
+        plan_report: Box<PlanReport>,
+        #[serde(default)]
+        file_hashes: FileHashes,
+    },
+    /// `op_exec` is about to be called for op `op_index` of the plan for `prefix`/`virt_addr`.
+    OpStarted {
+        prefix: PathBuf,
+        virt_addr: PathBuf,
+        op_index: usize,
+        op_definition: String,
+    },
+    /// `op_exec` returned successfully, and its outputs were written to the output maps in `file_hashes`.
+    OpFinished {
+        prefix: PathBuf,
+        virt_addr: PathBuf,
+        op_index: usize,
+        response: OpExecResponse,
+        #[serde(default)]
+        file_hashes: FileHashes,
+    },
+    /// `op_exec` returned an error, so the op may be retried.
+    OpFailed {
+        prefix: PathBuf,
+        virt_addr: PathBuf,
+        op_index: usize,
+        error: String,
+    },
+    /// The user chose to skip an op that was interrupted, rather than execute it again.
+    OpSkipped {
+        prefix: PathBuf,
+        virt_addr: PathBuf,
+        op_index: usize,
+    },
+    /// Every op in the run was executed.
+    Completed,
+}
+
+/// The progress through a single plan, as recorded in a journal.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PlanProgress {
+    /// The number of ops, from the start of the plan, that finished or were skipped.
+    pub done_ops: usize,
+    /// Whether op `done_ops` was started but never finished or failed, such that it may or
+    /// may not have taken effect.
+    pub interrupted: bool,
+}
+
+/// An append-only record of the ops executed by an apply run, kept under `.autoschematic/journal/`,
+/// so that an interrupted run can be resumed without executing any op twice.
+/// A journal is removed once its run completes, and the directory is ignored by git.
+pub struct Journal {
+    path: PathBuf,
+    file: Mutex<File>,
+}
+
+impl Journal {
+    pub fn dir() -> PathBuf {
+        PathBuf::from(".autoschematic").join("journal")
+    }
+
+    /// Start a new journal for an apply run of `plan_reports`.
+    /// Refuses while the journal of an earlier run is incomplete; see `check_none_incomplete`.
+    pub fn create(plan_reports: &[PlanReport]) -> anyhow::Result<Self> {
+        Self::check_none_incomplete()?;
+
+        let mut file_hashes = FileHashes::new();
+        for plan_report in plan_reports {
+            file_hashes.extend(plan_file_hashes(plan_report)?);
+        }
+
+        let dir = Self::dir();
+        std::fs::create_dir_all(&dir)?;
+
+        let gitignore = dir.join(".gitignore");
+        if !gitignore.exists() {
+            std::fs::write(&gitignore, "*\n").context(format!("Writing {}", gitignore.display()))?;
+        }
+
+        let millis = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis();
+        let path = dir.join(format!("{millis}.ndjson"));
+
+        let journal = Self::open(&path)?;
+        journal.append(&JournalEntry::Started {
+            plan_reports: plan_reports.to_vec(),
+            file_hashes,
+        })?;
+        Ok(journal)
+    }
+
+    /// Open an existing journal to append to it.
+    pub fn open(path: &Path) -> anyhow::Result<Self> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .context(format!("Opening journal {}", path.display()))?;
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            file: Mutex::new(file),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append `entry` and sync it to disk before returning.
+    pub fn append(&self, entry: &JournalEntry) -> anyhow::Result<()> {
+        let mut line = serde_json::to_string(entry)?;
+        line.push('\n');
+
+        let mut file = self.file.lock().unwrap();
+        file.write_all(line.as_bytes())?;
+        file.sync_data()?;
+        Ok(())
+    }
+
+    /// Record that every op in the run was executed, and remove the journal, as there's nothing left to resume.
+    /// The Completed entry is written first, so that the run isn't resumed should the removal fail.
+    pub fn complete(&self) -> anyhow::Result<()> {
+        self.append(&JournalEntry::Completed)?;
+        std::fs::remove_file(&self.path).context(format!("Removing journal {}", self.path.display()))?;
+        Ok(())
+    }
+
+    /// Read every entry in the journal at `path`. A truncated final line, as left by a crash
+    /// part-way through a write, is ignored.
+    pub fn read(path: &Path) -> anyhow::Result<Vec<JournalEntry>> {
+        let reader = BufReader::new(File::open(path)?);
+
+        let lines: Vec<String> = reader.lines().collect::<Result<_, _>>()?;
+
+        let mut entries = Vec::new();
+        for (i, line) in lines.iter().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str(line) {
+                Ok(entry) => entries.push(entry),
+                Err(_) if i == lines.len() - 1 => break,
+                Err(e) => return Err(e).context(format!("Reading journal {} line {}", path.display(), i + 1)),
+            }
+        }
+
+        Ok(entries)
+    }
+
+    /// Find the journals of every run that never completed, oldest first.
+    pub fn incomplete() -> anyhow::Result<Vec<(PathBuf, Vec<JournalEntry>)>> {
+        let dir = Self::dir();
+        if !dir.is_dir() {
+            return Ok(Vec::new());
+        }
+
+        let mut journals: Vec<(u128, PathBuf)> = Vec::new();
+        for entry in std::fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.extension().is_none_or(|e| e != "ndjson") {
+                continue;
+            }
+            let Some(millis) = path.file_stem().and_then(|s| s.to_str()).and_then(|s| s.parse().ok()) else {
+                continue;
+            };
+            journals.push((millis, path));
+        }
+        journals.sort();
+
+        let mut incomplete = Vec::new();
+        for (_, path) in journals {
+            let entries = Self::read(&path)?;
+            if !entries.iter().any(|e| matches!(e, JournalEntry::Completed)) {
+                incomplete.push((path, entries));
+            }
+        }
+
+        Ok(incomplete)
+    }
+
+    /// Find the most recent journal that belongs to a run that never completed.
+    pub fn latest_incomplete() -> anyhow::Result<Option<(PathBuf, Vec<JournalEntry>)>> {
+        Ok(Self::incomplete()?.pop())
+    }
+
+    /// Refuse to go on while a run is incomplete. Applying its plans again in a new run,
+    /// and later resuming the old one, would execute the same ops twice.
+    pub fn check_none_incomplete() -> anyhow::Result<()> {
+        if let Some((path, _)) = Self::latest_incomplete()? {
+            bail!(
+                "An earlier apply was interrupted, and its journal {} is incomplete.\nContinue it with apply --resume, or abandon it with apply --discard-journal.",
+                path.display()
+            );
+        }
+        Ok(())
+    }
+
+    /// Remove the journals of every run that never completed, such that they can no longer be resumed.
+    /// Returns the paths removed.
+    pub fn discard_incomplete() -> anyhow::Result<Vec<PathBuf>> {
+        let mut discarded = Vec::new();
+        for (path, _) in Self::incomplete()? {
+            std::fs::remove_file(&path).context(format!("Removing journal {}", path.display()))?;
+            discarded.push(path);
+        }
+        Ok(discarded)
+    }
+}
+
+/// Hash the resource file and the output maps that `plan_report` was planned against.
+pub fn plan_file_hashes(plan_report: &PlanReport) -> anyhow::Result<FileHashes> {
+    let mut tracked = SavedPlan::default();
+    tracked.track_file(&plan_report.prefix.join(&plan_report.virt_addr))?;
+    tracked.track_output_maps(plan_report)?;
+    Ok(tracked.file_hashes.into_iter().chain(tracked.output_map_hashes).collect())
+}
+
+/// Return each file recorded in `entries` whose contents no longer match what the run
+/// planned against or last wrote to it. A run can't be safely resumed once these have changed.
+pub fn changed_files(entries: &[JournalEntry]) -> anyhow::Result<Vec<PathBuf>> {
+    let mut expected = FileHashes::new();
+
+    for entry in entries {
+        match entry {
+            JournalEntry::Started { file_hashes, .. } | JournalEntry::Planned { file_hashes, .. } => {
+                for (path, hash) in file_hashes {
+                    expected.entry(path.clone()).or_insert_with(|| hash.clone());
+                }
+            }
+            JournalEntry::OpFinished { file_hashes, .. } => {
+                expected.extend(file_hashes.clone());
+            }
+            _ => {}
+        }
+    }
+
+    let mut changed = Vec::new();
+    for (path, hash) in expected {
+        if SavedPlan::hash_file(&path)? != hash {
+            changed.push(path);
+        }
+    }
+
+    changed.sort();
+    Ok(changed)
+}
+
+/// Replay `entries` to find the plans of the run, along with the progress made through each.
+pub fn replay(entries: &[JournalEntry]) -> Vec<(PlanReport, PlanProgress)> {
+    let mut plan_reports = Vec::new();
+    let mut progress: HashMap<(PathBuf, PathBuf), PlanProgress> = HashMap::new();
+
+    for entry in entries {
+        match entry {
+            JournalEntry::Started {
+                plan_reports: started, ..
+            } => {
+                plan_reports = started.clone();
+            }
+            JournalEntry::Planned { plan_report, .. } => {
+                plan_reports.push(*plan_report.clone());
+            }
+            JournalEntry::OpStarted {
+                prefix,
+                virt_addr,
+                op_index,
+                ..
+            } => {
+                let p = progress.entry((prefix.clone(), virt_addr.clone())).or_default();
+                if *op_index == p.done_ops {
+                    p.interrupted = true;
+                }
+            }
+            JournalEntry::OpFinished {
+                prefix,
+                virt_addr,
+                op_index,
+                ..
+            }
+            | JournalEntry::OpSkipped {
+                prefix,
+                virt_addr,
+                op_index,
+            } => {
+                let p = progress.entry((prefix.clone(), virt_addr.clone())).or_default();
+                if *op_index == p.done_ops {
+                    p.done_ops += 1;
+                    p.interrupted = false;
+                }
+            }
+            JournalEntry::OpFailed {
+                prefix,
+                virt_addr,
+                op_index,
+                ..
+            } => {
+                let p = progress.entry((prefix.clone(), virt_addr.clone())).or_default();
+                if *op_index == p.done_ops {
+                    p.interrupted = false;
+                }
+            }
+            JournalEntry::Completed => {}
+        }
+    }
+
+    plan_reports
+        .into_iter()
+        .map(|plan_report| {
+            let p = progress
+                .get(&(plan_report.prefix.clone(), plan_report.virt_addr.clone()))
+                .cloned()
+                .unwrap_or_default();
+            (plan_report, p)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_replay() {
+        let plan_report = |addr: &str| PlanReport {
+            prefix: PathBuf::from("main"),
+            virt_addr: PathBuf::from(addr),
+            ..Default::default()
+        };
+        let op = |event: &str, addr: &str, op_index: usize| {
+            let (prefix, virt_addr) = (PathBuf::from("main"), PathBuf::from(addr));
+            match event {
+                "started" => JournalEntry::OpStarted {
+                    prefix,
+                    virt_addr,
+                    op_index,
+                    op_definition: String::new(),
+                },
+                "finished" => JournalEntry::OpFinished {
+                    prefix,
+                    virt_addr,
+                    op_index,
+                    response: OpExecResponse {
+                        outputs: None,
+                        friendly_message: None,
+                    },
+                    file_hashes: FileHashes::new(),
+                },
+                _ => JournalEntry::OpFailed {
+                    prefix,
+                    virt_addr,
+                    op_index,
+                    error: String::new(),
+                },
+            }
+        };
+
+        let entries = vec![
+            JournalEntry::Started {
+                plan_reports: vec![plan_report("a.ron"), plan_report("b.ron"), plan_report("c.ron")],
+                file_hashes: FileHashes::new(),
+            },
+            op("started", "a.ron", 0),
+            op("finished", "a.ron", 0),
+            op("started", "a.ron", 1),
+            op("finished", "a.ron", 1),
+            op("started", "b.ron", 0),
+            op("finished", "b.ron", 0),
+            op("started", "b.ron", 1),
+            op("started", "c.ron", 0),
+            op("failed", "c.ron", 0),
+        ];
+
+        let progress: Vec<PlanProgress> = replay(&entries).into_iter().map(|(_, p)| p).collect();
+        assert_eq!(
+            progress,
+            vec![
+                PlanProgress {
+                    done_ops: 2,
+                    interrupted: false
+                },
+                PlanProgress {
+                    done_ops: 1,
+                    interrupted: true
+                },
+                PlanProgress {
+                    done_ops: 0,
+                    interrupted: false
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_changed_files() {
+        let dir = std::env::temp_dir().join(format!("autoschematic-journal-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let (resource, output_map) = (dir.join("a.ron"), dir.join("a.ron.out.json"));
+
+        std::fs::write(&resource, "planned").unwrap();
+        let planned = SavedPlan::hash_file(&resource).unwrap();
+        std::fs::write(&output_map, "written").unwrap();
+        let written = SavedPlan::hash_file(&output_map).unwrap();
+
+        let entries = vec![
+            JournalEntry::Started {
+                plan_reports: Vec::new(),
+                file_hashes: FileHashes::from([(resource.clone(), planned), (output_map.clone(), None)]),
+            },
+            JournalEntry::OpFinished {
+                prefix: PathBuf::from("main"),
+                virt_addr: PathBuf::from("a.ron"),
+                op_index: 0,
+                response: OpExecResponse {
+                    outputs: None,
+                    friendly_message: None,
+                },
+                file_hashes: FileHashes::from([(output_map.clone(), written)]),
+            },
+        ];
+
+        // The run wrote the output map itself, so it's expected to differ from when the run started.
+        assert!(changed_files(&entries).unwrap().is_empty());
+
+        std::fs::write(&resource, "edited").unwrap();
+        std::fs::remove_file(&output_map).unwrap();
+        assert_eq!(changed_files(&entries).unwrap(), vec![resource.clone(), output_map.clone()]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -2,6 +2,7 @@ pub mod aux_task;
 pub mod bundle;
 pub mod config;
 pub mod config_bundle;
+pub mod config_policy;
 pub mod config_rbac;
 pub mod connector;
 pub mod connector_cache;
@@ -13,10 +14,14 @@ pub mod flock;
 pub mod fuzz;
 pub mod git_util;
 pub mod glob;
+pub mod graph;
 pub mod grpc_bridge;
+pub mod journal;
 pub mod keystore;
+pub mod lockfile;
 pub mod macros;
 pub mod manifest;
+pub mod protect;
 pub mod report;
 pub mod ron;
 pub mod secret;
@@ -1,84 +1,169 @@
-use std::{collections::HashMap, path::PathBuf};
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::BufReader,
+    path::{Path, PathBuf},
+};
 
-use anyhow::bail;
-use ron;
+use anyhow::{Context, bail};
+use ron::ser::PrettyConfig;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
 use crate::{
-    // binary_cache::BinaryCache,
-    connector::r#type::ConnectorType,
-    error::{self, AutoschematicErrorType},
-    manifest::ConnectorManifest,
-    util,
+    config::Spec,
+    util::{RON, repo_root},
 };
 
+/// autoschematic.lock.ron, as written by `autoschematic install`.
+/// Pins the exact build of each connector's executable, so that every
+/// checkout of the repository runs the same connectors.
 #[derive(Debug, Default, Deserialize, Serialize)]
 pub struct AutoschematicLockfile {
-    pub entries: HashMap<PathBuf, LockEntry>,
+    /// Keyed by {prefix}/{connector shortname}.
+    pub entries: BTreeMap<PathBuf, LockEntry>,
 }
 
-#[derive(Debug, Default, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
 pub struct LockEntry {
-    pub owner: String,
-    pub repo: String,
-    pub version: String,
-    pub manifest: ConnectorManifest,
+    /// The exact version installed, where the package manager reports one.
+    #[serde(default)]
+    pub version: Option<String>,
+    /// The executable that was hashed.
+    pub executable: PathBuf,
+    /// The hex-encoded sha256 of the executable.
+    pub sha256: String,
 }
 
-pub async fn load_lockfile() -> anyhow::Result<AutoschematicLockfile> {
-    let repo_root = util::repo_root()?;
-    let lockfile_path = repo_root.join("autoschematic.lock.ron");
-    if !lockfile_path.is_file() {
-        return Ok(AutoschematicLockfile::default());
-    }
-    let lockfile_s = tokio::fs::read_to_string(&lockfile_path).await?;
+pub fn lockfile_key(prefix: &Path, shortname: &str) -> PathBuf {
+    prefix.join(shortname)
+}
 
-    let lockfile: AutoschematicLockfile = match ron::from_str(&lockfile_s) {
-        Ok(it) => it,
-        Err(err) => {
-            return Err(error::AutoschematicError {
-                kind: AutoschematicErrorType::InternalError(anyhow::anyhow!("Bad lockfile: {}", err)),
+/// The executable that a connector with this spec runs, if it can be pinned by hash.
+/// Specs that run an interpreter or `cargo run` over a source tree have no such executable.
+pub fn spec_executable(spec: &Spec) -> anyhow::Result<Option<PathBuf>> {
+    match spec {
+        Spec::Binary { path, .. } => {
+            if path.is_file() {
+                Ok(Some(path.clone()))
+            } else {
+                Ok(Some(which::which(path).context(format!("{}: not found", path.display()))?))
             }
-            .into());
         }
-    };
-    Ok(lockfile)
+        Spec::Cargo { .. } => Ok(Some(spec.command()?.binary)),
+        Spec::Pip { binary: Some(_), .. } => Ok(Some(spec.command()?.binary)),
+        _ => Ok(None),
+    }
 }
 
-pub async fn resolve_lock_entry(
-    lockfile: &AutoschematicLockfile,
-    connector_type: &ConnectorType,
-    binary_cache: &BinaryCache,
-) -> Result<Option<ConnectorType>, anyhow::Error> {
-    match &connector_type {
-        ConnectorType::LockFile(path, short_name) => {
-            let Some(lock_entry) = lockfile.entries.get(path) else {
-                return Err(error::AutoschematicError {
-                    kind: AutoschematicErrorType::InternalError(anyhow::anyhow!(
-                        "Lock entry {} not found",
-                        path.to_string_lossy()
-                    )),
-                }
-                .into());
-            };
-            match lock_entry.manifest.r#type.as_str() {
-                "binary-tarpc" => {
-                    binary_cache
-                        .fetch_connector_release(
-                            &lock_entry.owner,
-                            &lock_entry.repo,
-                            &lock_entry.version,
-                            &lock_entry.manifest,
-                            &util::short_target(),
-                        )
-                        .await?;
-                    // TODO pick it up back here
-                    Ok(None)
-                }
-                "python" => Ok(None),
-                other => bail!("Connector Manifest has unrecognized type {}", other),
-            }
+pub fn sha256_file(path: &Path) -> anyhow::Result<String> {
+    let mut file = BufReader::new(File::open(path).context(format!("Opening {}", path.display()))?);
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)?;
+    Ok(hex::encode(hasher.finalize()))
+}
+
+impl LockEntry {
+    /// Hash the executable for `spec`, or None if it has none that can be pinned.
+    pub fn for_spec(spec: &Spec, version: Option<String>) -> anyhow::Result<Option<Self>> {
+        let Some(executable) = spec_executable(spec)? else {
+            return Ok(None);
+        };
+
+        Ok(Some(LockEntry {
+            version,
+            sha256: sha256_file(&executable)?,
+            executable,
+        }))
+    }
+
+    /// Check that `executable` is the build recorded in this entry.
+    pub fn verify(&self, executable: &Path) -> anyhow::Result<()> {
+        let sha256 = sha256_file(executable)?;
+        if sha256 != self.sha256 {
+            bail!(
+                "{} has sha256 {}, but autoschematic.lock.ron pins {}{}. Install the pinned build, or run `autoschematic install --update-lock` to re-pin it.",
+                executable.display(),
+                sha256,
+                self.sha256,
+                self.version.as_ref().map(|v| format!(" (version {v})")).unwrap_or_default()
+            );
+        }
+        Ok(())
+    }
+}
+
+impl AutoschematicLockfile {
+    pub fn path() -> anyhow::Result<PathBuf> {
+        Ok(repo_root()?.join("autoschematic.lock.ron"))
+    }
+
+    /// Load autoschematic.lock.ron from the repository root, or None if the repository has no lockfile.
+    pub fn load() -> anyhow::Result<Option<Self>> {
+        let Ok(lockfile_path) = Self::path() else {
+            return Ok(None);
+        };
+
+        if !lockfile_path.is_file() {
+            return Ok(None);
         }
-        _ => Ok(Some(connector_type.clone())),
+
+        let lockfile_body = std::fs::read_to_string(&lockfile_path).context("Reading autoschematic.lock.ron")?;
+        let lockfile = RON.from_str(&lockfile_body).context("Parsing autoschematic.lock.ron")?;
+
+        Ok(Some(lockfile))
+    }
+
+    pub fn write(&self) -> anyhow::Result<PathBuf> {
+        let lockfile_path = Self::path()?;
+        std::fs::write(&lockfile_path, RON.to_string_pretty(self, PrettyConfig::default())?)?;
+        Ok(lockfile_path)
+    }
+
+    /// Check the executable for `spec` against its lock entry, if there is one.
+    pub fn verify(&self, prefix: &Path, shortname: &str, spec: &Spec) -> anyhow::Result<()> {
+        let Some(lock_entry) = self.entries.get(&lockfile_key(prefix, shortname)) else {
+            return Ok(());
+        };
+
+        let Some(executable) = spec_executable(spec)? else {
+            return Ok(());
+        };
+
+        lock_entry
+            .verify(&executable)
+            .context(format!("Connector {} in prefix {}", shortname, prefix.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verify() {
+        let path = std::env::temp_dir().join(format!("autoschematic-lockfile-test-{}", std::process::id()));
+        std::fs::write(&path, b"connector build 1").unwrap();
+
+        let spec = Spec::Binary {
+            path: path.clone(),
+            protocol: Default::default(),
+        };
+
+        let lock_entry = LockEntry::for_spec(&spec, Some(String::from("1.0.0"))).unwrap().unwrap();
+        assert_eq!(lock_entry.executable, path);
+        assert!(lock_entry.verify(&path).is_ok());
+
+        let mut lockfile = AutoschematicLockfile::default();
+        lockfile.entries.insert(lockfile_key(Path::new("main"), "binary"), lock_entry);
+
+        assert!(lockfile.verify(Path::new("main"), "binary", &spec).is_ok());
+        // Connectors without an entry aren't pinned.
+        assert!(lockfile.verify(Path::new("main"), "other", &spec).is_ok());
+
+        std::fs::write(&path, b"connector build 2").unwrap();
+        assert!(lockfile.verify(Path::new("main"), "binary", &spec).is_err());
+
+        std::fs::remove_file(&path).unwrap();
     }
 }
@@ -28,6 +28,7 @@ macro_rules! connector_op {
             op_definition: ConnectorOp::to_string(&$op)?,
             writes_outputs: Vec::new(),
             friendly_message: Some($message),
+            ..Default::default()
         }
     }};
     ($op:expr, $outputs:expr, $message:expr) => {{
@@ -35,6 +36,7 @@ macro_rules! connector_op {
             op_definition: ConnectorOp::to_string(&$op)?,
             writes_outputs: $outputs,
             friendly_message: Some($message),
+            ..Default::default()
         }
     }};
 }
@@ -0,0 +1,178 @@
+use std::path::Path;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::{
+    config::{AutoschematicConfig, Connector},
+    connector::{OpKind, PlanResponseElement},
+    glob::addr_matches_glob,
+    report::PlanReport,
+};
+
+lazy_static! {
+    static ref DESTRUCTIVE_OP_RE: Regex =
+        Regex::new(r"(?i)\b(delet|destroy|drop|replac|recreat|remov|terminat|purg|truncat|wip)").unwrap();
+}
+
+/// Whether `addr` in `prefix` is matched by a `protect` glob on the prefix, or on `connector_def`.
+pub fn is_protected(config: &AutoschematicConfig, prefix: &Path, connector_def: Option<&Connector>, addr: &Path) -> bool {
+    let prefix_patterns = prefix
+        .to_str()
+        .and_then(|prefix_name| config.prefixes.get(prefix_name))
+        .map(|prefix_def| prefix_def.protect.as_slice())
+        .unwrap_or_default();
+
+    let connector_patterns = connector_def.map(|c| c.protect.as_slice()).unwrap_or_default();
+
+    prefix_patterns
+        .iter()
+        .chain(connector_patterns)
+        .any(|pattern| addr_matches_glob(addr, Path::new(pattern)))
+}
+
+/// Whether `op` deletes or replaces its resource. This uses the op's `op_kind` and `destructive` flag
+/// where the connector sets them, and falls back to judging by its description for `OpKind::Other`.
+pub fn op_is_destructive(op: &PlanResponseElement) -> bool {
+    if op.op_kind != OpKind::Other || op.destructive {
+        return op.is_destructive();
+    }
+
+    DESTRUCTIVE_OP_RE.is_match(op.friendly_message.as_deref().unwrap_or(&op.op_definition))
+}
+
+/// Whether `plan_report` would delete or replace its resource. A non-empty plan with `desired = None`
+/// is treated as a delete, whatever its ops say.
+pub fn plan_is_destructive(plan_report: &PlanReport) -> bool {
+    if plan_report.connector_ops.is_empty() {
+        return false;
+    }
+
+    plan_report.desired_none || plan_report.connector_ops.iter().any(op_is_destructive)
+}
+
+/// Set `plan_report.protected` if it would delete or replace a resource matched by a `protect` rule,
+/// by either its virtual or physical address.
+pub fn flag_protected(config: &AutoschematicConfig, plan_report: &mut PlanReport) {
+    let connector_def = plan_report.connector_def.as_ref();
+
+    let addr_protected = is_protected(config, &plan_report.prefix, connector_def, &plan_report.virt_addr)
+        || plan_report
+            .phy_addr
+            .as_ref()
+            .is_some_and(|phy_addr| is_protected(config, &plan_report.prefix, connector_def, phy_addr));
+
+    plan_report.protected = addr_protected && plan_is_destructive(plan_report);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::util::RON;
+
+    #[test]
+    fn test_flag_protected() {
+        let config: AutoschematicConfig = RON
+            .from_str(
+                r#"AutoschematicConfig(
+                prefixes: {
+                    "main": Prefix(
+                        connectors: [
+                            Connector(
+                                shortname: "aws",
+                                spec: Binary(path: "aws-connector"),
+                                protect: ["aws/s3/**/prod-*.ron"],
+                            ),
+                        ],
+                        protect: ["aws/rds"],
+                    ),
+                },
+            )"#,
+            )
+            .unwrap();
+
+        let op = |message: &str| PlanResponseElement {
+            op_definition: String::new(),
+            writes_outputs: Vec::new(),
+            friendly_message: Some(message.to_string()),
+            ..Default::default()
+        };
+
+        let kind_op = |message: &str, op_kind: OpKind| PlanResponseElement { op_kind, ..op(message) };
+
+        let plan_report = |addr: &str, ops: Vec<PlanResponseElement>, desired_none: bool| PlanReport {
+            prefix: PathBuf::from("main"),
+            connector_def: config.prefixes["main"].connectors.first().cloned(),
+            virt_addr: PathBuf::from(addr),
+            connector_ops: ops,
+            desired_none,
+            ..Default::default()
+        };
+
+        let cases = [
+            (
+                plan_report("aws/rds/db/main.ron", vec![op("Delete DB instance main")], false),
+                true,
+            ),
+            (
+                plan_report("aws/rds/db/main.ron", vec![op("Modify DB instance main")], false),
+                false,
+            ),
+            (
+                plan_report(
+                    "aws/rds/db/main.ron",
+                    vec![kind_op("Modify DB instance main", OpKind::Update)],
+                    false,
+                ),
+                false,
+            ),
+            (
+                plan_report("aws/rds/db/main.ron", vec![op("Remove DB instance main")], true),
+                true,
+            ),
+            (plan_report("aws/rds/db/main.ron", Vec::new(), true), false),
+            (
+                plan_report(
+                    "aws/rds/db/main.ron",
+                    vec![kind_op("Modify DB instance main", OpKind::Replace)],
+                    false,
+                ),
+                true,
+            ),
+            (
+                plan_report(
+                    "aws/rds/db/main.ron",
+                    vec![kind_op("Delete tag on main", OpKind::Update)],
+                    false,
+                ),
+                false,
+            ),
+            (
+                plan_report("aws/s3/us-east-1/prod-logs.ron", vec![op("Replace bucket")], false),
+                true,
+            ),
+            (
+                plan_report("aws/s3/us-east-1/staging-logs.ron", vec![op("Delete bucket")], false),
+                false,
+            ),
+        ];
+
+        for (message, expected) in [
+            ("Terminate instance i-0123", true),
+            ("REMOVE rule 10 from security group", true),
+            ("Drop table users", true),
+            ("Purge queue jobs", true),
+            ("Modify instance type", false),
+            ("Add tag Name", false),
+        ] {
+            assert_eq!(op_is_destructive(&op(message)), expected, "{message}");
+        }
+
+        for (mut plan_report, expected) in cases {
+            flag_protected(&config, &mut plan_report);
+            assert_eq!(plan_report.protected, expected, "{}", plan_report.virt_addr.display());
+        }
+    }
+}
@@ -1,14 +1,14 @@
 use std::{
-    collections::{HashMap, HashSet},
-    path::PathBuf,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    path::{Path, PathBuf},
 };
 
 use crate::{
     bundle::{BundleMapFile, UnbundleResponseElement},
     config::{self, Spec},
-    connector::{OpExecResponse, PlanResponseElement},
+    connector::{OpExecResponse, OutputMapFile, PlanResponseElement},
     error::ErrorMessage,
-    template::ReadOutput,
+    template::{ReadOutput, get_read_outputs},
 };
 use anyhow::bail;
 use serde::{Deserialize, Serialize};
@@ -30,6 +30,16 @@ pub struct PlanReport {
     // outputs to compute Connector::addr_virt_to_phy(). Should we?
     pub missing_outputs: Vec<ReadOutput>,
     pub error: Option<ErrorMessage>,
+    /// Whether this plan was made with `desired = None`, that is, to delete the resource.
+    #[serde(default)]
+    pub desired_none: bool,
+    /// Whether this plan was made with `current = None`, that is, to create the resource.
+    #[serde(default)]
+    pub current_none: bool,
+    /// Whether this plan would delete or replace a resource matched by a `protect` rule in autoschematic.ron.
+    /// See `protect::flag_protected`.
+    #[serde(default)]
+    pub protected: bool,
 }
 
 #[derive(Serialize, Deserialize, Clone, Default)]
@@ -40,6 +50,188 @@ pub struct PlanReportSet {
     pub deferred_count: usize,
     pub object_count: usize,
     pub deferred_pending_outputs: HashSet<ReadOutput>,
+    #[serde(default)]
+    pub summary: PlanSummary,
+}
+
+/// Counts of the resources that a plan would change, by how it would change them.
+#[derive(Serialize, Deserialize, Clone, Copy, Default, Debug, PartialEq)]
+pub struct PlanChangeCounts {
+    /// Resources with no current state.
+    pub created: usize,
+    /// Resources with both a current state and a desired file.
+    pub modified: usize,
+    /// Resources with no desired file.
+    pub deleted: usize,
+    /// Resources that couldn't be planned, as they read outputs that don't exist yet.
+    pub deferred: usize,
+    /// Resources whose plan failed.
+    pub failed: usize,
+    /// The total number of ops across all resources.
+    pub ops: usize,
+}
+
+impl PlanChangeCounts {
+    fn add(&mut self, plan_report: &PlanReport) {
+        if !plan_report.missing_outputs.is_empty() {
+            self.deferred += 1;
+        } else if plan_report.error.is_some() {
+            self.failed += 1;
+        } else if !plan_report.connector_ops.is_empty() {
+            if plan_report.desired_none {
+                self.deleted += 1;
+            } else if plan_report.current_none {
+                self.created += 1;
+            } else {
+                self.modified += 1;
+            }
+            self.ops += plan_report.connector_ops.len();
+        }
+    }
+}
+
+/// An overview of a plan, with its change counts by prefix and connector.
+#[derive(Serialize, Deserialize, Clone, Default, Debug)]
+pub struct PlanSummary {
+    /// Change counts by prefix, then by connector shortname.
+    pub counts: BTreeMap<String, BTreeMap<String, PlanChangeCounts>>,
+    pub total: PlanChangeCounts,
+    /// The outputs that deferred resources are waiting on.
+    pub missing_outputs: BTreeSet<String>,
+}
+
+impl PlanSummary {
+    /// Count `plan_report`, including deferred and failed plans.
+    pub fn add(&mut self, plan_report: &PlanReport) {
+        let connector = plan_report
+            .connector_def
+            .as_ref()
+            .map(|c| c.shortname.clone())
+            .unwrap_or_default();
+
+        self.counts
+            .entry(plan_report.prefix.to_string_lossy().to_string())
+            .or_default()
+            .entry(connector)
+            .or_default()
+            .add(plan_report);
+        self.total.add(plan_report);
+
+        self.missing_outputs
+            .extend(plan_report.missing_outputs.iter().map(|o| o.into_string()));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.total == PlanChangeCounts::default()
+    }
+}
+
+/// A PlanReportSet as written to disk by `autoschematic plan --out`, along with
+/// a snapshot of the state it was planned against. `autoschematic apply --plan-file`
+/// uses the snapshot to refuse to execute a plan that has since gone stale.
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct SavedPlan {
+    pub plan_report_set: PlanReportSet,
+    /// The git blob hash of each changed file as it was planned,
+    /// or None if the file was deleted.
+    pub file_hashes: HashMap<PathBuf, Option<String>>,
+    /// The git blob hash of each output map file read by the plan,
+    /// or None if it did not exist.
+    pub output_map_hashes: HashMap<PathBuf, Option<String>>,
+    /// The result of Connector::version() at plan time, by prefix and then connector shortname.
+    pub connector_versions: HashMap<String, HashMap<String, String>>,
+}
+
+impl SavedPlan {
+    /// Hash the file at `path` as a git blob, as `git hash-object` would.
+    pub fn hash_file(path: &Path) -> anyhow::Result<Option<String>> {
+        if !path.is_file() {
+            return Ok(None);
+        }
+
+        Ok(Some(git2::Oid::hash_file(git2::ObjectType::Blob, path)?.to_string()))
+    }
+
+    pub fn track_file(&mut self, path: &Path) -> anyhow::Result<()> {
+        self.file_hashes.insert(path.to_path_buf(), Self::hash_file(path)?);
+        Ok(())
+    }
+
+    /// Record the contents that the file at `path` was planned against, or None if it was deleted.
+    /// Unlike track_file, this doesn't read the working tree, so it also holds when planning a git ref range.
+    pub fn track_contents(&mut self, path: &Path, contents: Option<&[u8]>) -> anyhow::Result<()> {
+        let hash = match contents {
+            Some(contents) => Some(git2::Oid::hash_object(git2::ObjectType::Blob, contents)?.to_string()),
+            None => None,
+        };
+        self.file_hashes.insert(path.to_path_buf(), hash);
+        Ok(())
+    }
+
+    /// Record the output map files that `plan_report` depends on: those of the resource itself,
+    /// and those of any out:// references in its body.
+    pub fn track_output_maps(&mut self, plan_report: &PlanReport) -> anyhow::Result<()> {
+        let mut addrs = vec![plan_report.virt_addr.clone()];
+
+        if let Some(phy_addr) = &plan_report.phy_addr {
+            addrs.push(phy_addr.clone());
+        }
+
+        if let Ok(body) = std::fs::read_to_string(plan_report.prefix.join(&plan_report.virt_addr)) {
+            for read_output in get_read_outputs(&body) {
+                addrs.push(read_output.addr);
+            }
+        }
+
+        for addr in addrs {
+            self.track_output_map(&plan_report.prefix, &addr)?;
+        }
+
+        Ok(())
+    }
+
+    fn track_output_map(&mut self, prefix: &Path, addr: &Path) -> anyhow::Result<()> {
+        let output_path = OutputMapFile::path(prefix, addr);
+
+        if self.output_map_hashes.contains_key(&output_path) {
+            return Ok(());
+        }
+
+        let hash = Self::hash_file(&output_path)?;
+        self.output_map_hashes.insert(output_path, hash);
+
+        // Follow links, so that a change to the output map they point to is caught too.
+        if let Some(OutputMapFile::PointerToVirtual(virt_addr)) = OutputMapFile::read(prefix, addr)? {
+            self.track_output_map(prefix, &virt_addr)?;
+        }
+
+        Ok(())
+    }
+
+    /// Return each tracked file or output map whose contents no longer match
+    /// the hash recorded at plan time.
+    pub fn changed_files(&self) -> anyhow::Result<Vec<PathBuf>> {
+        let mut changed = Vec::new();
+
+        for (path, hash) in self.file_hashes.iter().chain(self.output_map_hashes.iter()) {
+            if Self::hash_file(path)? != *hash {
+                changed.push(path.clone());
+            }
+        }
+
+        changed.sort();
+        Ok(changed)
+    }
+
+    pub fn write(&self, path: &Path) -> anyhow::Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn read(path: &Path) -> anyhow::Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&contents)?)
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Default, Debug)]
@@ -138,3 +330,52 @@ impl UnbundleReport {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plan_summary() {
+        let connector_def: config::Connector = crate::util::RON
+            .from_str(r#"Connector(shortname: "aws", spec: Binary(path: "aws-connector"))"#)
+            .unwrap();
+
+        let plan_report = |prefix: &str, ops: usize, current_none: bool, desired_none: bool| PlanReport {
+            prefix: PathBuf::from(prefix),
+            connector_def: Some(connector_def.clone()),
+            connector_ops: vec![PlanResponseElement::default(); ops],
+            current_none,
+            desired_none,
+            ..Default::default()
+        };
+
+        let mut summary = PlanSummary::default();
+        summary.add(&plan_report("main", 2, true, false));
+        summary.add(&plan_report("main", 1, false, false));
+        summary.add(&plan_report("main", 1, false, true));
+        summary.add(&plan_report("main", 0, false, false));
+        summary.add(&PlanReport {
+            missing_outputs: vec![ReadOutput {
+                addr: PathBuf::from("vpc.ron"),
+                key: String::from("vpc_id"),
+            }],
+            ..plan_report("staging", 0, true, false)
+        });
+
+        assert_eq!(
+            summary.counts["main"]["aws"],
+            PlanChangeCounts {
+                created: 1,
+                modified: 1,
+                deleted: 1,
+                ops: 4,
+                ..Default::default()
+            }
+        );
+        assert_eq!(summary.counts["staging"]["aws"].deferred, 1);
+        assert_eq!(summary.total.ops, 4);
+        assert_eq!(summary.total.deferred, 1);
+        assert!(summary.missing_outputs.contains("out://vpc.ron[vpc_id]"));
+    }
+}
@@ -1,3 +1,5 @@
+use std::ops::Range;
+
 use crate::connector::DocIdent;
 use anyhow::Result;
 use pest::{Parser, Span, iterators::Pair};
@@ -72,7 +74,8 @@ type RonPath = Vec<Component>;
 #[derive(Default)]
 pub struct RonStringReport {
     pub root: Option<String>,
-    pub strings: Vec<(RonPath, String)>,
+    /// Each string's path, contents, and the byte range of its contents within the source.
+    pub strings: Vec<(RonPath, String, Range<usize>)>,
 }
 
 /// Assuming src is a RON config file, parse it and return a list of Name1.field1.field2.key: "value"
@@ -385,14 +388,14 @@ fn descend_find_strings(
     root_name: &mut Option<String>,
     src: &str,
     trail: &mut Vec<Component>,
-    results: &mut Vec<(Vec<Component>, String)>,
+    results: &mut Vec<(Vec<Component>, String, Range<usize>)>,
 ) {
-    eprintln!("{pair:?}, {src}, {trail:?}");
     match pair.as_rule() {
         Rule::string_std => {
             let raw = pair.as_str();
             let content = raw[1..raw.len() - 1].to_owned();
-            results.push((trail.clone(), content));
+            let span = pair.as_span();
+            results.push((trail.clone(), content, span.start() + 1..span.end() - 1));
         }
 
         Rule::ron | Rule::value => {
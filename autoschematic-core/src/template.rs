@@ -62,7 +62,7 @@ pub fn descend_get_read_outputs(config: &str) -> anyhow::Result<RonOutputReport>
 
     res.root = reads_strings.root;
 
-    for (path, s) in reads_strings.strings {
+    for (path, s, _) in reads_strings.strings {
         if let Some(cap) = OUTREF_REGEX.captures(&s) {
             let filename = cap.get(1).map(|m| m.as_str()).unwrap_or("");
             let key = cap.get(2).map(|m| m.as_str()).unwrap_or("");
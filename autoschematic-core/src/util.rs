@@ -24,6 +24,7 @@ use similar::{ChangeTag, TextDiff};
 
 use crate::{
     config::AutoschematicConfig,
+    config_policy::AutoschematicPolicyConfig,
     diag::{Diagnostic, DiagnosticPosition, DiagnosticResponse, DiagnosticSeverity, DiagnosticSpan},
     error::{AutoschematicError, AutoschematicErrorType},
 };
@@ -127,6 +128,30 @@ pub fn load_autoschematic_config() -> anyhow::Result<AutoschematicConfig> {
     Ok(config_file)
 }
 
+/// The per-repository virtualenv that `autoschematic install` installs Spec::Pip connectors into.
+pub fn python_venv_dir() -> anyhow::Result<PathBuf> {
+    Ok(repo_root()?.join(".autoschematic").join("venv"))
+}
+
+/// The path of `binary` within the per-repository virtualenv, if it is installed there.
+pub fn python_venv_binary(binary: &str) -> Option<PathBuf> {
+    let path = python_venv_dir().ok()?.join("bin").join(binary);
+    path.is_file().then_some(path)
+}
+
+/// Load autoschematic.policy.ron from the repository root, or None if the repository has no policy.
+pub fn load_autoschematic_policy_config() -> anyhow::Result<Option<AutoschematicPolicyConfig>> {
+    let policy_path = repo_root()?.join("autoschematic.policy.ron");
+    if !policy_path.is_file() {
+        return Ok(None);
+    }
+
+    let policy_body = std::fs::read_to_string(policy_path).context("Reading autoschematic.policy.ron")?;
+    let policy = RON.from_str(&policy_body).context("Parsing autoschematic.policy.ron")?;
+
+    Ok(Some(policy))
+}
+
 pub fn optional_string_from_utf8(s: Option<Vec<u8>>) -> anyhow::Result<Option<String>> {
     match s {
         Some(s) => Ok(Some(String::from_utf8(s)?)),
@@ -402,6 +427,18 @@ pub fn path_relative_from(path: &Path, base: &Path) -> Option<PathBuf> {
     }
 }
 
+/// List the address of every file within `prefix`, relative to it,
+/// skipping hidden files and directories such as .bundle and .secret.
+pub fn list_prefix_addrs(prefix: &Path) -> Vec<PathBuf> {
+    walkdir::WalkDir::new(prefix)
+        .into_iter()
+        .filter_entry(|entry| entry.depth() == 0 || !entry.file_name().to_string_lossy().starts_with('.'))
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .filter_map(|entry| entry.path().strip_prefix(prefix).ok().map(Path::to_path_buf))
+        .collect()
+}
+
 pub fn copy_dir_all(src: impl AsRef<Path>, dst: impl AsRef<Path>) -> std::io::Result<()> {
     fs::create_dir_all(&dst)?;
     for entry in fs::read_dir(src)? {
@@ -1,12 +1,15 @@
 pub mod apply;
 pub mod check_drift;
+pub mod destroy;
 pub mod filter;
+pub mod gc;
 pub mod get;
 pub mod get_docstring;
 pub mod get_skeletons;
 pub mod import;
 pub mod list;
 pub mod plan;
+pub mod pull_state;
 pub mod rename;
 pub mod task_exec;
 pub mod unbundle;
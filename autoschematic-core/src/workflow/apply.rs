@@ -4,16 +4,37 @@ use anyhow::bail;
 
 use crate::{
     config::AutoschematicConfig,
-    connector::{Connector, FilterResponse, OutputMapFile, VirtToPhyResponse},
+    connector::{Connector, FilterResponse, OpExecResponse, OutputMapFile, VirtToPhyResponse},
     connector_cache::ConnectorCache,
+    journal::{FileHashes, Journal, JournalEntry},
     keystore::KeyStore,
-    report::{ApplyReport, PlanReport},
+    report::{ApplyReport, PlanReport, SavedPlan},
 };
 
 pub async fn apply_connector(connector: Arc<dyn Connector>, plan: &PlanReport) -> anyhow::Result<Option<ApplyReport>> {
+    apply_connector_journaled(connector, plan, None, 0).await
+}
+
+/// Like apply_connector, but records each op in `journal` before and after it is executed,
+/// and starts from op `start_op` so that an interrupted plan can be resumed.
+pub async fn apply_connector_journaled(
+    connector: Arc<dyn Connector>,
+    plan: &PlanReport,
+    journal: Option<&Journal>,
+    start_op: usize,
+) -> anyhow::Result<Option<ApplyReport>> {
     let mut apply_report = ApplyReport::default();
 
-    for op in &plan.connector_ops {
+    for (op_index, op) in plan.connector_ops.iter().enumerate().skip(start_op) {
+        if let Some(journal) = journal {
+            journal.append(&JournalEntry::OpStarted {
+                prefix: plan.prefix.clone(),
+                virt_addr: plan.virt_addr.clone(),
+                op_index,
+                op_definition: op.op_definition.clone(),
+            })?;
+        }
+
         // let Some(phy_addr) = connector.addr_virt_to_phy(&virt_addr).await? else {
         //     exec_error = Some(anyhow!(
         //         "Error: virt addr could not be resolved: {:?}",
@@ -21,20 +42,26 @@ pub async fn apply_connector(connector: Arc<dyn Connector>, plan: &PlanReport) -
         //     ));
         //     break;
         // };
-        // TODO again, this is the diabolical incongruity between virt_addr and phy_addr depending on
-        // the presence of one or the other. Are we really sure this isn't ananas?
-        let op_exec_output = match connector.addr_virt_to_phy(&plan.virt_addr).await? {
-            VirtToPhyResponse::NotPresent => connector.op_exec(&plan.virt_addr, &op.op_definition).await?,
-            VirtToPhyResponse::Deferred(_read_outputs) => {
-                bail!("Apply run on plan with deferred outputs.")
+        let op_exec_output = match exec_op(&connector, plan, &op.op_definition).await {
+            Ok(op_exec_output) => op_exec_output,
+            Err(e) => {
+                if let Some(journal) = journal {
+                    journal.append(&JournalEntry::OpFailed {
+                        prefix: plan.prefix.clone(),
+                        virt_addr: plan.virt_addr.clone(),
+                        op_index,
+                        error: format!("{e:#}"),
+                    })?;
+                }
+                return Err(e);
             }
-            VirtToPhyResponse::Present(phy_addr) => connector.op_exec(&phy_addr, &op.op_definition).await?,
-            VirtToPhyResponse::Null(phy_addr) => connector.op_exec(&phy_addr, &op.op_definition).await?,
         };
 
         apply_report.prefix = plan.prefix.clone();
         apply_report.virt_addr = plan.virt_addr.clone();
 
+        let wrote_from = apply_report.wrote_files.len();
+
         if let Some(outputs) = &op_exec_output.outputs
             && !outputs.is_empty()
         {
@@ -63,12 +90,40 @@ pub async fn apply_connector(connector: Arc<dyn Connector>, plan: &PlanReport) -
             }
         }
 
+        if let Some(journal) = journal {
+            let mut file_hashes = FileHashes::new();
+            for path in &apply_report.wrote_files[wrote_from..] {
+                file_hashes.insert(path.clone(), SavedPlan::hash_file(path)?);
+            }
+
+            journal.append(&JournalEntry::OpFinished {
+                prefix: plan.prefix.clone(),
+                virt_addr: plan.virt_addr.clone(),
+                op_index,
+                response: op_exec_output.clone(),
+                file_hashes,
+            })?;
+        }
+
         apply_report.outputs.push(op_exec_output);
     }
 
     Ok(Some(apply_report))
 }
 
+async fn exec_op(connector: &Arc<dyn Connector>, plan: &PlanReport, op_definition: &str) -> anyhow::Result<OpExecResponse> {
+    // TODO again, this is the diabolical incongruity between virt_addr and phy_addr depending on
+    // the presence of one or the other. Are we really sure this isn't ananas?
+    match connector.addr_virt_to_phy(&plan.virt_addr).await? {
+        VirtToPhyResponse::NotPresent => connector.op_exec(&plan.virt_addr, op_definition).await,
+        VirtToPhyResponse::Deferred(_read_outputs) => {
+            bail!("Apply run on plan with deferred outputs.")
+        }
+        VirtToPhyResponse::Present(phy_addr) => connector.op_exec(&phy_addr, op_definition).await,
+        VirtToPhyResponse::Null(phy_addr) => connector.op_exec(&phy_addr, op_definition).await,
+    }
+}
+
 /// For a given path, attempt to resolve its prefix and Connector impl and return a Vec of ConnectorOps.
 /// Note that this, unlike the server implementation, does not handle setting desired = None where files do
 /// not exist - it is intended to be used from the command line or from LSPs to quickly modify resources.
@@ -78,6 +133,7 @@ pub async fn apply(
     keystore: Option<Arc<dyn KeyStore>>,
     connector_filter: &Option<String>,
     plan_report: &PlanReport,
+    journal: Option<&Journal>,
 ) -> Result<Option<ApplyReport>, anyhow::Error> {
     let Some(prefix_name) = plan_report.prefix.to_str() else {
         // TODO shouldn't this throw an error?
@@ -116,7 +172,7 @@ pub async fn apply(
             .await?
             == FilterResponse::Resource
         {
-            let apply_report = apply_connector(connector, plan_report).await?;
+            let apply_report = apply_connector_journaled(connector, plan_report, journal, 0).await?;
             return Ok(apply_report);
         }
     }
@@ -1,10 +1,16 @@
-use std::{path::Path, sync::Arc};
+use std::{collections::HashMap, path::Path, sync::Arc};
 
 use crate::{
-    config::AutoschematicConfig, connector::FilterResponse, connector_cache::ConnectorCache, error::AutoschematicError,
+    config::AutoschematicConfig,
+    connector::{FilterResponse, OutputMap, OutputMapFile},
+    connector_cache::ConnectorCache,
+    error::AutoschematicError,
     keystore::KeyStore,
 };
 
+/// Each changed output key mapped to its (current, stored) values.
+pub type OutputsDrift = HashMap<String, (Option<String>, Option<String>)>;
+
 pub enum CheckDriftResult {
     /// Neither the desired (local) resource nor the current (remote) resource exist.
     NeitherExist,
@@ -15,14 +21,41 @@ pub enum CheckDriftResult {
         current: Option<Vec<u8>>,
         desired: Option<Vec<u8>>,
     },
+    /// Both exist and Connector::eq(...) returned true, but the outputs reported by the remote resource
+    /// differ from those stored in its output map file.
+    /// `changed` maps each differing output key to its (current, stored) values.
+    OutputsDrifted { changed: OutputsDrift },
     /// Both exist, and Connector::eq(...) returned true.
     Equal,
 }
 
+/// Compare the outputs reported by a remote resource against those stored in the output map file
+/// at `virt_addr`, following any links. Only keys reported by the remote resource are compared,
+/// since connectors may store outputs at apply time that get() doesn't report.
+fn diff_outputs(prefix: &Path, virt_addr: &Path, current_outputs: &OutputMap) -> anyhow::Result<OutputsDrift> {
+    let stored_outputs = match OutputMapFile::read_recurse(prefix, virt_addr)? {
+        Some(OutputMapFile::OutputMap(map)) => map,
+        _ => HashMap::new(),
+    };
+
+    let mut changed = HashMap::new();
+    for (key, current_value) in current_outputs {
+        let stored_value = stored_outputs.get(key);
+        if stored_value != Some(current_value) {
+            changed.insert(key.clone(), (Some(current_value.clone()), stored_value.cloned()));
+        }
+    }
+
+    Ok(changed)
+}
+
+/// Compare the resource at `prefix`/`virt_addr` against its remote state.
+/// If `connector_filter` is set, only that connector is spawned and asked about the address.
 pub async fn check_drift(
     autoschematic_config: &AutoschematicConfig,
     connector_cache: &ConnectorCache,
     keystore: Option<Arc<dyn KeyStore>>,
+    connector_filter: Option<&str>,
     prefix: &Path,
     virt_addr: &Path,
 ) -> Result<CheckDriftResult, AutoschematicError> {
@@ -43,8 +76,13 @@ pub async fn check_drift(
     };
 
     let mut current_state = None;
+    let mut current_outputs = None;
 
     for connector_def in &prefix_def.connectors {
+        if connector_filter.is_some_and(|f| f != connector_def.shortname) {
+            continue;
+        }
+
         let (connector, _inbox) = connector_cache
             .get_or_spawn_connector(autoschematic_config, prefix_name, connector_def, keystore.clone(), true)
             .await?;
@@ -59,15 +97,14 @@ pub async fn check_drift(
                 }
                 crate::connector::VirtToPhyResponse::Present(phy_addr) => {
                     if let Some(body) = connector.get(&phy_addr).await? {
-                        // return Ok(Some(body.resource_definition));
-                        // TODO we also need to check if outputs have drifted!
                         current_state = Some(body.resource_definition);
+                        current_outputs = body.outputs;
                     }
                 }
                 crate::connector::VirtToPhyResponse::Null(virt_addr) => {
                     if let Some(body) = connector.get(&virt_addr).await? {
                         current_state = Some(body.resource_definition);
-                        // return Ok(Some(body.resource_definition));
+                        current_outputs = body.outputs;
                     }
                 }
             }
@@ -87,7 +124,15 @@ pub async fn check_drift(
                     });
                 }
                 (Some(a), Some(b)) => match connector.eq(virt_addr, &a, b.as_bytes()).await? {
-                    true => return Ok(CheckDriftResult::Equal),
+                    true => {
+                        if let Some(current_outputs) = current_outputs {
+                            let changed = diff_outputs(prefix, virt_addr, &current_outputs)?;
+                            if !changed.is_empty() {
+                                return Ok(CheckDriftResult::OutputsDrifted { changed });
+                            }
+                        }
+                        return Ok(CheckDriftResult::Equal);
+                    }
                     false => {
                         return Ok(CheckDriftResult::NotEqual {
                             current: Some(a),
@@ -0,0 +1,103 @@
+use std::{path::Path, sync::Arc};
+
+use anyhow::Context;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::{
+    config::AutoschematicConfig, connector_cache::ConnectorCache, glob::addr_matches_filter,
+    graph::sort_plan_reports_for_destroy, keystore::KeyStore, protect::flag_protected, report::PlanReport,
+    workflow::plan::plan_connector_with_desired,
+};
+
+/// Plan the destruction of every resource that the connectors in `prefix` list with a virtual address under `subpath`,
+/// by planning each with `desired = None`. Resources whose plans are empty are left out.
+/// The plans are returned in the order they should be applied: each resource is destroyed before
+/// the resources it reads outputs from.
+pub async fn plan_destroy(
+    autoschematic_config: &AutoschematicConfig,
+    connector_cache: &ConnectorCache,
+    keystore: Option<Arc<dyn KeyStore>>,
+    prefix: &Path,
+    connector_filter: &Option<String>,
+    subpath: &Path,
+) -> anyhow::Result<Vec<PlanReport>> {
+    let Some(prefix_name) = prefix.to_str() else {
+        return Ok(Vec::new());
+    };
+
+    let Some(prefix_def) = autoschematic_config.prefixes.get(prefix_name) else {
+        return Ok(Vec::new());
+    };
+
+    let mut plan_reports = Vec::new();
+
+    for connector_def in &prefix_def.connectors {
+        if let Some(connector_filter) = &connector_filter
+            && connector_def.shortname != *connector_filter
+        {
+            continue;
+        }
+
+        let (connector, mut inbox) = connector_cache
+            .get_or_spawn_connector(autoschematic_config, prefix_name, connector_def, keystore.clone(), true)
+            .await?;
+        let _reader_handle = tokio::spawn(async move {
+            loop {
+                match inbox.recv().await {
+                    Ok(Some(stdout)) => {
+                        eprintln!("{}", stdout.to_string_lossy());
+                    }
+                    Err(RecvError::Closed) => break,
+                    _ => {}
+                }
+            }
+        });
+
+        let connector_subpaths = connector
+            .subpaths()
+            .await
+            .context(format!("{}::subpaths()", connector_def.shortname))?;
+
+        // Connector subpaths are physical, while `subpath` filters virtual addresses, so every subpath is listed.
+        for connector_subpath in connector_subpaths {
+            let phy_addrs = connector.list(&connector_subpath).await.context(format!(
+                "{}::list({})",
+                connector_def.shortname,
+                connector_subpath.display()
+            ))?;
+
+            for phy_addr in phy_addrs {
+                let virt_addr = connector.addr_phy_to_virt(&phy_addr).await?.unwrap_or(phy_addr);
+
+                if !addr_matches_filter(&virt_addr, subpath) {
+                    continue;
+                }
+
+                if plan_reports
+                    .iter()
+                    .any(|p: &PlanReport| p.virt_addr == virt_addr && p.connector_def.as_ref() == Some(connector_def))
+                {
+                    continue;
+                }
+
+                let Some(mut plan_report) =
+                    plan_connector_with_desired(connector_def.clone(), connector.clone(), prefix, &virt_addr, None).await?
+                else {
+                    continue;
+                };
+
+                flag_protected(autoschematic_config, &mut plan_report);
+
+                if plan_report.connector_ops.is_empty() && plan_report.missing_outputs.is_empty() {
+                    continue;
+                }
+
+                plan_reports.push(plan_report);
+            }
+        }
+    }
+
+    sort_plan_reports_for_destroy(&mut plan_reports)?;
+
+    Ok(plan_reports)
+}
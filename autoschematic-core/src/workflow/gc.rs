@@ -0,0 +1,217 @@
+use std::{
+    collections::HashSet,
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use anyhow::bail;
+use serde::Serialize;
+
+use crate::{bundle::BundleMapFile, config::AutoschematicConfig, connector::OutputMapFile};
+
+/// Why a map file in .autoschematic/ or .bundle/ is garbage.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum GarbageKind {
+    /// An output map for a resource file that no longer exists.
+    OrphanedOutputMap,
+    /// A link from a physical address that no longer resolves to the output map of an existing resource.
+    BrokenLink { target: PathBuf },
+    /// A bundle map for a resource file that no longer exists.
+    OrphanedBundleMap,
+    /// A bundle map marking a file as the child of a bundle that no longer exists.
+    OrphanedChildOf { parent: PathBuf },
+}
+
+impl fmt::Display for GarbageKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GarbageKind::OrphanedOutputMap => write!(f, "output map for a resource that no longer exists"),
+            GarbageKind::BrokenLink { target } => write!(f, "broken link to {}", target.display()),
+            GarbageKind::OrphanedBundleMap => write!(f, "bundle map for a resource that no longer exists"),
+            GarbageKind::OrphanedChildOf { parent } => {
+                write!(f, "child of bundle {}, which no longer exists", parent.display())
+            }
+        }
+    }
+}
+
+/// A map file that no longer describes any resource, and can be removed.
+#[derive(Debug, Clone, Serialize)]
+pub struct Garbage {
+    pub prefix: PathBuf,
+    pub addr: PathBuf,
+    /// The path of the map file itself.
+    pub path: PathBuf,
+    pub kind: GarbageKind,
+}
+
+/// Follow the links from the output map file at `addr`, returning the address of the
+/// output map they resolve to, or None if they end at a missing file or loop back on themselves.
+fn resolve_link(prefix: &Path, addr: &Path) -> anyhow::Result<Option<PathBuf>> {
+    let mut seen = HashSet::new();
+    let mut addr = addr.to_path_buf();
+
+    loop {
+        if !seen.insert(addr.clone()) {
+            return Ok(None);
+        }
+
+        match OutputMapFile::read(prefix, &addr)? {
+            Some(OutputMapFile::PointerToVirtual(target)) => addr = target,
+            Some(OutputMapFile::OutputMap(_)) => return Ok(Some(addr)),
+            None => return Ok(None),
+        }
+    }
+}
+
+/// Cross-check the output and bundle map files in `prefix_filter`, or in every prefix,
+/// against the resource files they describe, and return every map file that is garbage.
+pub fn find_garbage(config: &AutoschematicConfig, prefix_filter: Option<&str>) -> anyhow::Result<Vec<Garbage>> {
+    if let Some(prefix_filter) = prefix_filter
+        && !config.prefixes.contains_key(prefix_filter)
+    {
+        bail!("No prefix named {} in autoschematic.ron", prefix_filter);
+    }
+
+    let mut garbage = Vec::new();
+
+    for prefix_name in config.prefixes.keys() {
+        if prefix_filter.is_some_and(|prefix_filter| prefix_filter != prefix_name) {
+            continue;
+        }
+
+        let prefix = PathBuf::from(prefix_name);
+        let exists = |addr: &Path| prefix.join(addr).is_file();
+
+        for addr in OutputMapFile::list(&prefix) {
+            let kind = match OutputMapFile::read(&prefix, &addr)? {
+                Some(OutputMapFile::OutputMap(_)) if !exists(&addr) => GarbageKind::OrphanedOutputMap,
+                Some(OutputMapFile::PointerToVirtual(target)) => match resolve_link(&prefix, &target)? {
+                    Some(resolved) if exists(&resolved) => continue,
+                    _ => GarbageKind::BrokenLink { target },
+                },
+                _ => continue,
+            };
+
+            garbage.push(Garbage {
+                path: OutputMapFile::path(&prefix, &addr),
+                prefix: prefix.clone(),
+                addr,
+                kind,
+            });
+        }
+
+        for addr in BundleMapFile::list(&prefix) {
+            let kind = match BundleMapFile::read(&prefix, &addr)? {
+                Some(_) if !exists(&addr) => GarbageKind::OrphanedBundleMap,
+                Some(BundleMapFile::ChildOf { parent }) if !exists(&parent) => GarbageKind::OrphanedChildOf { parent },
+                _ => continue,
+            };
+
+            garbage.push(Garbage {
+                path: BundleMapFile::path(&prefix, &addr),
+                prefix: prefix.clone(),
+                addr,
+                kind,
+            });
+        }
+    }
+
+    Ok(garbage)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{connector::OutputMap, util::RON};
+
+    #[test]
+    fn test_find_garbage() {
+        // Output maps are kept under .autoschematic/<prefix>, which an absolute prefix replaces,
+        // so that the whole test lives in the temp dir.
+        let prefix = std::env::temp_dir().join(format!("autoschematic-gc-test-{}", std::process::id()));
+        std::fs::create_dir_all(&prefix).unwrap();
+
+        let config: AutoschematicConfig = RON
+            .from_str(&format!(
+                "AutoschematicConfig(prefixes: {{ {:?}: Prefix(connectors: []) }})",
+                prefix.display()
+            ))
+            .unwrap();
+
+        let resource = |addr: &str| {
+            let path = prefix.join(addr);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "()").unwrap();
+        };
+        let output_map = |addr: &str| {
+            OutputMapFile::OutputMap(OutputMap::from([(String::from("id"), String::from("1"))]))
+                .write(&prefix, Path::new(addr))
+                .unwrap();
+        };
+        let link = |addr: &str, target: &str| {
+            OutputMapFile::write_link(&prefix, Path::new(addr), Path::new(target)).unwrap();
+        };
+
+        // A resource with its output map, and a link to it: none of these are garbage.
+        resource("vpc/main.ron");
+        output_map("vpc/main.ron");
+        link("vpc/vpc-0123.ron", "vpc/main.ron");
+
+        // An output map whose resource was deleted.
+        output_map("vpc/deleted.ron");
+
+        // A link to an output map that doesn't exist, and two links to each other.
+        link("vpc/vpc-4567.ron", "vpc/missing.ron");
+        link("vpc/loop-a.ron", "vpc/loop-b.ron");
+        link("vpc/loop-b.ron", "vpc/loop-a.ron");
+
+        // A child of a bundle that was deleted, and a child of one that still exists.
+        resource("vpc/bundle.ron");
+        BundleMapFile::Bundle.write(&prefix, Path::new("vpc/bundle.ron")).unwrap();
+        resource("vpc/child.ron");
+        BundleMapFile::write_link(&prefix, Path::new("vpc/child.ron"), Path::new("vpc/bundle.ron")).unwrap();
+        resource("vpc/orphan.ron");
+        BundleMapFile::write_link(&prefix, Path::new("vpc/orphan.ron"), Path::new("vpc/gone.ron")).unwrap();
+
+        let mut garbage: Vec<(PathBuf, GarbageKind)> = find_garbage(&config, None)
+            .unwrap()
+            .into_iter()
+            .map(|garbage| (garbage.addr, garbage.kind))
+            .collect();
+        garbage.sort_by(|a, b| a.0.cmp(&b.0));
+
+        assert_eq!(
+            garbage,
+            vec![
+                (PathBuf::from("vpc/deleted.ron"), GarbageKind::OrphanedOutputMap),
+                (
+                    PathBuf::from("vpc/loop-a.ron"),
+                    GarbageKind::BrokenLink {
+                        target: PathBuf::from("vpc/loop-b.ron")
+                    }
+                ),
+                (
+                    PathBuf::from("vpc/loop-b.ron"),
+                    GarbageKind::BrokenLink {
+                        target: PathBuf::from("vpc/loop-a.ron")
+                    }
+                ),
+                (
+                    PathBuf::from("vpc/orphan.ron"),
+                    GarbageKind::OrphanedChildOf {
+                        parent: PathBuf::from("vpc/gone.ron")
+                    }
+                ),
+                (
+                    PathBuf::from("vpc/vpc-4567.ron"),
+                    GarbageKind::BrokenLink {
+                        target: PathBuf::from("vpc/missing.ron")
+                    }
+                ),
+            ]
+        );
+
+        std::fs::remove_dir_all(&prefix).unwrap();
+    }
+}
@@ -2,6 +2,7 @@ use std::{path::Path, sync::Arc};
 
 use crate::{
     config::{AutoschematicConfig, AuxTask, Connector, Prefix},
+    config_policy::{AutoschematicPolicyConfig, PolicyMatch, PolicyRule},
     config_rbac::{self},
     connector::{DocIdent, FilterResponse, GetDocResponse},
     connector_cache::ConnectorCache,
@@ -26,6 +27,7 @@ pub fn get_system_docstring(path: &Path, ident: DocIdent) -> Result<Option<GetDo
             ],
             [config_rbac::User, config_rbac::Grant]
         ),
+        "autoschematic.policy.ron" => doc_dispatch!(ident, [AutoschematicPolicyConfig, PolicyRule, PolicyMatch], [PolicyCheck]),
         _ => Ok(None),
     }
 }
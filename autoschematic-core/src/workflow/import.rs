@@ -4,6 +4,7 @@ use std::{
 };
 
 use anyhow::Context;
+use serde::Serialize;
 use tokio::{
     sync::{Semaphore, broadcast::error::RecvError},
     task::JoinSet,
@@ -18,7 +19,8 @@ use crate::{
     keystore::KeyStore,
 };
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
 pub enum ImportMessage {
     StartImport { subpath: PathBuf },
     SkipExisting { prefix: PathBuf, addr: PathBuf },
@@ -8,6 +8,7 @@ use crate::{
     connector::{Connector, FilterResponse, VirtToPhyResponse},
     connector_cache::ConnectorCache,
     keystore::KeyStore,
+    protect::flag_protected,
     report::PlanReport,
     template::template_config,
     util::split_prefix_addr,
@@ -18,11 +19,32 @@ pub async fn plan_connector(
     connector: Arc<dyn Connector>,
     prefix: &Path,
     virt_addr: &Path,
+) -> Result<Option<PlanReport>, anyhow::Error> {
+    let path = prefix.join(virt_addr);
+
+    let desired = if path.is_file() {
+        Some(tokio::fs::read(&path).await?)
+    } else {
+        None
+    };
+
+    plan_connector_with_desired(connector_def, connector, prefix, virt_addr, desired).await
+}
+
+/// Like plan_connector, but plans against the given `desired` body instead of reading it from disk.
+/// A `desired` of None plans the deletion of the resource.
+pub async fn plan_connector_with_desired(
+    connector_def: config::Connector,
+    connector: Arc<dyn Connector>,
+    prefix: &Path,
+    virt_addr: &Path,
+    desired: Option<Vec<u8>>,
 ) -> Result<Option<PlanReport>, anyhow::Error> {
     let mut plan_report = PlanReport {
         prefix: prefix.into(),
         connector_def: Some(connector_def.clone()),
         virt_addr: virt_addr.into(),
+        desired_none: desired.is_none(),
         ..Default::default()
     };
 
@@ -57,12 +79,9 @@ pub async fn plan_connector(
         None => None,
     };
 
-    let path = prefix.join(virt_addr);
-
-    let connector_ops = if path.is_file() {
-        // let desired = std::fs::read(&path)?;
-        let desired_bytes = tokio::fs::read(&path).await?;
+    plan_report.current_none = current.is_none();
 
+    let connector_ops = if let Some(desired_bytes) = desired {
         match std::str::from_utf8(&desired_bytes) {
             Ok(desired) => {
                 let template_result = template_config(prefix, desired)?;
@@ -98,7 +117,7 @@ pub async fn plan_connector(
             }
         }
     } else {
-        // The file does not exist, so `desired` is therefore None.
+        // The file does not exist (or is being deleted), so `desired` is therefore None.
         // Generally speaking, this will destroy the given resource if it currently exists.
 
         // TODO warning that this phy .unwrap_or( virt )
@@ -128,6 +147,34 @@ pub async fn plan(
     keystore: Option<Arc<dyn KeyStore>>,
     connector_filter: &Option<String>,
     path: &Path,
+) -> Result<Option<PlanReport>, anyhow::Error> {
+    let desired = if path.is_file() {
+        Some(tokio::fs::read(path).await?)
+    } else {
+        None
+    };
+
+    plan_with_desired(
+        autoschematic_config,
+        connector_cache,
+        keystore,
+        connector_filter,
+        path,
+        desired,
+    )
+    .await
+}
+
+/// Like plan, but plans against the given `desired` body instead of reading it from disk,
+/// for instance to plan the contents of a file at a given git commit.
+/// A `desired` of None plans the deletion of the resource at `path`.
+pub async fn plan_with_desired(
+    autoschematic_config: &AutoschematicConfig,
+    connector_cache: Arc<ConnectorCache>,
+    keystore: Option<Arc<dyn KeyStore>>,
+    connector_filter: &Option<String>,
+    path: &Path,
+    desired: Option<Vec<u8>>,
 ) -> Result<Option<PlanReport>, anyhow::Error> {
     let autoschematic_config = Arc::new(autoschematic_config.clone());
 
@@ -160,6 +207,7 @@ pub async fn plan(
         // let prefix_name = prefix_name.clone();
         let prefix = prefix.clone();
         let virt_addr = virt_addr.clone();
+        let desired = desired.clone();
         joinset.spawn(async move {
             let Some(prefix_name) = prefix.to_str() else {
                 return Ok(None);
@@ -185,7 +233,7 @@ pub async fn plan(
                 .await?
                 == FilterResponse::Resource
             {
-                let plan_report = plan_connector(connector_def, connector, &prefix, &virt_addr).await?;
+                let plan_report = plan_connector_with_desired(connector_def, connector, &prefix, &virt_addr, desired).await?;
                 return Ok(plan_report);
             }
             Ok(None)
@@ -199,7 +247,8 @@ pub async fn plan(
     }
 
     while let Some(res) = joinset.join_next().await {
-        if let Some(plan_report) = res?? {
+        if let Some(mut plan_report) = res?? {
+            flag_protected(&autoschematic_config, &mut plan_report);
             return Ok(Some(plan_report));
         }
     }
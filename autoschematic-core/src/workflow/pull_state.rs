@@ -0,0 +1,149 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::Context;
+
+use crate::{
+    connector::{Connector, OutputMapFile, VirtToPhyResponse},
+    template::{ReadOutput, template_config},
+};
+
+#[derive(Default)]
+pub struct PullStateReport {
+    pub object_count: usize,
+    pub import_count: usize,
+    pub delete_count: usize,
+    pub deferred_count: usize,
+    pub missing_outputs: HashSet<ReadOutput>,
+}
+
+impl PullStateReport {
+    /// Count the result of pulling the state of a single resource.
+    pub fn add(&mut self, result: &PullStateResult) {
+        match result {
+            PullStateResult::Deferred { missing_outputs } => {
+                self.deferred_count += 1;
+                self.missing_outputs.extend(missing_outputs.iter().cloned());
+            }
+            PullStateResult::Unchanged => {}
+            PullStateResult::Pulled { .. } => self.import_count += 1,
+            PullStateResult::Deleted { .. } => self.delete_count += 1,
+        }
+    }
+}
+
+/// The outcome of pulling the remote state of a single resource into its local file.
+#[derive(Debug)]
+pub enum PullStateResult {
+    /// The resource reads outputs that don't exist yet, so its remote state can't be looked up.
+    Deferred { missing_outputs: Vec<ReadOutput> },
+    /// The local file and output map already match the remote state.
+    Unchanged,
+    /// The local file and/or output map were rewritten from the remote state.
+    Pulled { wrote_files: Vec<PathBuf> },
+    /// The resource doesn't exist remotely, so its local file and output map were removed.
+    Deleted { removed_files: Vec<PathBuf> },
+}
+
+/// Fetch the current state of the resource at `virt_addr` with `connector`, and write it over the local file
+/// and output map if it differs from the templated local file.
+/// If the resource doesn't exist remotely and `delete` is set, remove the local file and output map instead.
+/// The caller must already have checked that `connector` manages `virt_addr` as a resource.
+/// Returns None if the local file doesn't exist, or if `virt_addr` has no physical address.
+pub async fn pull_state_connector(
+    connector_shortname: &str,
+    connector: Arc<dyn Connector>,
+    prefix: &Path,
+    virt_addr: &Path,
+    delete: bool,
+) -> anyhow::Result<Option<PullStateResult>> {
+    let path = prefix.join(virt_addr);
+
+    let phy_addr = match connector.addr_virt_to_phy(virt_addr).await? {
+        VirtToPhyResponse::NotPresent => return Ok(None),
+        VirtToPhyResponse::Deferred(read_outputs) => {
+            return Ok(Some(PullStateResult::Deferred {
+                missing_outputs: read_outputs,
+            }));
+        }
+        VirtToPhyResponse::Present(phy_addr) => phy_addr,
+        VirtToPhyResponse::Null(phy_addr) => phy_addr,
+    };
+
+    if !path.is_file() {
+        return Ok(None);
+    }
+
+    let desired_bytes = tokio::fs::read(&path).await?;
+
+    let desired = match str::from_utf8(&desired_bytes) {
+        Ok(desired) => {
+            // If valid utf8, try to template.
+            let template_result = template_config(prefix, desired)?;
+
+            if !template_result.missing.is_empty() {
+                return Ok(Some(PullStateResult::Deferred {
+                    missing_outputs: template_result.missing.into_iter().collect(),
+                }));
+            }
+
+            template_result.body.into_bytes()
+        }
+        Err(_) => desired_bytes,
+    };
+
+    let Some(current) = connector.get(&phy_addr).await.context(format!(
+        "{}::get({})",
+        connector_shortname,
+        phy_addr.to_str().unwrap_or_default()
+    ))?
+    else {
+        if !delete {
+            return Ok(Some(PullStateResult::Unchanged));
+        }
+
+        // Resource didn't exist remotely, and `delete` was indicated, so let's delete it!
+        let mut removed_files = Vec::new();
+
+        std::fs::remove_file(&path)?;
+        removed_files.push(path);
+
+        if let Some(phy_output_path) = OutputMapFile::delete(prefix, &phy_addr)? {
+            removed_files.push(phy_output_path);
+        }
+
+        if let Some(virt_output_path) = OutputMapFile::delete(prefix, virt_addr)? {
+            removed_files.push(virt_output_path);
+        }
+
+        return Ok(Some(PullStateResult::Deleted { removed_files }));
+    };
+
+    let mut wrote_files = Vec::new();
+
+    if !connector.eq(&phy_addr, &current.resource_definition, &desired).await? {
+        tokio::fs::write(&path, current.resource_definition).await?;
+        wrote_files.push(path);
+    }
+
+    if let Some(outputs) = current.outputs
+        && !outputs.is_empty()
+    {
+        let output_map_file = OutputMapFile::OutputMap(outputs);
+
+        wrote_files.push(output_map_file.write(prefix, virt_addr)?);
+
+        if virt_addr != phy_addr {
+            wrote_files.push(OutputMapFile::write_link(prefix, &phy_addr, virt_addr)?);
+        }
+    }
+
+    if wrote_files.is_empty() {
+        Ok(Some(PullStateResult::Unchanged))
+    } else {
+        Ok(Some(PullStateResult::Pulled { wrote_files }))
+    }
+}
@@ -3,10 +3,90 @@ use crate::{
     connector::{FilterResponse, OutputMapFile, VirtToPhyResponse},
     connector_cache::ConnectorCache,
     keystore::KeyStore,
-    util::{repo_root, split_prefix_addr},
+    ron::path_at::find_strings,
+    template::OUTREF_REGEX,
+    util::{list_prefix_addrs, repo_root, split_prefix_addr},
 };
 use anyhow::{Context, bail};
-use std::{path::Path, sync::Arc};
+use regex::Captures;
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// The files that a rename wrote and removed.
+#[derive(Debug, Default)]
+pub struct RenameReport {
+    pub wrote_files: Vec<PathBuf>,
+    pub removed_files: Vec<PathBuf>,
+    /// The resource files whose out:// references to the old address were rewritten.
+    pub rewrote_references: Vec<PathBuf>,
+}
+
+/// Rewrite every out://old_addr[key] reference within the strings of the RON config `src` to point at `new_addr`.
+/// Returns None if `src` has no such references.
+pub fn rewrite_read_outputs(src: &str, old_addr: &Path, new_addr: &Path) -> anyhow::Result<Option<String>> {
+    let mut res = src.to_string();
+    let mut rewrote = false;
+
+    // Splice from the back, so that the spans of earlier strings stay valid.
+    for (_, s, span) in find_strings(src)?.strings.into_iter().rev() {
+        let rewritten = OUTREF_REGEX.replace_all(&s, |caps: &Captures| {
+            if Path::new(&caps[1]) == old_addr {
+                format!("out://{}[{}]", new_addr.display(), &caps[2])
+            } else {
+                caps[0].to_string()
+            }
+        });
+
+        if rewritten != s {
+            res.replace_range(span, &rewritten);
+            rewrote = true;
+        }
+    }
+
+    Ok(rewrote.then_some(res))
+}
+
+/// Rewrite the out:// references to `old_addr` in every resource file in `prefix`, returning the files rewritten.
+/// Files that don't parse as RON are skipped.
+pub fn rewrite_references(prefix: &Path, old_addr: &Path, new_addr: &Path) -> anyhow::Result<Vec<PathBuf>> {
+    let mut rewritten = Vec::new();
+
+    for addr in list_prefix_addrs(prefix) {
+        let path = prefix.join(&addr);
+        let Ok(src) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+
+        if let Ok(Some(new_src)) = rewrite_read_outputs(&src, old_addr, new_addr) {
+            std::fs::write(&path, new_src)?;
+            rewritten.push(path);
+        }
+    }
+
+    Ok(rewritten)
+}
+
+/// Refuse to rename a resource to `new_addr` in `prefix` if a resource file or output map already exists there,
+/// as the rename would overwrite it.
+pub fn check_rename_target(prefix: &Path, new_addr: &Path) -> anyhow::Result<()> {
+    let new_path = prefix.join(new_addr);
+    if new_path.exists() {
+        bail!("Rename: {} already exists", new_path.display());
+    }
+
+    let new_output_path = OutputMapFile::path(prefix, new_addr);
+    if new_output_path.exists() {
+        bail!(
+            "Rename: an output map already exists for {} at {}",
+            new_path.display(),
+            new_output_path.display()
+        );
+    }
+
+    Ok(())
+}
 
 pub async fn rename(
     autoschematic_config: &AutoschematicConfig,
@@ -14,7 +94,7 @@ pub async fn rename(
     keystore: Option<Arc<dyn KeyStore>>,
     old_addr: &Path,
     new_addr: &Path,
-) -> anyhow::Result<()> {
+) -> anyhow::Result<RenameReport> {
     // TODO chwd to the root of the git repo
     let _root = repo_root()?;
 
@@ -38,6 +118,10 @@ pub async fn rename(
         bail!("No such prefix: {}", prefix.display());
     };
 
+    check_rename_target(&prefix, &new_virt_addr)?;
+
+    let mut report = RenameReport::default();
+
     for connector_def in &prefix_def.connectors {
         // TODO Does rename, and therefore virt_to_phy/phy_to_virt require init()? Does virt to phy mapping
         // require the connector's config files, or ought it be statically determined by outputs alone?
@@ -83,9 +167,18 @@ pub async fn rename(
                         )
                     };
 
-                    OutputMapFile::write_link(&prefix, &phy_addr, &new_virt_addr)?;
+                    report
+                        .wrote_files
+                        .push(OutputMapFile::write_link(&prefix, &phy_addr, &new_virt_addr)?);
+
+                    report.wrote_files.push(output_map_file.write(&prefix, &new_virt_addr)?);
 
-                    output_map_file.write(&prefix, &new_virt_addr)?;
+                    // Where the phy and old virt addresses are the same, the link above has already replaced the old output map.
+                    if phy_addr != old_virt_addr
+                        && let Some(old_output_path) = OutputMapFile::delete(&prefix, &old_virt_addr)?
+                    {
+                        report.removed_files.push(old_output_path);
+                    }
 
                     let new_virt_path = prefix.join(&new_virt_addr);
 
@@ -93,13 +186,74 @@ pub async fn rename(
                         std::fs::create_dir_all(parent)?;
                     }
 
-                    std::fs::copy(prefix.join(&old_virt_addr), prefix.join(&new_virt_addr)).context("copy virt")?;
+                    std::fs::copy(prefix.join(&old_virt_addr), &new_virt_path).context("copy virt")?;
 
                     std::fs::remove_file(prefix.join(&old_virt_addr))?;
+
+                    report.wrote_files.push(new_virt_path);
+                    report.removed_files.push(prefix.join(&old_virt_addr));
+
+                    report.rewrote_references = rewrite_references(&prefix, &old_virt_addr, &new_virt_addr)?;
+
+                    return Ok(report);
                 }
             }
         }
     }
 
-    Ok(())
+    bail!(
+        "Rename: no connector in prefix {} manages {}",
+        prefix.display(),
+        old_virt_addr.display()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rewrite_read_outputs() {
+        let src = r#"Instance(
+    subnet: "out://vpc/subnets/a.ron[subnet_id]",
+    security_groups: ["out://vpc/sgs/web.ron[id]", "out://vpc/subnets/ab.ron[subnet_id]"],
+    tags: {"cidr": "subnet out://vpc/subnets/a.ron[cidr]"},
+)"#;
+
+        let rewritten = rewrite_read_outputs(src, Path::new("vpc/subnets/a.ron"), Path::new("vpc/subnets/public-a.ron"))
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(
+            rewritten,
+            r#"Instance(
+    subnet: "out://vpc/subnets/public-a.ron[subnet_id]",
+    security_groups: ["out://vpc/sgs/web.ron[id]", "out://vpc/subnets/ab.ron[subnet_id]"],
+    tags: {"cidr": "subnet out://vpc/subnets/public-a.ron[cidr]"},
+)"#
+        );
+
+        assert!(
+            rewrite_read_outputs(src, Path::new("vpc/subnets/b.ron"), Path::new("vpc/subnets/c.ron"))
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_check_rename_target() {
+        // An absolute prefix replaces .autoschematic/ in output map paths, keeping them in the temp dir too.
+        let prefix = std::env::temp_dir().join(format!("autoschematic-rename-test-{}", std::process::id()));
+        std::fs::create_dir_all(prefix.join("vpc")).unwrap();
+
+        std::fs::write(prefix.join("vpc/main.ron"), "()").unwrap();
+        OutputMapFile::write_link(&prefix, Path::new("vpc/vpc-0123.ron"), Path::new("vpc/main.ron")).unwrap();
+
+        assert!(check_rename_target(&prefix, Path::new("vpc/renamed.ron")).is_ok());
+        assert!(check_rename_target(&prefix, Path::new("vpc/main.ron")).is_err());
+        // No resource file, but an output map that the rename would overwrite.
+        assert!(check_rename_target(&prefix, Path::new("vpc/vpc-0123.ron")).is_err());
+
+        std::fs::remove_dir_all(&prefix).unwrap();
+    }
 }
@@ -2,6 +2,7 @@ use std::{
     collections::HashMap,
     path::{Path, PathBuf},
     sync::Arc,
+    time::Duration,
 };
 
 use dashmap::DashMap;
@@ -9,9 +10,10 @@ use dashmap::DashMap;
 use anyhow::{Result, bail};
 use autoschematic_core::{
     config::AutoschematicConfig,
+    config_policy::AutoschematicPolicyConfig,
     config_rbac::AutoschematicRbacConfig,
     connector::{DocIdent, FilterResponse},
-    connector_cache::{ConnectorCache, TopResponse},
+    connector_cache::{ConnectorCache, TopResponse, max_resident_memory_from_env},
     manifest::ConnectorManifest,
     ron::path_at::{self, Component},
     template::{self},
@@ -762,6 +764,11 @@ impl Backend {
                     res.push(diag);
                 }
             }
+            "autoschematic.policy.ron" => {
+                if let Some(diag) = self.check::<AutoschematicPolicyConfig>(text).await? {
+                    res.push(diag);
+                }
+            }
             // "autoschematic.lock.ron" => {
             //     if let Some(diag) = self.check::<AutoschematicLockfile>(text).await? {
             //         res.push(diag);
@@ -798,7 +805,9 @@ async fn main() -> Result<()> {
         .compact()
         .init();
 
-    let connector_cache = Arc::new(ConnectorCache::default());
+    let connector_cache = Arc::new(ConnectorCache::default().with_max_resident_memory(max_resident_memory_from_env()));
+    // Stop connectors that have gone idle, or that push the total memory of connectors over the limit.
+    let _evictor = connector_cache.spawn_evictor(Duration::from_secs(60));
 
     let (stdin, stdout) = (tokio::io::stdin(), tokio::io::stdout());
     let (service, socket) = LspService::new(|client| Backend {
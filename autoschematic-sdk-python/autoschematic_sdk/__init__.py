@@ -15,11 +15,13 @@ from .types import (
     DiagnosticSpan,
     DocIdent,
     EnumVariantIdent,
+    FieldDiff,
     FieldIdent,
     FilterResponse,
     GetDocResponse,
     GetResponse,
     OpExecResponse,
+    OpKind,
     PlanResponseElement,
     ReadOutput,
     Skeleton,
@@ -38,6 +40,8 @@ __all__ = [
     "FilterResponse",
     "GetResponse",
     "PlanResponseElement",
+    "OpKind",
+    "FieldDiff",
     "OpExecResponse",
     "ReadOutput",
     "VirtToPhyNotPresent",
@@ -24,7 +24,7 @@ _sym_db = _symbol_database.Default()
 
 
 
-DESCRIPTOR = _descriptor_pool.Default().AddSerializedFile(b'\n\x0f\x63onnector.proto\x12\tconnector\"\x07\n\x05\x45mpty\"\"\n\x0fVersionResponse\x12\x0f\n\x07version\x18\x01 \x01(\t\"\x1d\n\rFilterRequest\x12\x0c\n\x04\x61\x64\x64r\x18\x01 \x01(\t\"!\n\x0e\x46ilterResponse\x12\x0f\n\x07\x62itmask\x18\x01 \x01(\r\"\x1e\n\x0bListRequest\x12\x0f\n\x07subpath\x18\x01 \x01(\t\"\x1d\n\x0cListResponse\x12\r\n\x05\x61\x64\x64rs\x18\x01 \x03(\t\"\x1a\n\nGetRequest\x12\x0c\n\x04\x61\x64\x64r\x18\x01 \x01(\t\"\xb3\x01\n\x0bGetResponse\x12\x0e\n\x06\x65xists\x18\x01 \x01(\x08\x12\x1b\n\x13resource_definition\x18\x02 \x01(\x0c\x12\x11\n\tvirt_addr\x18\x03 \x01(\t\x12\x34\n\x07outputs\x18\x04 \x03(\x0b\x32#.connector.GetResponse.OutputsEntry\x1a.\n\x0cOutputsEntry\x12\x0b\n\x03key\x18\x01 \x01(\t\x12\r\n\x05value\x18\x02 \x01(\t:\x02\x38\x01\"=\n\x0bPlanRequest\x12\x0c\n\x04\x61\x64\x64r\x18\x01 \x01(\t\x12\x0f\n\x07\x63urrent\x18\x02 \x01(\x0c\x12\x0f\n\x07\x64\x65sired\x18\x03 \x01(\x0c\"^\n\x13PlanResponseElement\x12\x15\n\rop_definition\x18\x01 \x01(\t\x12\x16\n\x0ewrites_outputs\x18\x02 \x03(\t\x12\x18\n\x10\x66riendly_message\x18\x03 \x01(\t\";\n\x0cPlanResponse\x12+\n\x03ops\x18\x01 \x03(\x0b\x32\x1e.connector.PlanResponseElement\")\n\rOpExecRequest\x12\x0c\n\x04\x61\x64\x64r\x18\x01 \x01(\t\x12\n\n\x02op\x18\x02 \x01(\t\"\x93\x01\n\x0eOpExecResponse\x12\x37\n\x07outputs\x18\x01 \x03(\x0b\x32&.connector.OpExecResponse.OutputsEntry\x12\x18\n\x10\x66riendly_message\x18\x02 \x01(\t\x1a.\n\x0cOutputsEntry\x12\x0b\n\x03key\x18\x01 \x01(\t\x12\r\n\x05value\x18\x02 \x01(\t:\x02\x38\x01\"$\n\x14\x41\x64\x64rPhyToVirtRequest\x12\x0c\n\x04\x61\x64\x64r\x18\x01 \x01(\t\"<\n\x15\x41\x64\x64rPhyToVirtResponse\x12\x10\n\x08has_virt\x18\x01 \x01(\x08\x12\x11\n\tvirt_addr\x18\x02 \x01(\t\"$\n\x14\x41\x64\x64rVirtToPhyRequest\x12\x0c\n\x04\x61\x64\x64r\x18\x01 \x01(\t\"\'\n\nReadOutput\x12\x0c\n\x04\x61\x64\x64r\x18\x01 \x01(\t\x12\x0b\n\x03key\x18\x02 \x01(\t\"0\n\x08\x44\x65\x66\x65rred\x12$\n\x05reads\x18\x01 \x03(\x0b\x32\x15.connector.ReadOutput\"\x14\n\x04Path\x12\x0c\n\x04path\x18\x01 \x01(\t\"\xb8\x01\n\x15\x41\x64\x64rVirtToPhyResponse\x12\'\n\x0bnot_present\x18\x01 \x01(\x0b\x32\x10.connector.EmptyH\x00\x12\'\n\x08\x64\x65\x66\x65rred\x18\x02 \x01(\x0b\x32\x13.connector.DeferredH\x00\x12\"\n\x07present\x18\x03 \x01(\x0b\x32\x0f.connector.PathH\x00\x12\x1f\n\x04null\x18\x04 \x01(\x0b\x32\x0f.connector.PathH\x00\x42\x08\n\x06result\"$\n\x10SubpathsResponse\x12\x10\n\x08subpaths\x18\x01 \x03(\t\"&\n\x08Skeleton\x12\x0c\n\x04\x61\x64\x64r\x18\x01 \x01(\t\x12\x0c\n\x04\x62ody\x18\x02 \x01(\x0c\">\n\x14GetSkeletonsResponse\x12&\n\tskeletons\x18\x01 \x03(\x0b\x32\x13.connector.Skeleton\"\x1b\n\x0bStructIdent\x12\x0c\n\x04name\x18\x01 \x01(\t\"*\n\nFieldIdent\x12\x0e\n\x06parent\x18\x01 \x01(\t\x12\x0c\n\x04name\x18\x02 \x01(\t\"0\n\x10\x45numVariantIdent\x12\x0e\n\x06parent\x18\x01 \x01(\t\x12\x0c\n\x04name\x18\x02 \x01(\t\"\x9a\x01\n\x08\x44ocIdent\x12(\n\x06struct\x18\x01 \x01(\x0b\x32\x16.connector.StructIdentH\x00\x12&\n\x05\x66ield\x18\x02 \x01(\x0b\x32\x15.connector.FieldIdentH\x00\x12\x33\n\x0c\x65num_variant\x18\x03 \x01(\x0b\x32\x1b.connector.EnumVariantIdentH\x00\x42\x07\n\x05ident\"A\n\rGetDocRequest\x12\x0c\n\x04\x61\x64\x64r\x18\x01 \x01(\t\x12\"\n\x05ident\x18\x02 \x01(\x0b\x32\x13.connector.DocIdent\"Q\n\x0eGetDocResponse\x12\x0f\n\x07has_doc\x18\x01 \x01(\x08\x12\x0c\n\x04type\x18\x02 \x01(\t\x12\x10\n\x08markdown\x18\x03 \x01(\t\x12\x0e\n\x06\x66ields\x18\x04 \x03(\t\"/\n\tEqRequest\x12\x0c\n\x04\x61\x64\x64r\x18\x01 \x01(\t\x12\t\n\x01\x61\x18\x02 \x01(\x0c\x12\t\n\x01\x62\x18\x03 \x01(\x0c\"\x1b\n\nEqResponse\x12\r\n\x05\x65qual\x18\x01 \x01(\x08\"/\n\x12\x44iagnosticPosition\x12\x0c\n\x04line\x18\x01 \x01(\r\x12\x0b\n\x03\x63ol\x18\x02 \x01(\r\"j\n\x0e\x44iagnosticSpan\x12,\n\x05start\x18\x01 \x01(\x0b\x32\x1d.connector.DiagnosticPosition\x12*\n\x03\x65nd\x18\x02 \x01(\x0b\x32\x1d.connector.DiagnosticPosition\"X\n\nDiagnostic\x12\x10\n\x08severity\x18\x01 \x01(\r\x12\'\n\x04span\x18\x02 \x01(\x0b\x32\x19.connector.DiagnosticSpan\x12\x0f\n\x07message\x18\x03 \x01(\t\"&\n\x0b\x44iagRequest\x12\x0c\n\x04\x61\x64\x64r\x18\x01 \x01(\t\x12\t\n\x01\x61\x18\x02 \x01(\x0c\":\n\x0c\x44iagResponse\x12*\n\x0b\x64iagnostics\x18\x01 \x03(\x0b\x32\x15.connector.Diagnostic\"I\n\x0fTaskExecRequest\x12\x0c\n\x04\x61\x64\x64r\x18\x01 \x01(\t\x12\x0c\n\x04\x62ody\x18\x02 \x01(\x0c\x12\x0b\n\x03\x61rg\x18\x03 \x01(\x0c\x12\r\n\x05state\x18\x04 \x01(\x0c\"\xc3\x02\n\x10TaskExecResponse\x12\x12\n\nnext_state\x18\x01 \x01(\x0c\x12\x16\n\x0emodified_files\x18\x02 \x03(\t\x12\x39\n\x07outputs\x18\x03 \x03(\x0b\x32(.connector.TaskExecResponse.OutputsEntry\x12\x39\n\x07secrets\x18\x04 \x03(\x0b\x32(.connector.TaskExecResponse.SecretsEntry\x12\x18\n\x10\x66riendly_message\x18\x05 \x01(\t\x12\x13\n\x0b\x64\x65lay_until\x18\x06 \x01(\x04\x1a.\n\x0cOutputsEntry\x12\x0b\n\x03key\x18\x01 \x01(\t\x12\r\n\x05value\x18\x02 \x01(\t:\x02\x38\x01\x1a.\n\x0cSecretsEntry\x12\x0b\n\x03key\x18\x01 \x01(\t\x12\r\n\x05value\x18\x02 \x01(\t:\x02\x38\x01\"/\n\x0fUnbundleRequest\x12\x0c\n\x04\x61\x64\x64r\x18\x01 \x01(\t\x12\x0e\n\x06\x62undle\x18\x02 \x01(\x0c\"9\n\x17UnbundleResponseElement\x12\x0c\n\x04\x61\x64\x64r\x18\x01 \x01(\t\x12\x10\n\x08\x63ontents\x18\x02 \x01(\x0c\"G\n\x10UnbundleResponse\x12\x33\n\x07\x62undles\x18\x01 \x03(\x0b\x32\".connector.UnbundleResponseElement*Z\n\x12\x46ilterResponseType\x12\x08\n\x04NONE\x10\x00\x12\n\n\x06\x43ONFIG\x10\x01\x12\x0c\n\x08RESOURCE\x10\x02\x12\n\n\x06\x42UNDLE\x10\x04\x12\x08\n\x04TASK\x10\x08\x12\n\n\x06METRIC\x10\x10\x32\xf7\x07\n\tConnector\x12*\n\x04Init\x12\x10.connector.Empty\x1a\x10.connector.Empty\x12\x37\n\x07Version\x12\x10.connector.Empty\x1a\x1a.connector.VersionResponse\x12=\n\x06\x46ilter\x12\x18.connector.FilterRequest\x1a\x19.connector.FilterResponse\x12\x37\n\x04List\x12\x16.connector.ListRequest\x1a\x17.connector.ListResponse\x12\x39\n\x08Subpaths\x12\x10.connector.Empty\x1a\x1b.connector.SubpathsResponse\x12\x34\n\x03Get\x12\x15.connector.GetRequest\x1a\x16.connector.GetResponse\x12\x37\n\x04Plan\x12\x16.connector.PlanRequest\x1a\x17.connector.PlanResponse\x12=\n\x06OpExec\x12\x18.connector.OpExecRequest\x1a\x19.connector.OpExecResponse\x12R\n\rAddrVirtToPhy\x12\x1f.connector.AddrVirtToPhyRequest\x1a .connector.AddrVirtToPhyResponse\x12R\n\rAddrPhyToVirt\x12\x1f.connector.AddrPhyToVirtRequest\x1a .connector.AddrPhyToVirtResponse\x12\x41\n\x0cGetSkeletons\x12\x10.connector.Empty\x1a\x1f.connector.GetSkeletonsResponse\x12\x43\n\x0cGetDocstring\x12\x18.connector.GetDocRequest\x1a\x19.connector.GetDocResponse\x12\x31\n\x02\x45q\x12\x14.connector.EqRequest\x1a\x15.connector.EqResponse\x12\x37\n\x04\x44iag\x12\x16.connector.DiagRequest\x1a\x17.connector.DiagResponse\x12\x43\n\x08TaskExec\x12\x1a.connector.TaskExecRequest\x1a\x1b.connector.TaskExecResponse\x12\x43\n\x08Unbundle\x12\x1a.connector.UnbundleRequest\x1a\x1b.connector.UnbundleResponseb\x06proto3')
+DESCRIPTOR = _descriptor_pool.Default().AddSerializedFile(b'\n\x0f\x63onnector.proto\x12\tconnector\"\x07\n\x05\x45mpty\"\"\n\x0fVersionResponse\x12\x0f\n\x07version\x18\x01 \x01(\t\"\x1d\n\rFilterRequest\x12\x0c\n\x04\x61\x64\x64r\x18\x01 \x01(\t\"!\n\x0e\x46ilterResponse\x12\x0f\n\x07\x62itmask\x18\x01 \x01(\r\"\x1e\n\x0bListRequest\x12\x0f\n\x07subpath\x18\x01 \x01(\t\"\x1d\n\x0cListResponse\x12\r\n\x05\x61\x64\x64rs\x18\x01 \x03(\t\"\x1a\n\nGetRequest\x12\x0c\n\x04\x61\x64\x64r\x18\x01 \x01(\t\"\xb3\x01\n\x0bGetResponse\x12\x0e\n\x06\x65xists\x18\x01 \x01(\x08\x12\x1b\n\x13resource_definition\x18\x02 \x01(\x0c\x12\x11\n\tvirt_addr\x18\x03 \x01(\t\x12\x34\n\x07outputs\x18\x04 \x03(\x0b\x32#.connector.GetResponse.OutputsEntry\x1a.\n\x0cOutputsEntry\x12\x0b\n\x03key\x18\x01 \x01(\t\x12\r\n\x05value\x18\x02 \x01(\t:\x02\x38\x01\"=\n\x0bPlanRequest\x12\x0c\n\x04\x61\x64\x64r\x18\x01 \x01(\t\x12\x0f\n\x07\x63urrent\x18\x02 \x01(\x0c\x12\x0f\n\x07\x64\x65sired\x18\x03 \x01(\x0c\"8\n\tFieldDiff\x12\x0c\n\x04path\x18\x01 \x01(\t\x12\x0e\n\x06\x62\x65\x66ore\x18\x02 \x01(\t\x12\r\n\x05\x61\x66ter\x18\x03 \x01(\t\"\xbc\x01\n\x13PlanResponseElement\x12\x15\n\rop_definition\x18\x01 \x01(\t\x12\x16\n\x0ewrites_outputs\x18\x02 \x03(\t\x12\x18\n\x10\x66riendly_message\x18\x03 \x01(\t\x12\"\n\x07op_kind\x18\x04 \x01(\x0e\x32\x11.connector.OpKind\x12\x13\n\x0b\x64\x65structive\x18\x05 \x01(\x08\x12#\n\x05\x64iffs\x18\x06 \x03(\x0b\x32\x14.connector.FieldDiff\";\n\x0cPlanResponse\x12+\n\x03ops\x18\x01 \x03(\x0b\x32\x1e.connector.PlanResponseElement\")\n\rOpExecRequest\x12\x0c\n\x04\x61\x64\x64r\x18\x01 \x01(\t\x12\n\n\x02op\x18\x02 \x01(\t\"\x93\x01\n\x0eOpExecResponse\x12\x37\n\x07outputs\x18\x01 \x03(\x0b\x32&.connector.OpExecResponse.OutputsEntry\x12\x18\n\x10\x66riendly_message\x18\x02 \x01(\t\x1a.\n\x0cOutputsEntry\x12\x0b\n\x03key\x18\x01 \x01(\t\x12\r\n\x05value\x18\x02 \x01(\t:\x02\x38\x01\"$\n\x14\x41\x64\x64rPhyToVirtRequest\x12\x0c\n\x04\x61\x64\x64r\x18\x01 \x01(\t\"<\n\x15\x41\x64\x64rPhyToVirtResponse\x12\x10\n\x08has_virt\x18\x01 \x01(\x08\x12\x11\n\tvirt_addr\x18\x02 \x01(\t\"$\n\x14\x41\x64\x64rVirtToPhyRequest\x12\x0c\n\x04\x61\x64\x64r\x18\x01 \x01(\t\"\'\n\nReadOutput\x12\x0c\n\x04\x61\x64\x64r\x18\x01 \x01(\t\x12\x0b\n\x03key\x18\x02 \x01(\t\"0\n\x08\x44\x65\x66\x65rred\x12$\n\x05reads\x18\x01 \x03(\x0b\x32\x15.connector.ReadOutput\"\x14\n\x04Path\x12\x0c\n\x04path\x18\x01 \x01(\t\"\xb8\x01\n\x15\x41\x64\x64rVirtToPhyResponse\x12\'\n\x0bnot_present\x18\x01 \x01(\x0b\x32\x10.connector.EmptyH\x00\x12\'\n\x08\x64\x65\x66\x65rred\x18\x02 \x01(\x0b\x32\x13.connector.DeferredH\x00\x12\"\n\x07present\x18\x03 \x01(\x0b\x32\x0f.connector.PathH\x00\x12\x1f\n\x04null\x18\x04 \x01(\x0b\x32\x0f.connector.PathH\x00\x42\x08\n\x06result\"$\n\x10SubpathsResponse\x12\x10\n\x08subpaths\x18\x01 \x03(\t\"&\n\x08Skeleton\x12\x0c\n\x04\x61\x64\x64r\x18\x01 \x01(\t\x12\x0c\n\x04\x62ody\x18\x02 \x01(\x0c\">\n\x14GetSkeletonsResponse\x12&\n\tskeletons\x18\x01 \x03(\x0b\x32\x13.connector.Skeleton\"\x1b\n\x0bStructIdent\x12\x0c\n\x04name\x18\x01 \x01(\t\"*\n\nFieldIdent\x12\x0e\n\x06parent\x18\x01 \x01(\t\x12\x0c\n\x04name\x18\x02 \x01(\t\"0\n\x10\x45numVariantIdent\x12\x0e\n\x06parent\x18\x01 \x01(\t\x12\x0c\n\x04name\x18\x02 \x01(\t\"\x9a\x01\n\x08\x44ocIdent\x12(\n\x06struct\x18\x01 \x01(\x0b\x32\x16.connector.StructIdentH\x00\x12&\n\x05\x66ield\x18\x02 \x01(\x0b\x32\x15.connector.FieldIdentH\x00\x12\x33\n\x0c\x65num_variant\x18\x03 \x01(\x0b\x32\x1b.connector.EnumVariantIdentH\x00\x42\x07\n\x05ident\"A\n\rGetDocRequest\x12\x0c\n\x04\x61\x64\x64r\x18\x01 \x01(\t\x12\"\n\x05ident\x18\x02 \x01(\x0b\x32\x13.connector.DocIdent\"Q\n\x0eGetDocResponse\x12\x0f\n\x07has_doc\x18\x01 \x01(\x08\x12\x0c\n\x04type\x18\x02 \x01(\t\x12\x10\n\x08markdown\x18\x03 \x01(\t\x12\x0e\n\x06\x66ields\x18\x04 \x03(\t\"/\n\tEqRequest\x12\x0c\n\x04\x61\x64\x64r\x18\x01 \x01(\t\x12\t\n\x01\x61\x18\x02 \x01(\x0c\x12\t\n\x01\x62\x18\x03 \x01(\x0c\"\x1b\n\nEqResponse\x12\r\n\x05\x65qual\x18\x01 \x01(\x08\"/\n\x12\x44iagnosticPosition\x12\x0c\n\x04line\x18\x01 \x01(\r\x12\x0b\n\x03\x63ol\x18\x02 \x01(\r\"j\n\x0e\x44iagnosticSpan\x12,\n\x05start\x18\x01 \x01(\x0b\x32\x1d.connector.DiagnosticPosition\x12*\n\x03\x65nd\x18\x02 \x01(\x0b\x32\x1d.connector.DiagnosticPosition\"X\n\nDiagnostic\x12\x10\n\x08severity\x18\x01 \x01(\r\x12\'\n\x04span\x18\x02 \x01(\x0b\x32\x19.connector.DiagnosticSpan\x12\x0f\n\x07message\x18\x03 \x01(\t\"&\n\x0b\x44iagRequest\x12\x0c\n\x04\x61\x64\x64r\x18\x01 \x01(\t\x12\t\n\x01\x61\x18\x02 \x01(\x0c\":\n\x0c\x44iagResponse\x12*\n\x0b\x64iagnostics\x18\x01 \x03(\x0b\x32\x15.connector.Diagnostic\"I\n\x0fTaskExecRequest\x12\x0c\n\x04\x61\x64\x64r\x18\x01 \x01(\t\x12\x0c\n\x04\x62ody\x18\x02 \x01(\x0c\x12\x0b\n\x03\x61rg\x18\x03 \x01(\x0c\x12\r\n\x05state\x18\x04 \x01(\x0c\"\xc3\x02\n\x10TaskExecResponse\x12\x12\n\nnext_state\x18\x01 \x01(\x0c\x12\x16\n\x0emodified_files\x18\x02 \x03(\t\x12\x39\n\x07outputs\x18\x03 \x03(\x0b\x32(.connector.TaskExecResponse.OutputsEntry\x12\x39\n\x07secrets\x18\x04 \x03(\x0b\x32(.connector.TaskExecResponse.SecretsEntry\x12\x18\n\x10\x66riendly_message\x18\x05 \x01(\t\x12\x13\n\x0b\x64\x65lay_until\x18\x06 \x01(\x04\x1a.\n\x0cOutputsEntry\x12\x0b\n\x03key\x18\x01 \x01(\t\x12\r\n\x05value\x18\x02 \x01(\t:\x02\x38\x01\x1a.\n\x0cSecretsEntry\x12\x0b\n\x03key\x18\x01 \x01(\t\x12\r\n\x05value\x18\x02 \x01(\t:\x02\x38\x01\"/\n\x0fUnbundleRequest\x12\x0c\n\x04\x61\x64\x64r\x18\x01 \x01(\t\x12\x0e\n\x06\x62undle\x18\x02 \x01(\x0c\"9\n\x17UnbundleResponseElement\x12\x0c\n\x04\x61\x64\x64r\x18\x01 \x01(\t\x12\x10\n\x08\x63ontents\x18\x02 \x01(\x0c\"G\n\x10UnbundleResponse\x12\x33\n\x07\x62undles\x18\x01 \x03(\x0b\x32\".connector.UnbundleResponseElement*Z\n\x12\x46ilterResponseType\x12\x08\n\x04NONE\x10\x00\x12\n\n\x06\x43ONFIG\x10\x01\x12\x0c\n\x08RESOURCE\x10\x02\x12\n\n\x06\x42UNDLE\x10\x04\x12\x08\n\x04TASK\x10\x08\x12\n\n\x06METRIC\x10\x10*D\n\x06OpKind\x12\t\n\x05OTHER\x10\x00\x12\n\n\x06\x43REATE\x10\x01\x12\n\n\x06UPDATE\x10\x02\x12\n\n\x06\x44\x45LETE\x10\x03\x12\x0b\n\x07REPLACE\x10\x04\x32\xf7\x07\n\tConnector\x12*\n\x04Init\x12\x10.connector.Empty\x1a\x10.connector.Empty\x12\x37\n\x07Version\x12\x10.connector.Empty\x1a\x1a.connector.VersionResponse\x12=\n\x06\x46ilter\x12\x18.connector.FilterRequest\x1a\x19.connector.FilterResponse\x12\x37\n\x04List\x12\x16.connector.ListRequest\x1a\x17.connector.ListResponse\x12\x39\n\x08Subpaths\x12\x10.connector.Empty\x1a\x1b.connector.SubpathsResponse\x12\x34\n\x03Get\x12\x15.connector.GetRequest\x1a\x16.connector.GetResponse\x12\x37\n\x04Plan\x12\x16.connector.PlanRequest\x1a\x17.connector.PlanResponse\x12=\n\x06OpExec\x12\x18.connector.OpExecRequest\x1a\x19.connector.OpExecResponse\x12R\n\rAddrVirtToPhy\x12\x1f.connector.AddrVirtToPhyRequest\x1a .connector.AddrVirtToPhyResponse\x12R\n\rAddrPhyToVirt\x12\x1f.connector.AddrPhyToVirtRequest\x1a .connector.AddrPhyToVirtResponse\x12\x41\n\x0cGetSkeletons\x12\x10.connector.Empty\x1a\x1f.connector.GetSkeletonsResponse\x12\x43\n\x0cGetDocstring\x12\x18.connector.GetDocRequest\x1a\x19.connector.GetDocResponse\x12\x31\n\x02\x45q\x12\x14.connector.EqRequest\x1a\x15.connector.EqResponse\x12\x37\n\x04\x44iag\x12\x16.connector.DiagRequest\x1a\x17.connector.DiagResponse\x12\x43\n\x08TaskExec\x12\x1a.connector.TaskExecRequest\x1a\x1b.connector.TaskExecResponse\x12\x43\n\x08Unbundle\x12\x1a.connector.UnbundleRequest\x1a\x1b.connector.UnbundleResponseb\x06proto3')
 
 _globals = globals()
 _builder.BuildMessageAndEnumDescriptors(DESCRIPTOR, _globals)
@@ -39,8 +39,10 @@ if not _descriptor._USE_C_DESCRIPTORS:
   _globals['_TASKEXECRESPONSE_OUTPUTSENTRY']._serialized_options = b'8\001'
   _globals['_TASKEXECRESPONSE_SECRETSENTRY']._loaded_options = None
   _globals['_TASKEXECRESPONSE_SECRETSENTRY']._serialized_options = b'8\001'
-  _globals['_FILTERRESPONSETYPE']._serialized_start=2844
-  _globals['_FILTERRESPONSETYPE']._serialized_end=2934
+  _globals['_FILTERRESPONSETYPE']._serialized_start=2997
+  _globals['_FILTERRESPONSETYPE']._serialized_end=3087
+  _globals['_OPKIND']._serialized_start=3089
+  _globals['_OPKIND']._serialized_end=3157
   _globals['_EMPTY']._serialized_start=30
   _globals['_EMPTY']._serialized_end=37
   _globals['_VERSIONRESPONSE']._serialized_start=39
@@ -61,76 +63,78 @@ if not _descriptor._USE_C_DESCRIPTORS:
   _globals['_GETRESPONSE_OUTPUTSENTRY']._serialized_end=412
   _globals['_PLANREQUEST']._serialized_start=414
   _globals['_PLANREQUEST']._serialized_end=475
-  _globals['_PLANRESPONSEELEMENT']._serialized_start=477
-  _globals['_PLANRESPONSEELEMENT']._serialized_end=571
-  _globals['_PLANRESPONSE']._serialized_start=573
-  _globals['_PLANRESPONSE']._serialized_end=632
-  _globals['_OPEXECREQUEST']._serialized_start=634
-  _globals['_OPEXECREQUEST']._serialized_end=675
-  _globals['_OPEXECRESPONSE']._serialized_start=678
-  _globals['_OPEXECRESPONSE']._serialized_end=825
+  _globals['_FIELDDIFF']._serialized_start=477
+  _globals['_FIELDDIFF']._serialized_end=533
+  _globals['_PLANRESPONSEELEMENT']._serialized_start=536
+  _globals['_PLANRESPONSEELEMENT']._serialized_end=724
+  _globals['_PLANRESPONSE']._serialized_start=726
+  _globals['_PLANRESPONSE']._serialized_end=785
+  _globals['_OPEXECREQUEST']._serialized_start=787
+  _globals['_OPEXECREQUEST']._serialized_end=828
+  _globals['_OPEXECRESPONSE']._serialized_start=831
+  _globals['_OPEXECRESPONSE']._serialized_end=978
   _globals['_OPEXECRESPONSE_OUTPUTSENTRY']._serialized_start=366
   _globals['_OPEXECRESPONSE_OUTPUTSENTRY']._serialized_end=412
-  _globals['_ADDRPHYTOVIRTREQUEST']._serialized_start=827
-  _globals['_ADDRPHYTOVIRTREQUEST']._serialized_end=863
-  _globals['_ADDRPHYTOVIRTRESPONSE']._serialized_start=865
-  _globals['_ADDRPHYTOVIRTRESPONSE']._serialized_end=925
-  _globals['_ADDRVIRTTOPHYREQUEST']._serialized_start=927
-  _globals['_ADDRVIRTTOPHYREQUEST']._serialized_end=963
-  _globals['_READOUTPUT']._serialized_start=965
-  _globals['_READOUTPUT']._serialized_end=1004
-  _globals['_DEFERRED']._serialized_start=1006
-  _globals['_DEFERRED']._serialized_end=1054
-  _globals['_PATH']._serialized_start=1056
-  _globals['_PATH']._serialized_end=1076
-  _globals['_ADDRVIRTTOPHYRESPONSE']._serialized_start=1079
-  _globals['_ADDRVIRTTOPHYRESPONSE']._serialized_end=1263
-  _globals['_SUBPATHSRESPONSE']._serialized_start=1265
-  _globals['_SUBPATHSRESPONSE']._serialized_end=1301
-  _globals['_SKELETON']._serialized_start=1303
-  _globals['_SKELETON']._serialized_end=1341
-  _globals['_GETSKELETONSRESPONSE']._serialized_start=1343
-  _globals['_GETSKELETONSRESPONSE']._serialized_end=1405
-  _globals['_STRUCTIDENT']._serialized_start=1407
-  _globals['_STRUCTIDENT']._serialized_end=1434
-  _globals['_FIELDIDENT']._serialized_start=1436
-  _globals['_FIELDIDENT']._serialized_end=1478
-  _globals['_ENUMVARIANTIDENT']._serialized_start=1480
-  _globals['_ENUMVARIANTIDENT']._serialized_end=1528
-  _globals['_DOCIDENT']._serialized_start=1531
-  _globals['_DOCIDENT']._serialized_end=1685
-  _globals['_GETDOCREQUEST']._serialized_start=1687
-  _globals['_GETDOCREQUEST']._serialized_end=1752
-  _globals['_GETDOCRESPONSE']._serialized_start=1754
-  _globals['_GETDOCRESPONSE']._serialized_end=1835
-  _globals['_EQREQUEST']._serialized_start=1837
-  _globals['_EQREQUEST']._serialized_end=1884
-  _globals['_EQRESPONSE']._serialized_start=1886
-  _globals['_EQRESPONSE']._serialized_end=1913
-  _globals['_DIAGNOSTICPOSITION']._serialized_start=1915
-  _globals['_DIAGNOSTICPOSITION']._serialized_end=1962
-  _globals['_DIAGNOSTICSPAN']._serialized_start=1964
-  _globals['_DIAGNOSTICSPAN']._serialized_end=2070
-  _globals['_DIAGNOSTIC']._serialized_start=2072
-  _globals['_DIAGNOSTIC']._serialized_end=2160
-  _globals['_DIAGREQUEST']._serialized_start=2162
-  _globals['_DIAGREQUEST']._serialized_end=2200
-  _globals['_DIAGRESPONSE']._serialized_start=2202
-  _globals['_DIAGRESPONSE']._serialized_end=2260
-  _globals['_TASKEXECREQUEST']._serialized_start=2262
-  _globals['_TASKEXECREQUEST']._serialized_end=2335
-  _globals['_TASKEXECRESPONSE']._serialized_start=2338
-  _globals['_TASKEXECRESPONSE']._serialized_end=2661
+  _globals['_ADDRPHYTOVIRTREQUEST']._serialized_start=980
+  _globals['_ADDRPHYTOVIRTREQUEST']._serialized_end=1016
+  _globals['_ADDRPHYTOVIRTRESPONSE']._serialized_start=1018
+  _globals['_ADDRPHYTOVIRTRESPONSE']._serialized_end=1078
+  _globals['_ADDRVIRTTOPHYREQUEST']._serialized_start=1080
+  _globals['_ADDRVIRTTOPHYREQUEST']._serialized_end=1116
+  _globals['_READOUTPUT']._serialized_start=1118
+  _globals['_READOUTPUT']._serialized_end=1157
+  _globals['_DEFERRED']._serialized_start=1159
+  _globals['_DEFERRED']._serialized_end=1207
+  _globals['_PATH']._serialized_start=1209
+  _globals['_PATH']._serialized_end=1229
+  _globals['_ADDRVIRTTOPHYRESPONSE']._serialized_start=1232
+  _globals['_ADDRVIRTTOPHYRESPONSE']._serialized_end=1416
+  _globals['_SUBPATHSRESPONSE']._serialized_start=1418
+  _globals['_SUBPATHSRESPONSE']._serialized_end=1454
+  _globals['_SKELETON']._serialized_start=1456
+  _globals['_SKELETON']._serialized_end=1494
+  _globals['_GETSKELETONSRESPONSE']._serialized_start=1496
+  _globals['_GETSKELETONSRESPONSE']._serialized_end=1558
+  _globals['_STRUCTIDENT']._serialized_start=1560
+  _globals['_STRUCTIDENT']._serialized_end=1587
+  _globals['_FIELDIDENT']._serialized_start=1589
+  _globals['_FIELDIDENT']._serialized_end=1631
+  _globals['_ENUMVARIANTIDENT']._serialized_start=1633
+  _globals['_ENUMVARIANTIDENT']._serialized_end=1681
+  _globals['_DOCIDENT']._serialized_start=1684
+  _globals['_DOCIDENT']._serialized_end=1838
+  _globals['_GETDOCREQUEST']._serialized_start=1840
+  _globals['_GETDOCREQUEST']._serialized_end=1905
+  _globals['_GETDOCRESPONSE']._serialized_start=1907
+  _globals['_GETDOCRESPONSE']._serialized_end=1988
+  _globals['_EQREQUEST']._serialized_start=1990
+  _globals['_EQREQUEST']._serialized_end=2037
+  _globals['_EQRESPONSE']._serialized_start=2039
+  _globals['_EQRESPONSE']._serialized_end=2066
+  _globals['_DIAGNOSTICPOSITION']._serialized_start=2068
+  _globals['_DIAGNOSTICPOSITION']._serialized_end=2115
+  _globals['_DIAGNOSTICSPAN']._serialized_start=2117
+  _globals['_DIAGNOSTICSPAN']._serialized_end=2223
+  _globals['_DIAGNOSTIC']._serialized_start=2225
+  _globals['_DIAGNOSTIC']._serialized_end=2313
+  _globals['_DIAGREQUEST']._serialized_start=2315
+  _globals['_DIAGREQUEST']._serialized_end=2353
+  _globals['_DIAGRESPONSE']._serialized_start=2355
+  _globals['_DIAGRESPONSE']._serialized_end=2413
+  _globals['_TASKEXECREQUEST']._serialized_start=2415
+  _globals['_TASKEXECREQUEST']._serialized_end=2488
+  _globals['_TASKEXECRESPONSE']._serialized_start=2491
+  _globals['_TASKEXECRESPONSE']._serialized_end=2814
   _globals['_TASKEXECRESPONSE_OUTPUTSENTRY']._serialized_start=366
   _globals['_TASKEXECRESPONSE_OUTPUTSENTRY']._serialized_end=412
-  _globals['_TASKEXECRESPONSE_SECRETSENTRY']._serialized_start=2615
-  _globals['_TASKEXECRESPONSE_SECRETSENTRY']._serialized_end=2661
-  _globals['_UNBUNDLEREQUEST']._serialized_start=2663
-  _globals['_UNBUNDLEREQUEST']._serialized_end=2710
-  _globals['_UNBUNDLERESPONSEELEMENT']._serialized_start=2712
-  _globals['_UNBUNDLERESPONSEELEMENT']._serialized_end=2769
-  _globals['_UNBUNDLERESPONSE']._serialized_start=2771
-  _globals['_UNBUNDLERESPONSE']._serialized_end=2842
-  _globals['_CONNECTOR']._serialized_start=2937
-  _globals['_CONNECTOR']._serialized_end=3952
+  _globals['_TASKEXECRESPONSE_SECRETSENTRY']._serialized_start=2768
+  _globals['_TASKEXECRESPONSE_SECRETSENTRY']._serialized_end=2814
+  _globals['_UNBUNDLEREQUEST']._serialized_start=2816
+  _globals['_UNBUNDLEREQUEST']._serialized_end=2863
+  _globals['_UNBUNDLERESPONSEELEMENT']._serialized_start=2865
+  _globals['_UNBUNDLERESPONSEELEMENT']._serialized_end=2922
+  _globals['_UNBUNDLERESPONSE']._serialized_start=2924
+  _globals['_UNBUNDLERESPONSE']._serialized_end=2995
+  _globals['_CONNECTOR']._serialized_start=3160
+  _globals['_CONNECTOR']._serialized_end=4175
 # @@protoc_insertion_point(module_scope)
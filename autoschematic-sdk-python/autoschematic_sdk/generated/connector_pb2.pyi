@@ -15,12 +15,25 @@ class FilterResponseType(int, metaclass=_enum_type_wrapper.EnumTypeWrapper):
     BUNDLE: _ClassVar[FilterResponseType]
     TASK: _ClassVar[FilterResponseType]
     METRIC: _ClassVar[FilterResponseType]
+
+class OpKind(int, metaclass=_enum_type_wrapper.EnumTypeWrapper):
+    __slots__ = ()
+    OTHER: _ClassVar[OpKind]
+    CREATE: _ClassVar[OpKind]
+    UPDATE: _ClassVar[OpKind]
+    DELETE: _ClassVar[OpKind]
+    REPLACE: _ClassVar[OpKind]
 NONE: FilterResponseType
 CONFIG: FilterResponseType
 RESOURCE: FilterResponseType
 BUNDLE: FilterResponseType
 TASK: FilterResponseType
 METRIC: FilterResponseType
+OTHER: OpKind
+CREATE: OpKind
+UPDATE: OpKind
+DELETE: OpKind
+REPLACE: OpKind
 
 class Empty(_message.Message):
     __slots__ = ()
@@ -91,15 +104,31 @@ class PlanRequest(_message.Message):
     desired: bytes
     def __init__(self, addr: _Optional[str] = ..., current: _Optional[bytes] = ..., desired: _Optional[bytes] = ...) -> None: ...
 
+class FieldDiff(_message.Message):
+    __slots__ = ("path", "before", "after")
+    PATH_FIELD_NUMBER: _ClassVar[int]
+    BEFORE_FIELD_NUMBER: _ClassVar[int]
+    AFTER_FIELD_NUMBER: _ClassVar[int]
+    path: str
+    before: str
+    after: str
+    def __init__(self, path: _Optional[str] = ..., before: _Optional[str] = ..., after: _Optional[str] = ...) -> None: ...
+
 class PlanResponseElement(_message.Message):
-    __slots__ = ("op_definition", "writes_outputs", "friendly_message")
+    __slots__ = ("op_definition", "writes_outputs", "friendly_message", "op_kind", "destructive", "diffs")
     OP_DEFINITION_FIELD_NUMBER: _ClassVar[int]
     WRITES_OUTPUTS_FIELD_NUMBER: _ClassVar[int]
     FRIENDLY_MESSAGE_FIELD_NUMBER: _ClassVar[int]
+    OP_KIND_FIELD_NUMBER: _ClassVar[int]
+    DESTRUCTIVE_FIELD_NUMBER: _ClassVar[int]
+    DIFFS_FIELD_NUMBER: _ClassVar[int]
     op_definition: str
     writes_outputs: _containers.RepeatedScalarFieldContainer[str]
     friendly_message: str
-    def __init__(self, op_definition: _Optional[str] = ..., writes_outputs: _Optional[_Iterable[str]] = ..., friendly_message: _Optional[str] = ...) -> None: ...
+    op_kind: OpKind
+    destructive: bool
+    diffs: _containers.RepeatedCompositeFieldContainer[FieldDiff]
+    def __init__(self, op_definition: _Optional[str] = ..., writes_outputs: _Optional[_Iterable[str]] = ..., friendly_message: _Optional[str] = ..., op_kind: _Optional[_Union[OpKind, str]] = ..., destructive: bool = ..., diffs: _Optional[_Iterable[_Union[FieldDiff, _Mapping]]] = ...) -> None: ...
 
 class PlanResponse(_message.Message):
     __slots__ = ("ops",)
@@ -76,6 +76,16 @@ class _ConnectorServicer(connector_pb2_grpc.ConnectorServicer):
                         op_definition=op.op_definition,
                         writes_outputs=op.writes_outputs,
                         friendly_message=op.friendly_message,
+                        op_kind=int(op.op_kind),
+                        destructive=op.destructive,
+                        diffs=[
+                            connector_pb2.FieldDiff(
+                                path=d.path,
+                                before=d.before or "",
+                                after=d.after or "",
+                            )
+                            for d in op.diffs
+                        ],
                     )
                     for op in ops
                 ]
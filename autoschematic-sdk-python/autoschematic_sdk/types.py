@@ -2,7 +2,7 @@ from __future__ import annotations
 
 from abc import ABC, abstractmethod
 from dataclasses import dataclass, field
-from enum import IntFlag
+from enum import IntEnum, IntFlag
 
 
 class FilterResponse(IntFlag):
@@ -22,11 +22,29 @@ class GetResponse:
     outputs: dict[str, str] | None = None
 
 
+class OpKind(IntEnum):
+    OTHER = 0
+    CREATE = 1
+    UPDATE = 2
+    DELETE = 3
+    REPLACE = 4
+
+
+@dataclass
+class FieldDiff:
+    path: str
+    before: str | None = None
+    after: str | None = None
+
+
 @dataclass
 class PlanResponseElement:
     op_definition: str
     writes_outputs: list[str] = field(default_factory=list)
     friendly_message: str = ""
+    op_kind: OpKind = OpKind.OTHER
+    destructive: bool = False
+    diffs: list[FieldDiff] = field(default_factory=list)
 
 
 @dataclass
@@ -10,6 +10,7 @@ from autoschematic_sdk import (
     GetResponse,
     PlanResponseElement,
     OpExecResponse,
+    OpKind,
     connector_main,
     match_addr,
     InvalidAddr,
@@ -124,6 +125,7 @@ class DummyConnector(Connector):
                         op_definition='{{"action": "delete"}}',
                         writes_outputs=[],
                         friendly_message="Delete widget",
+                        op_kind=OpKind.DELETE,
                     )
                 ]
             # current == None implies the resource at `addr` doesn't yet exist
@@ -133,6 +135,7 @@ class DummyConnector(Connector):
                         op_definition='{{"action": "create"}}',
                         writes_outputs=["widget_id"],
                         friendly_message="Create widget",
+                        op_kind=OpKind.CREATE,
                     )
                 ]
             case [current, desired] if current != desired:
@@ -141,6 +144,7 @@ class DummyConnector(Connector):
                         op_definition='{{"action": "update"}}',
                         writes_outputs=["widget_id"],
                         friendly_message="Update widget",
+                        op_kind=OpKind.UPDATE,
                     )
                 ]
 
@@ -21,6 +21,7 @@ from autoschematic_sdk import (
     GetResponse,
     PlanResponseElement,
     OpExecResponse,
+    OpKind,
     VirtToPhyDeferred,
     VirtToPhyNotPresent,
     VirtToPhyNull,
@@ -220,6 +221,7 @@ class GrafanaConnector(Connector):
                         op_definition=json.dumps({"action": "delete", "type": resource_type}),
                         writes_outputs=[],
                         friendly_message=f"Delete {resource_type}",
+                        op_kind=OpKind.DELETE,
                     )
                 ]
             case [None, desired]:
@@ -232,6 +234,7 @@ class GrafanaConnector(Connector):
                         }),
                         writes_outputs=["id"],
                         friendly_message=f"Create {resource_type}",
+                        op_kind=OpKind.CREATE,
                     )
                 ]
             case [current, desired] if current != desired:
@@ -244,6 +247,7 @@ class GrafanaConnector(Connector):
                         }),
                         writes_outputs=["id"],
                         friendly_message=f"Update {resource_type}",
+                        op_kind=OpKind.UPDATE,
                     )
                 ]
 
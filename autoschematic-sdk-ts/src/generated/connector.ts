@@ -72,6 +72,57 @@ export function filterResponseTypeToJSON(object: FilterResponseType): string {
   }
 }
 
+export enum OpKind {
+  OTHER = 0,
+  CREATE = 1,
+  UPDATE = 2,
+  DELETE = 3,
+  REPLACE = 4,
+  UNRECOGNIZED = -1,
+}
+
+export function opKindFromJSON(object: any): OpKind {
+  switch (object) {
+    case 0:
+    case "OTHER":
+      return OpKind.OTHER;
+    case 1:
+    case "CREATE":
+      return OpKind.CREATE;
+    case 2:
+    case "UPDATE":
+      return OpKind.UPDATE;
+    case 3:
+    case "DELETE":
+      return OpKind.DELETE;
+    case 4:
+    case "REPLACE":
+      return OpKind.REPLACE;
+    case -1:
+    case "UNRECOGNIZED":
+    default:
+      return OpKind.UNRECOGNIZED;
+  }
+}
+
+export function opKindToJSON(object: OpKind): string {
+  switch (object) {
+    case OpKind.OTHER:
+      return "OTHER";
+    case OpKind.CREATE:
+      return "CREATE";
+    case OpKind.UPDATE:
+      return "UPDATE";
+    case OpKind.DELETE:
+      return "DELETE";
+    case OpKind.REPLACE:
+      return "REPLACE";
+    case OpKind.UNRECOGNIZED:
+    default:
+      return "UNRECOGNIZED";
+  }
+}
+
 /** / A generic empty message (was google.protobuf.Empty) */
 export interface Empty {
 }
@@ -120,10 +171,21 @@ export interface PlanRequest {
   desired: Uint8Array;
 }
 
+export interface FieldDiff {
+  path: string;
+  /** empty means “none” */
+  before: string;
+  /** empty means “none” */
+  after: string;
+}
+
 export interface PlanResponseElement {
   opDefinition: string;
   writesOutputs: string[];
   friendlyMessage: string;
+  opKind: OpKind;
+  destructive: boolean;
+  diffs: FieldDiff[];
 }
 
 export interface PlanResponse {
@@ -885,8 +947,100 @@ export const PlanRequest: MessageFns<PlanRequest> = {
   },
 };
 
+function createBaseFieldDiff(): FieldDiff {
+  return { path: "", before: "", after: "" };
+}
+
+export const FieldDiff: MessageFns<FieldDiff> = {
+  encode(message: FieldDiff, writer: BinaryWriter = new BinaryWriter()): BinaryWriter {
+    if (message.path !== "") {
+      writer.uint32(10).string(message.path);
+    }
+    if (message.before !== "") {
+      writer.uint32(18).string(message.before);
+    }
+    if (message.after !== "") {
+      writer.uint32(26).string(message.after);
+    }
+    return writer;
+  },
+
+  decode(input: BinaryReader | Uint8Array, length?: number): FieldDiff {
+    const reader = input instanceof BinaryReader ? input : new BinaryReader(input);
+    const end = length === undefined ? reader.len : reader.pos + length;
+    const message = createBaseFieldDiff();
+    while (reader.pos < end) {
+      const tag = reader.uint32();
+      switch (tag >>> 3) {
+        case 1: {
+          if (tag !== 10) {
+            break;
+          }
+
+          message.path = reader.string();
+          continue;
+        }
+        case 2: {
+          if (tag !== 18) {
+            break;
+          }
+
+          message.before = reader.string();
+          continue;
+        }
+        case 3: {
+          if (tag !== 26) {
+            break;
+          }
+
+          message.after = reader.string();
+          continue;
+        }
+      }
+      if ((tag & 7) === 4 || tag === 0) {
+        break;
+      }
+      reader.skip(tag & 7);
+    }
+    return message;
+  },
+
+  fromJSON(object: any): FieldDiff {
+    return {
+      path: isSet(object.path) ? globalThis.String(object.path) : "",
+      before: isSet(object.before) ? globalThis.String(object.before) : "",
+      after: isSet(object.after) ? globalThis.String(object.after) : "",
+    };
+  },
+
+  toJSON(message: FieldDiff): unknown {
+    const obj: any = {};
+    if (message.path !== "") {
+      obj.path = message.path;
+    }
+    if (message.before !== "") {
+      obj.before = message.before;
+    }
+    if (message.after !== "") {
+      obj.after = message.after;
+    }
+    return obj;
+  },
+
+  create<I extends Exact<DeepPartial<FieldDiff>, I>>(base?: I): FieldDiff {
+    return FieldDiff.fromPartial(base ?? ({} as any));
+  },
+  fromPartial<I extends Exact<DeepPartial<FieldDiff>, I>>(object: I): FieldDiff {
+    const message = createBaseFieldDiff();
+    message.path = object.path ?? "";
+    message.before = object.before ?? "";
+    message.after = object.after ?? "";
+    return message;
+  },
+};
+
 function createBasePlanResponseElement(): PlanResponseElement {
-  return { opDefinition: "", writesOutputs: [], friendlyMessage: "" };
+  return { opDefinition: "", writesOutputs: [], friendlyMessage: "", opKind: 0, destructive: false, diffs: [] };
 }
 
 export const PlanResponseElement: MessageFns<PlanResponseElement> = {
@@ -900,6 +1054,15 @@ export const PlanResponseElement: MessageFns<PlanResponseElement> = {
     if (message.friendlyMessage !== "") {
       writer.uint32(26).string(message.friendlyMessage);
     }
+    if (message.opKind !== 0) {
+      writer.uint32(32).int32(message.opKind);
+    }
+    if (message.destructive !== false) {
+      writer.uint32(40).bool(message.destructive);
+    }
+    for (const v of message.diffs) {
+      FieldDiff.encode(v!, writer.uint32(50).fork()).join();
+    }
     return writer;
   },
 
@@ -934,6 +1097,30 @@ export const PlanResponseElement: MessageFns<PlanResponseElement> = {
           message.friendlyMessage = reader.string();
           continue;
         }
+        case 4: {
+          if (tag !== 32) {
+            break;
+          }
+
+          message.opKind = reader.int32() as any;
+          continue;
+        }
+        case 5: {
+          if (tag !== 40) {
+            break;
+          }
+
+          message.destructive = reader.bool();
+          continue;
+        }
+        case 6: {
+          if (tag !== 50) {
+            break;
+          }
+
+          message.diffs.push(FieldDiff.decode(reader, reader.uint32()));
+          continue;
+        }
       }
       if ((tag & 7) === 4 || tag === 0) {
         break;
@@ -950,6 +1137,9 @@ export const PlanResponseElement: MessageFns<PlanResponseElement> = {
         ? object.writesOutputs.map((e: any) => globalThis.String(e))
         : [],
       friendlyMessage: isSet(object.friendlyMessage) ? globalThis.String(object.friendlyMessage) : "",
+      opKind: isSet(object.opKind) ? opKindFromJSON(object.opKind) : 0,
+      destructive: isSet(object.destructive) ? globalThis.Boolean(object.destructive) : false,
+      diffs: globalThis.Array.isArray(object?.diffs) ? object.diffs.map((e: any) => FieldDiff.fromJSON(e)) : [],
     };
   },
 
@@ -964,6 +1154,15 @@ export const PlanResponseElement: MessageFns<PlanResponseElement> = {
     if (message.friendlyMessage !== "") {
       obj.friendlyMessage = message.friendlyMessage;
     }
+    if (message.opKind !== 0) {
+      obj.opKind = opKindToJSON(message.opKind);
+    }
+    if (message.destructive !== false) {
+      obj.destructive = message.destructive;
+    }
+    if (message.diffs?.length) {
+      obj.diffs = message.diffs.map((e) => FieldDiff.toJSON(e));
+    }
     return obj;
   },
 
@@ -975,6 +1174,9 @@ export const PlanResponseElement: MessageFns<PlanResponseElement> = {
     message.opDefinition = object.opDefinition ?? "";
     message.writesOutputs = object.writesOutputs?.map((e) => e) || [];
     message.friendlyMessage = object.friendlyMessage ?? "";
+    message.opKind = object.opKind ?? 0;
+    message.destructive = object.destructive ?? false;
+    message.diffs = object.diffs?.map((e) => FieldDiff.fromPartial(e)) || [];
     return message;
   },
 };
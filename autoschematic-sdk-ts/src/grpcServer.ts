@@ -21,6 +21,7 @@ import {
     AddrVirtToPhyResponse,
     Skeleton,
     UnbundleResponseElement,
+    opKindFromJSON,
 } from './generated/connector';
 
 import { Connector } from './types';
@@ -90,7 +91,16 @@ export function createConnectorServer(
                     r.desired?.length ? r.desired : undefined
                 );
 
-                callback(null, PlanResponse.create({ ops: ops }));
+                callback(null, PlanResponse.create({
+                    ops: ops.map((op) => ({
+                        opDefinition: op.opDefinition,
+                        writesOutputs: op.writesOutputs,
+                        friendlyMessage: op.friendlyMessage ?? '',
+                        opKind: opKindFromJSON(op.opKind ?? 'OTHER'),
+                        destructive: op.destructive ?? false,
+                        diffs: (op.diffs ?? []).map((d) => ({ path: d.path, before: d.before ?? '', after: d.after ?? '' })),
+                    })),
+                }));
 
             } catch (e: any) {
                 callback({ code: Status.INTERNAL, message: e.message }, null);
@@ -1,5 +1,13 @@
 export type FilterResponse = 'CONFIG' | 'RESOURCE' | 'BUNDLE' | 'TASK' | 'NONE';
 
+export type OpKind = 'CREATE' | 'UPDATE' | 'DELETE' | 'REPLACE' | 'OTHER';
+
+export interface FieldDiff {
+    path: string;
+    before?: string;
+    after?: string;
+}
+
 export interface ConnectorConstructor {
     __new(name: string, prefix: string): Promise<Connector>;
 }
@@ -22,6 +30,9 @@ export interface Connector {
         opDefinition: string;
         writesOutputs: string[];
         friendlyMessage?: string;
+        opKind?: OpKind;
+        destructive?: boolean;
+        diffs?: FieldDiff[];
     }>>;
 
     opExec(
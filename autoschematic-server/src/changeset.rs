@@ -1,11 +1,16 @@
 use std::{
     path::{Path, PathBuf},
     sync::Arc,
+    time::Duration,
 };
 
 use anyhow::{Context, anyhow};
 use askama::Template;
-use autoschematic_core::{config::AutoschematicConfig, connector_cache::ConnectorCache, report::PlanReportSet};
+use autoschematic_core::{
+    config::AutoschematicConfig,
+    connector_cache::{ConnectorCache, max_resident_memory_from_env},
+    report::PlanReportSet,
+};
 
 use futures_util::TryStreamExt;
 use git2::{Cred, IndexAddOption, PushOptions, RemoteCallbacks, Repository};
@@ -13,7 +18,6 @@ use octocrab::{
     Octocrab,
     models::{
         pulls::MergeableState,
-        repos::DiffEntryStatus,
         webhook_events::{EventInstallation, WebhookEvent},
     },
 };
@@ -25,7 +29,7 @@ use crate::{
     changeset_cache::CHANGESET_CACHE,
     credentials,
     error::{AutoschematicServerError, AutoschematicServerErrorType},
-    object::{Object, sort_objects_by_apply_order},
+    object::Object,
     template::{MiscError, random_failure_emoji},
 };
 
@@ -51,7 +55,7 @@ pub struct ChangeSet {
     issue_number: u64,
     pub client: Octocrab,
     pub objects: Vec<Object>,
-    connector_cache: ConnectorCache,
+    connector_cache: Arc<ConnectorCache>,
     pub last_plan: Option<PlanReportSet>,
 }
 
@@ -190,7 +194,6 @@ impl ChangeSet {
                     repo: repo.clone(),
                     head_sha: head_sha.clone(),
                     filename: previous_filename.into(),
-                    diff_status: DiffEntryStatus::Removed,
                 });
             }
             // }
@@ -199,13 +202,14 @@ impl ChangeSet {
                 repo: repo.clone(),
                 head_sha: head_sha.clone(),
                 filename: path,
-                diff_status: diff.status,
             });
         }
 
         let temp_dir = TempDir::new("autoschematic")?;
 
-        let objects = sort_objects_by_apply_order(&objects);
+        let connector_cache = Arc::new(ConnectorCache::default().with_max_resident_memory(max_resident_memory_from_env()));
+        // The evictor only holds a weak reference, and stops once the ChangeSet and its cache are dropped.
+        connector_cache.spawn_evictor(Duration::from_secs(60));
 
         Ok(Self {
             temp_dir,
@@ -217,7 +221,7 @@ impl ChangeSet {
             issue_number,
             client,
             objects,
-            connector_cache: ConnectorCache::default(),
+            connector_cache,
             last_plan: None,
         })
     }
@@ -228,7 +232,6 @@ impl ChangeSet {
             repo: self.repo.clone(),
             head_sha: self.head_sha.clone(),
             filename: "autoschematic.ron".into(),
-            diff_status: DiffEntryStatus::Unchanged,
         };
 
         let autoschematic_config_file: AutoschematicConfig = autoschematic_config_object
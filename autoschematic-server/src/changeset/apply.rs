@@ -4,6 +4,8 @@ use super::trace::{append_run_log, finish_run, start_run};
 use anyhow::bail;
 use autoschematic_core::config_rbac;
 use autoschematic_core::config_rbac::AutoschematicRbacConfig;
+use autoschematic_core::graph::sort_plan_reports_for_apply;
+use autoschematic_core::protect::flag_protected;
 use autoschematic_core::report::ApplyReport;
 use autoschematic_core::report::ApplyReportSet;
 use autoschematic_core::workflow;
@@ -24,6 +26,7 @@ impl ChangeSet {
         comment_url: &str,
         rbac_config: &AutoschematicRbacConfig,
         rbac_user: &config_rbac::User,
+        allow_destroy_protected: bool,
     ) -> Result<ApplyReportSet, anyhow::Error> {
         let trace_handle = start_run(self, comment_username, comment_url, "apply", "").await?;
         let mut pr_approvals = None;
@@ -45,8 +48,41 @@ impl ChangeSet {
 
         let autoschematic_config = self.get_autoschematic_config().await?;
 
+        if !allow_destroy_protected {
+            let mut protected = Vec::new();
+            for plan_report in &plan_report_set.plan_reports {
+                let mut plan_report = plan_report.clone();
+                flag_protected(&autoschematic_config, &mut plan_report);
+                if plan_report.protected {
+                    protected.push(plan_report.prefix.join(&plan_report.virt_addr).display().to_string());
+                }
+            }
+
+            if !protected.is_empty() {
+                bail!(
+                    "Refusing to apply, as the plan would delete or replace protected resources:\n{}\nRun apply with --allow-destroy-protected to apply anyway.",
+                    protected.join("\n")
+                );
+            }
+        }
+
+        if let Some(policy) = self.get_policy_config().await? {
+            let violations = policy.evaluate(&plan_report_set.plan_reports)?;
+            if !violations.is_empty() && !policy.allows_override(rbac_config, rbac_user) {
+                bail!(
+                    "Refusing to apply, as the plan violates autoschematic.policy.ron:\n{}\nOnly a user with one of the override roles [{}] may apply it.",
+                    violations.iter().map(|v| v.to_string()).collect::<Vec<_>>().join("\n"),
+                    policy.override_roles.join(", ")
+                );
+            }
+        }
+
         let _chwd = self.chwd_to_repo();
-        for plan_report in &plan_report_set.plan_reports {
+
+        let mut plan_reports = plan_report_set.plan_reports.clone();
+        sort_plan_reports_for_apply(&mut plan_reports)?;
+
+        for plan_report in &plan_reports {
             let Some(ref connector_def) = plan_report.connector_def else {
                 continue;
             };
@@ -7,7 +7,7 @@ use std::{
 use super::trace::{append_run_log, finish_run, start_run};
 use super::util::check_run_url;
 use anyhow::Context;
-use autoschematic_core::report::{PlanReport, PlanReportSet};
+use autoschematic_core::report::{PlanReport, PlanReportSet, PlanSummary};
 use autoschematic_core::{
     config_rbac::{self, AutoschematicRbacConfig},
     connector::FilterResponse,
@@ -15,6 +15,8 @@ use autoschematic_core::{
 use autoschematic_core::{
     connector::{Connector, VirtToPhyResponse},
     glob::addr_matches_filter,
+    graph::sort_plan_reports_for_apply,
+    protect::flag_protected,
     template::template_config,
 };
 use git2::Repository;
@@ -53,6 +55,7 @@ impl ChangeSet {
             object_count: 0,
             deferred_count: 0,
             deferred_pending_outputs: HashSet::new(),
+            summary: PlanSummary::default(),
         };
 
         'prefix: for (prefix_name, prefix) in &autoschematic_config.prefixes {
@@ -138,6 +141,13 @@ impl ChangeSet {
                         VirtToPhyResponse::NotPresent => None,
                         VirtToPhyResponse::Deferred(read_outputs) => {
                             plan_report_set.deferred_count += 1;
+                            plan_report_set.summary.add(&PlanReport {
+                                connector_def: Some(connector_def.clone()),
+                                prefix: PathBuf::from(&prefix_name),
+                                virt_addr: virt_addr.to_path_buf(),
+                                missing_outputs: read_outputs.clone(),
+                                ..Default::default()
+                            });
                             for output in read_outputs {
                                 plan_report_set.deferred_pending_outputs.insert(output);
                             }
@@ -178,6 +188,7 @@ impl ChangeSet {
                             }
                             None => None,
                         };
+                        let current_none = current.is_none();
 
                         let reads_outputs = Vec::new();
                         let plan = if object.filename.is_file() {
@@ -197,6 +208,13 @@ impl ChangeSet {
                                 )
                                 .await?;
 
+                                plan_report_set.summary.add(&PlanReport {
+                                    connector_def: Some(connector_def.clone()),
+                                    prefix: PathBuf::from(&prefix_name),
+                                    virt_addr: virt_addr.to_path_buf(),
+                                    missing_outputs: template_result.missing.iter().cloned().collect(),
+                                    ..Default::default()
+                                });
                                 for read_output in template_result.missing {
                                     plan_report_set.deferred_pending_outputs.insert(read_output);
                                 }
@@ -245,7 +263,7 @@ impl ChangeSet {
                                 )
                                 .await?;
 
-                                plan_report_set.plan_reports.push(PlanReport {
+                                let mut plan_report = PlanReport {
                                     connector_def: Some(connector_def.clone()),
                                     prefix: PathBuf::from(&prefix_name),
                                     virt_addr: virt_addr.to_path_buf(),
@@ -254,7 +272,13 @@ impl ChangeSet {
                                     reads_outputs,
                                     error: None,
                                     missing_outputs: Vec::new(),
-                                });
+                                    desired_none: !object.filename.is_file(),
+                                    current_none,
+                                    protected: false,
+                                };
+                                flag_protected(&autoschematic_config, &mut plan_report);
+                                plan_report_set.summary.add(&plan_report);
+                                plan_report_set.plan_reports.push(plan_report);
                             }
 
                             Err(e) => {
@@ -268,7 +292,7 @@ impl ChangeSet {
                                 .await?;
 
                                 plan_report_set.overall_success = false;
-                                plan_report_set.plan_reports.push(PlanReport {
+                                let plan_report = PlanReport {
                                     connector_def: Some(connector_def.clone()),
                                     prefix: PathBuf::from(&prefix_name),
                                     virt_addr: virt_addr.to_path_buf(),
@@ -277,7 +301,12 @@ impl ChangeSet {
                                     reads_outputs: Vec::new(),
                                     missing_outputs: Vec::new(),
                                     error: Some(e.into()),
-                                });
+                                    desired_none: !object.filename.is_file(),
+                                    current_none,
+                                    protected: false,
+                                };
+                                plan_report_set.summary.add(&plan_report);
+                                plan_report_set.plan_reports.push(plan_report);
                                 if !continue_on_error {
                                     break 'prefix;
                                 }
@@ -288,6 +317,9 @@ impl ChangeSet {
             }
         }
 
+        // Store the plan in the order it will be applied in, such that each resource comes after those it reads outputs from.
+        sort_plan_reports_for_apply(&mut plan_report_set.plan_reports)?;
+
         finish_run(&trace_handle).await?;
 
         // // TODO actually send a message...
@@ -1,13 +1,11 @@
-use std::{collections::HashSet, path::PathBuf};
+use std::path::PathBuf;
 
 use super::trace::{append_run_log, finish_run, start_run};
 use super::util::check_run_url;
-use anyhow::Context;
-use autoschematic_core::connector::{FilterResponse, OutputMapFile};
 use autoschematic_core::{
-    connector::{Connector, VirtToPhyResponse},
+    connector::FilterResponse,
     glob::addr_matches_filter,
-    template::{ReadOutput, template_config},
+    workflow::pull_state::{PullStateReport, PullStateResult, pull_state_connector},
 };
 use git2::Repository;
 use octocrab::params::checks::{CheckRunConclusion, CheckRunStatus};
@@ -15,15 +13,6 @@ use octocrab::params::checks::{CheckRunConclusion, CheckRunStatus};
 use super::ChangeSet;
 use crate::{KEYSTORE, object::Object};
 
-#[derive(Default)]
-pub struct PullStateReport {
-    pub object_count: usize,
-    pub import_count: usize,
-    pub delete_count: usize,
-    pub deferred_count: usize,
-    pub missing_outputs: HashSet<ReadOutput>,
-}
-
 impl ChangeSet {
     #[allow(clippy::too_many_arguments)]
     pub async fn pull_state(
@@ -113,129 +102,71 @@ impl ChangeSet {
                     };
                     tracing::info!("Pull State: {:?}", object.filename.clone());
 
-                    let check_run_name = format!(
-                        "autoschematic pull-state -p {} -c {} -s ./{}",
-                        prefix_name,
-                        &connector_def.shortname,
-                        &object.filename.to_string_lossy()
-                    );
-
-                    let phy_addr = match connector.addr_virt_to_phy(virt_addr).await? {
-                        VirtToPhyResponse::NotPresent => {
-                            continue 'object;
-                        }
-                        VirtToPhyResponse::Deferred(read_outputs) => {
-                            pull_state_report.deferred_count += 1;
-                            for output in read_outputs {
-                                pull_state_report.missing_outputs.insert(output);
-                            }
-                            continue 'object;
-                        }
-                        VirtToPhyResponse::Present(phy_addr) => phy_addr,
-                        VirtToPhyResponse::Null(phy_addr) => phy_addr,
-                    };
-
                     if self
                         .connector_cache
                         .filter_cached(&connector_def.shortname, &PathBuf::from(&prefix_name), virt_addr)
                         .await?
-                        == FilterResponse::Resource
+                        != FilterResponse::Resource
                     {
-                        // coz::progress!("pull_state_per_object");
-                        let file_check_run_id = self
-                            .create_check_run(None, &check_run_name, &check_run_url, CheckRunStatus::InProgress, None)
-                            .await?;
-
-                        let desired = if object.filename.is_file() {
-                            let desired_bytes = tokio::fs::read(&object.filename).await?;
-
-                            match str::from_utf8(&desired_bytes) {
-                                Ok(desired) => {
-                                    // If valid utf8, try to template.
-                                    let template_result = template_config(&PathBuf::from(&prefix_name), desired)?;
-
-                                    if !template_result.missing.is_empty() {
-                                        self.create_check_run(
-                                            Some(file_check_run_id),
-                                            &check_run_name,
-                                            &check_run_url,
-                                            CheckRunStatus::Completed,
-                                            Some(CheckRunConclusion::Skipped),
-                                        )
-                                        .await?;
-
-                                        pull_state_report.deferred_count += 1;
-                                        for output in template_result.missing {
-                                            pull_state_report.missing_outputs.insert(output);
-                                        }
-
-                                        continue 'object;
-                                    } else {
-                                        template_result.body.into_bytes()
-                                    }
-                                }
-                                Err(_) => desired_bytes,
-                            }
-                        } else {
-                            continue 'object;
-                        };
-
-                        let mut tick_import_count = false;
-                        let mut tick_delete_count = false;
-                        if let Some(current) = connector.get(&phy_addr).await.context(format!(
-                            "{}::get({})",
-                            connector_def.shortname,
-                            &phy_addr.to_str().unwrap_or_default()
-                        ))? {
-                            if !connector.eq(&phy_addr, &current.resource_definition, &desired).await? {
-                                tick_import_count = true;
-                                tokio::fs::write(&object.filename, current.resource_definition).await?;
-                                self.git_add(repo, &object.filename)?;
-                            }
-
-                            if let Some(outputs) = current.outputs
-                                && !outputs.is_empty()
-                            {
-                                tick_import_count = true;
+                        continue 'object;
+                    }
 
-                                let output_map_file = OutputMapFile::OutputMap(outputs);
-                                let prefix = PathBuf::from(&prefix_name);
+                    let check_run_name = format!(
+                        "autoschematic pull-state -p {} -c {} -s ./{}",
+                        prefix_name,
+                        &connector_def.shortname,
+                        &object.filename.to_string_lossy()
+                    );
 
-                                let virt_output_path = output_map_file.write(&prefix, virt_addr)?;
-                                self.git_add(repo, &virt_output_path)?;
+                    // coz::progress!("pull_state_per_object");
+                    let file_check_run_id = self
+                        .create_check_run(None, &check_run_name, &check_run_url, CheckRunStatus::InProgress, None)
+                        .await?;
 
-                                if virt_addr != phy_addr {
-                                    let phy_output_path = OutputMapFile::write_link(&prefix, &phy_addr, virt_addr)?;
-                                    self.git_add(repo, &phy_output_path)?;
-                                }
-                            }
-                        } else if delete {
-                            // Resource didn't exist remotely, and `delete` was indicated, so let's delete it!
-                            let prefix = PathBuf::from(&prefix_name);
-
-                            if prefix.join(virt_addr).is_file() {
-                                tick_delete_count = true;
-                                std::fs::remove_file(prefix.join(virt_addr))?;
-                                self.git_add(repo, &prefix.join(virt_addr))?;
-                            }
+                    let Some(result) = pull_state_connector(
+                        &connector_def.shortname,
+                        connector.clone(),
+                        &PathBuf::from(&prefix_name),
+                        virt_addr,
+                        delete,
+                    )
+                    .await?
+                    else {
+                        self.create_check_run(
+                            Some(file_check_run_id),
+                            &check_run_name,
+                            &check_run_url,
+                            CheckRunStatus::Completed,
+                            Some(CheckRunConclusion::Skipped),
+                        )
+                        .await?;
+                        continue 'object;
+                    };
 
-                            if let Some(phy_output_path) = OutputMapFile::delete(&prefix, &phy_addr)? {
-                                self.git_add(repo, &phy_output_path)?;
+                    match &result {
+                        PullStateResult::Pulled { wrote_files: paths } | PullStateResult::Deleted { removed_files: paths } => {
+                            for path in paths {
+                                self.git_add(repo, path)?;
                             }
+                        }
+                        PullStateResult::Deferred { .. } | PullStateResult::Unchanged => {}
+                    }
 
-                            if let Some(virt_output_path) = OutputMapFile::delete(&prefix, virt_addr)? {
-                                self.git_add(repo, &virt_output_path)?;
-                            }
-                        };
+                    let conclusion = match &result {
+                        PullStateResult::Deferred { .. } => CheckRunConclusion::Skipped,
+                        _ => CheckRunConclusion::Success,
+                    };
 
-                        if tick_delete_count {
-                            pull_state_report.delete_count += 1;
-                        }
+                    self.create_check_run(
+                        Some(file_check_run_id),
+                        &check_run_name,
+                        &check_run_url,
+                        CheckRunStatus::Completed,
+                        Some(conclusion),
+                    )
+                    .await?;
 
-                        if tick_import_count {
-                            pull_state_report.import_count += 1;
-                        }
-                    }
+                    pull_state_report.add(&result);
                 }
 
                 if (pull_state_report.import_count + pull_state_report.delete_count) > 0 {
@@ -2,6 +2,7 @@ use std::{collections::HashMap, fs, path::PathBuf};
 
 use anyhow::bail;
 use autoschematic_core::{
+    config_policy::AutoschematicPolicyConfig,
     config_rbac::{self, AutoschematicRbacConfig},
     util::RON,
 };
@@ -183,6 +184,29 @@ impl ChangeSet {
     /// to. In particular, get the latest state of it as commited to the default branch (main, master, etc)
     /// as it would be useless to validate the config at the tip of a pull-request branch.
     pub async fn get_rbac_config(&self) -> anyhow::Result<Option<AutoschematicRbacConfig>> {
+        let Some(decoded_content) = self.get_default_branch_file("autoschematic.rbac.ron").await? else {
+            return Ok(None);
+        };
+
+        let config: AutoschematicRbacConfig = RON.from_str(&decoded_content)?;
+
+        Ok(Some(config))
+    }
+
+    /// Get the policy config (autoschematic.policy.ron) from the default branch of the repository,
+    /// for the same reason as get_rbac_config: a pull request mustn't be able to loosen its own policy.
+    pub async fn get_policy_config(&self) -> anyhow::Result<Option<AutoschematicPolicyConfig>> {
+        let Some(decoded_content) = self.get_default_branch_file("autoschematic.policy.ron").await? else {
+            return Ok(None);
+        };
+
+        let config: AutoschematicPolicyConfig = RON.from_str(&decoded_content)?;
+
+        Ok(Some(config))
+    }
+
+    /// Get the contents of the file at `path` on the default branch, or None if there's no such file.
+    async fn get_default_branch_file(&self, path: &str) -> anyhow::Result<Option<String>> {
         let repository = self.client.repos(&self.owner, &self.repo).get().await?;
 
         let Some(default_branch) = repository.default_branch else {
@@ -193,25 +217,23 @@ impl ChangeSet {
             .client
             .repos(&self.owner, &self.repo)
             .get_content()
-            .path("autoschematic.rbac.ron")
+            .path(path)
             .r#ref(default_branch)
             .send()
             .await;
 
-        let contents = config_content?.take_items();
+        let contents = match config_content {
+            Ok(mut config_content) => config_content.take_items(),
+            Err(octocrab::Error::GitHub { source, .. }) if source.status_code == http::StatusCode::NOT_FOUND => {
+                return Ok(None);
+            }
+            Err(e) => return Err(e.into()),
+        };
         if contents.is_empty() {
             return Ok(None);
         }
 
-        let c = &contents[0];
-
-        let Some(decoded_content) = c.decoded_content() else {
-            return Ok(None);
-        };
-
-        let config: AutoschematicRbacConfig = RON.from_str(&decoded_content)?;
-
-        Ok(Some(config))
+        Ok(contents[0].decoded_content())
     }
 
     pub async fn get_pr_approvals(&self) -> anyhow::Result<Vec<config_rbac::User>> {
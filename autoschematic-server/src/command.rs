@@ -99,6 +99,10 @@ pub enum AutoschematicSubcommand {
         /// Optional path (can be a glob) to filter which resources are imported.
         #[arg(short, long, value_name = "subpath")]
         subpath: Option<String>,
+
+        /// Apply even if the plan would delete or replace resources matched by a `protect` rule.
+        #[arg(long, action = ArgAction::SetTrue)]
+        allow_destroy_protected: bool,
     },
 
     /// For each resource in the pull request, get its current state and import it into the repo.
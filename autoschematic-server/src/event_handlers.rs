@@ -17,8 +17,9 @@ use crate::{
     error::{AutoschematicServerError, AutoschematicServerErrorType},
     template::{
         self, ApplyErrorTemplate, ApplyNoPlanTemplate, ApplySuccessTemplate, CommandParseFailure, GreetingTemplate,
-        ImportErrorTemplate, ImportSuccessTemplate, MiscError, PlanErrorTemplate, PlanNoChangesTemplate,
-        PlanOverallErrorTemplate, PlanOverallSuccessTemplate, PlanSuccessTemplate, PrLockHeld, random_failure_emoji,
+        ImportErrorTemplate, ImportSuccessTemplate, MiscError, PlanErrorTemplate, PlanNoChangesTemplate, PlanOpReport,
+        PlanOverallErrorTemplate, PlanOverallSuccessTemplate, PlanPolicyViolationsTemplate, PlanSuccessTemplate, PrLockHeld,
+        random_failure_emoji,
     },
 };
 use crate::{TASK_REGISTRY, template::PullStateSuccessTemplate};
@@ -230,15 +231,8 @@ pub async fn dispatch(webhook_event: WebhookEvent) -> Result<(), AutoschematicSe
 
                                                 changeset.create_comment(&plan_error_template.render()?).await?;
                                             } else {
-                                                let mut op_reports = Vec::new();
-                                                for op in &plan_report.connector_ops {
-                                                    if let Some(msg) = &op.friendly_message {
-                                                        op_reports.push((msg.to_string(), op.op_definition.to_string()));
-                                                    } else {
-                                                        op_reports
-                                                            .push((String::from("Op Summary"), op.op_definition.to_string()));
-                                                    }
-                                                }
+                                                let op_reports: Vec<PlanOpReport> =
+                                                    plan_report.connector_ops.iter().map(PlanOpReport::from).collect();
                                                 // Skip empty plan reports :)
                                                 if !op_reports.is_empty() {
                                                     all_plans_empty = false;
@@ -246,6 +240,7 @@ pub async fn dispatch(webhook_event: WebhookEvent) -> Result<(), AutoschematicSe
                                                         success_emoji: template::random_success_emoji(),
                                                         filename: String::from(plan_report.virt_addr.to_string_lossy()),
                                                         op_reports,
+                                                        protected: plan_report.protected,
                                                     };
 
                                                     changeset.create_comment(&plan_success_template.render()?).await?;
@@ -253,6 +248,20 @@ pub async fn dispatch(webhook_event: WebhookEvent) -> Result<(), AutoschematicSe
                                             }
                                         }
 
+                                        if let Some(policy) = changeset.get_policy_config().await? {
+                                            let violations = policy.evaluate(&plan_report_set.plan_reports)?;
+                                            if !violations.is_empty() {
+                                                let msg = PlanPolicyViolationsTemplate {
+                                                    failure_emoji: template::random_failure_emoji(),
+                                                    override_roles: policy.override_roles.clone(),
+                                                    violations: violations.iter().map(|v| v.to_string()).collect(),
+                                                }
+                                                .render()?;
+
+                                                changeset.create_comment(&msg).await?;
+                                            }
+                                        }
+
                                         if overall_success {
                                             if all_plans_empty {
                                                 if plan_report_set.deferred_count > 0 {
@@ -294,6 +303,7 @@ pub async fn dispatch(webhook_event: WebhookEvent) -> Result<(), AutoschematicSe
                                                     let msg = PlanOverallSuccessWithDeferralsTemplate {
                                                         success_emoji: template::random_success_emoji(),
                                                         apply_command,
+                                                        summary: plan_report_set.summary.clone(),
                                                         deferred_count: plan_report_set.deferred_count,
                                                         output_keys: plan_report_set
                                                             .deferred_pending_outputs
@@ -308,6 +318,7 @@ pub async fn dispatch(webhook_event: WebhookEvent) -> Result<(), AutoschematicSe
                                                     let plan_overall_success_template = PlanOverallSuccessTemplate {
                                                         success_emoji: template::random_success_emoji(),
                                                         apply_command,
+                                                        summary: plan_report_set.summary.clone(),
                                                     };
 
                                                     changeset.create_comment(&plan_overall_success_template.render()?).await?;
@@ -357,7 +368,12 @@ pub async fn dispatch(webhook_event: WebhookEvent) -> Result<(), AutoschematicSe
                                 }
                             }
                         }
-                        crate::command::AutoschematicSubcommand::Apply { connector, subpath, .. } => {
+                        crate::command::AutoschematicSubcommand::Apply {
+                            connector,
+                            subpath,
+                            allow_destroy_protected,
+                            ..
+                        } => {
                             let subpath = subpath.map(PathBuf::from);
 
                             let repo = changeset.clone_repo().await?;
@@ -378,6 +394,7 @@ pub async fn dispatch(webhook_event: WebhookEvent) -> Result<(), AutoschematicSe
                                             comment_url.as_str(),
                                             &rbac_config,
                                             &rbac_user,
+                                            allow_destroy_protected,
                                         )
                                         .await?;
 
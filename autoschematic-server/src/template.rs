@@ -1,4 +1,8 @@
 use askama::Template;
+use autoschematic_core::{
+    connector::{OpKind, PlanResponseElement},
+    report::PlanSummary,
+};
 use rand::seq::IndexedRandom;
 
 const SUCCESS_EMOJI: &[&str] = &[
@@ -57,12 +61,62 @@ pub struct PlanDeferralLoopTemplate {
     pub output_keys: Vec<String>,
 }
 
+/// A single connector op as rendered in a plan comment.
+pub struct PlanOpReport {
+    /// A label for the op's OpKind, like "🗑️ Delete", or empty for OpKind::Other.
+    pub kind: &'static str,
+    pub destructive: bool,
+    pub message: String,
+    pub op_definition: String,
+    /// The op's field-level diffs, as lines of a ```diff block.
+    pub diffs: Vec<String>,
+}
+
+impl From<&PlanResponseElement> for PlanOpReport {
+    fn from(op: &PlanResponseElement) -> Self {
+        let kind = match op.op_kind {
+            OpKind::Create => "➕ Create",
+            OpKind::Update => "✏️ Update",
+            OpKind::Delete => "🗑️ Delete",
+            OpKind::Replace => "♻️ Replace",
+            OpKind::Other => "",
+        };
+
+        let diffs = op
+            .diffs
+            .iter()
+            .flat_map(|diff| {
+                let before = diff.before.as_ref().map(|before| format!("- {}: {}", diff.path, before));
+                let after = diff.after.as_ref().map(|after| format!("+ {}: {}", diff.path, after));
+                before.into_iter().chain(after)
+            })
+            .collect();
+
+        Self {
+            kind,
+            destructive: op.is_destructive(),
+            message: op.friendly_message.clone().unwrap_or(String::from("Op Summary")),
+            op_definition: op.op_definition.clone(),
+            diffs,
+        }
+    }
+}
+
 #[derive(Template)]
 #[template(path = "plan_success.md")]
 pub struct PlanSuccessTemplate {
     pub filename: String,
     pub success_emoji: &'static str,
-    pub op_reports: Vec<(String, String)>,
+    pub op_reports: Vec<PlanOpReport>,
+    pub protected: bool,
+}
+
+#[derive(Template)]
+#[template(path = "plan_policy_violations.md")]
+pub struct PlanPolicyViolationsTemplate {
+    pub failure_emoji: &'static str,
+    pub override_roles: Vec<String>,
+    pub violations: Vec<String>,
 }
 
 #[derive(Template)]
@@ -70,6 +124,7 @@ pub struct PlanSuccessTemplate {
 pub struct PlanOverallSuccessTemplate {
     pub success_emoji: &'static str,
     pub apply_command: String,
+    pub summary: PlanSummary,
 }
 
 #[derive(Template)]
@@ -77,6 +132,7 @@ pub struct PlanOverallSuccessTemplate {
 pub struct PlanOverallSuccessWithDeferralsTemplate {
     pub success_emoji: &'static str,
     pub apply_command: String,
+    pub summary: PlanSummary,
     pub deferred_count: usize,
     pub output_keys: Vec<String>,
 }
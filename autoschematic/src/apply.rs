@@ -1,41 +1,105 @@
 use std::{
     collections::HashSet,
     io::Write,
+    path::{Path, PathBuf},
     process::{Command, Stdio},
+    sync::Arc,
 };
 
+use anyhow::bail;
 use crossterm::style::Stylize;
-use dialoguer::Confirm;
+use dialoguer::{Confirm, Input, MultiSelect};
 use rand::Rng;
+use tokio::task::JoinSet;
 
 use autoschematic_core::{
-    git_util::{get_staged_files, git_add},
-    report::{ApplyReport, PlanReport, PlanReportSet},
+    config::AutoschematicConfig,
+    connector::OpKind,
+    git_util::git_add,
+    graph::{plan_report_dependencies, sort_changed_files_by_dependencies},
+    journal::{Journal, JournalEntry, changed_files, plan_file_hashes, replay},
+    protect::{flag_protected, op_is_destructive},
+    report::{ApplyReport, PlanReport, PlanReportSet, SavedPlan},
     template::ReadOutput,
-    util::{load_autoschematic_config, repo_root},
+    util::{load_autoschematic_config, load_autoschematic_policy_config, repo_root},
+    workflow,
 };
 
 use crate::{
     CONNECTOR_CACHE,
-    plan::{frame, print_frame_end, print_frame_start, print_plan, print_plan_addr},
+    output::{ExitStatus, Output},
+    plan::{
+        frame, get_connector_versions, get_staged_changed_files, print_frame_end, print_frame_start, print_plan,
+        print_plan_addr, print_plan_summary, print_policy_violations,
+    },
     safety_lock::check_safety_lock,
     spinner::show_spinner,
-    util::{colour_op_message, try_colour_op_message_diff},
+    util::{colour_op_message, path_matches_filter, print_no_files_matched, try_colour_op_message_diff},
 };
 
+/// Options for `autoschematic apply`, beyond the prefix, connector and subpath filters.
+#[derive(Clone, Default)]
+pub struct ApplyOptions {
+    pub ask_confirm: bool,
+    pub skip_commit: bool,
+    pub plan_file: Option<PathBuf>,
+    /// The maximum number of resources to apply at once. 0 or 1 applies them one at a time.
+    pub parallel: usize,
+    /// Continue the most recent apply run that was interrupted, instead of planning again.
+    pub resume: bool,
+    /// Abandon any apply run that was interrupted, such that it can no longer be resumed, and apply as usual.
+    pub discard_journal: bool,
+    /// Ask which ops to apply, rather than applying every op in the plan.
+    pub interactive: bool,
+    /// Apply plans that would delete or replace resources matched by a `protect` rule.
+    pub allow_destroy_protected: bool,
+    /// Apply plans that violate a rule in autoschematic.policy.ron. This isn't restricted to
+    /// the policy's `override_roles`, as the CLI can't tell which roles its user holds.
+    pub override_policy: bool,
+}
+
 pub async fn apply(
-    _prefix_filter: Option<String>,
+    prefix_filter: Option<String>,
     connector_filter: Option<String>,
-    _subpath_filter: Option<String>,
-    ask_confirm: bool,
-    skip_commit: bool,
+    subpath_filter: Option<String>,
+    options: ApplyOptions,
+    output: &Output,
 ) -> anyhow::Result<Vec<ApplyReport>> {
     check_safety_lock()?;
 
+    let ask_confirm = options.ask_confirm;
+    let skip_commit = options.skip_commit;
+
+    if ask_confirm && !output.is_human() {
+        bail!("Machine-readable output can't prompt for confirmation. Pass --skip-confirm to apply non-interactively.");
+    }
+
+    if options.interactive && !output.is_human() {
+        bail!("Machine-readable output can't prompt for which ops to apply.");
+    }
+
+    if options.discard_journal {
+        for path in Journal::discard_incomplete()? {
+            if output.is_human() {
+                println!(" ◇ Discarded the journal of an interrupted apply: {}", path.display());
+            }
+        }
+    } else if !options.resume {
+        Journal::check_none_incomplete()?;
+    }
+
+    if let Some(plan_file) = &options.plan_file {
+        return apply_saved_plan(plan_file, &options, output).await;
+    }
+
+    if options.resume {
+        return resume_apply(&options, output).await;
+    }
+
     let repo_root = repo_root()?;
     let config = load_autoschematic_config()?;
 
-    let staged_files = get_staged_files()?;
+    let mut staged_files = get_staged_changed_files().await?;
 
     let keystore = None;
 
@@ -48,7 +112,11 @@ pub async fn apply(
             .arg(pre_commit_hook)
             .stdin(Stdio::inherit())
             .stderr(Stdio::inherit())
-            .stdout(Stdio::inherit())
+            .stdout(if output.is_human() {
+                Stdio::inherit()
+            } else {
+                Stdio::from(std::io::stderr())
+            })
             .output()
             .expect("Git: pre-commit hooks failed!");
     }
@@ -60,7 +128,19 @@ pub async fn apply(
     let mut need_print_frame_end = false;
 
     if staged_files.is_empty() {
-        println!(" ∅  No files staged in git. Stage modified files with git add to plan or apply them.");
+        if output.is_human() {
+            println!(" ∅  No files staged in git. Stage modified files with git add to plan or apply them.");
+        }
+        return Ok(apply_report_set);
+    }
+
+    let staged_count = staged_files.len();
+    staged_files.retain(|f| path_matches_filter(&config, &f.path, &prefix_filter, &subpath_filter));
+
+    if staged_files.is_empty() {
+        if output.is_human() {
+            print_no_files_matched(staged_count, &prefix_filter, &subpath_filter);
+        }
         return Ok(apply_report_set);
     }
 
@@ -86,7 +166,11 @@ pub async fn apply(
     let mut deferred: Vec<PlanReport> = Vec::new();
     let mut set_outputs: HashSet<ReadOutput> = HashSet::new();
 
-    for path in staged_files {
+    // Plan, and so apply, each resource after the resources whose outputs it reads.
+    sort_changed_files_by_dependencies(&config, &mut staged_files)?;
+
+    for staged_file in staged_files {
+        let path = staged_file.path;
         let spinner_stop = show_spinner().await;
 
         //     // TODO track if no staged files matched FilterResponse::Resource...
@@ -107,6 +191,9 @@ pub async fn apply(
 
         spinner_stop.send(()).unwrap();
 
+        output.emit("plan_report", &plan_report)?;
+        plan_report_set.summary.add(&plan_report);
+
         if !plan_report.missing_outputs.is_empty() {
             deferred.push(plan_report.clone());
         }
@@ -115,13 +202,15 @@ pub async fn apply(
             continue;
         }
 
-        if need_print_frame_start {
-            need_print_frame_start = false;
-            need_print_frame_end = true;
-            print_frame_start();
-        }
+        if output.is_human() {
+            if need_print_frame_start {
+                need_print_frame_start = false;
+                need_print_frame_end = true;
+                print_frame_start();
+            }
 
-        print_plan(&plan_report);
+            print_plan(&plan_report);
+        }
 
         plan_report_set.plan_reports.push(plan_report);
     }
@@ -130,96 +219,68 @@ pub async fn apply(
         print_frame_end();
     }
 
-    const CHARSET: &[u8] = b"1234567890";
-    const PASSWORD_LEN: usize = 4;
+    if output.is_human() {
+        println!(" ◇ Plan complete.");
+    }
 
-    let verify_code: String = {
-        let mut rng = rand::rng();
-        (0..PASSWORD_LEN)
-            .map(|_| {
-                let idx = rng.random_range(0..CHARSET.len());
-                CHARSET[idx] as char
-            })
-            .collect()
-    };
+    print_plan_summary(&plan_report_set.summary, output)?;
 
-    println!(" ◇ Plan complete.");
     if plan_report_set.plan_reports.is_empty() {
-        println!(
-            " ≡ All plans are empty, implying that the remote configuration matches the desired configuration for all staged files."
-        );
+        if !deferred.is_empty() {
+            output.set_status(ExitStatus::ChangesPending);
+        }
+
+        if output.is_human() {
+            println!(
+                " ≡ All plans are empty, implying that the remote configuration matches the desired configuration for all staged files."
+            );
+        }
         return Ok(apply_report_set);
     }
 
-    if ask_confirm {
-        println!(
-            "Type {} to {} of the above actions and commit.",
-            verify_code.clone().bold(),
-            "execute all".underline_dark_grey()
-        );
-        println!("Hit Ctrl-c to cancel.");
+    let mut pending_op_count = 0;
+    if options.interactive {
+        let (selected, pending) = select_ops(std::mem::take(&mut plan_report_set.plan_reports));
+        plan_report_set.plan_reports = selected;
+        pending_op_count = pending.iter().map(|p| p.connector_ops.len()).sum();
 
-        loop {
-            print!(">");
-            let _ = std::io::stdout().flush();
-
-            let mut input_line = String::new();
-            std::io::stdin().read_line(&mut input_line).expect("Failed to read line");
-            let verify_code_attempt = input_line.trim();
-            match verify_code_attempt {
-                s if s == verify_code => {
-                    break;
-                }
-                _ => {
-                    println!("Wrong code.");
-                    continue;
-                }
-            }
+        // Deselecting an op may leave a plan that no longer deletes or replaces a protected resource.
+        for plan_report in &mut plan_report_set.plan_reports {
+            flag_protected(&config, plan_report);
         }
     }
 
+    check_protected(&plan_report_set.plan_reports, options.allow_destroy_protected)?;
+    check_policy(&plan_report_set.plan_reports, options.override_policy, output)?;
+
+    if options.interactive {
+        if !prompt_apply_selected(&plan_report_set.plan_reports)? {
+            return Ok(apply_report_set);
+        }
+    } else if ask_confirm {
+        prompt_verify_code();
+    }
+
+    let journal = Arc::new(Journal::create(&plan_report_set.plan_reports)?);
+
     let mut need_print_frame_start = true;
     let mut need_print_frame_end = false;
-    for plan_report in plan_report_set.plan_reports {
-        let spinner_stop = show_spinner().await;
-        let Some(apply_report) = autoschematic_core::workflow::apply::apply(
-            &config,
-            CONNECTOR_CACHE.clone(),
-            keystore.clone(),
-            &connector_filter,
-            &plan_report,
-        )
-        .await?
-        else {
-            spinner_stop.send(()).unwrap();
-            continue;
-        };
 
-        spinner_stop.send(()).unwrap();
+    let mut on_apply = |plan_report: &PlanReport, apply_report: ApplyReport| -> anyhow::Result<()> {
+        output.emit("apply_report", &apply_report)?;
 
-        if need_print_frame_start {
-            need_print_frame_start = false;
-            need_print_frame_end = true;
-            print_frame_start();
-        }
+        if output.is_human() {
+            if need_print_frame_start {
+                need_print_frame_start = false;
+                need_print_frame_end = true;
+                print_frame_start();
+            }
 
-        print_plan_addr(&plan_report);
+            print_plan_addr(plan_report);
+            print_apply_outputs(&apply_report);
+        }
 
         for output in &apply_report.outputs {
-            if let Some(ref friendly_message) = output.friendly_message {
-                let coloured_message =
-                    try_colour_op_message_diff(friendly_message).unwrap_or(colour_op_message(friendly_message));
-
-                for (i, line) in coloured_message.lines().enumerate() {
-                    if i == 0 {
-                        // println!("{}  ⟣ {}", frame(), line)
-                        println!("{}  ⟖ {}", frame(), line);
-                    } else {
-                        println!("{}  {}", frame(), line)
-                    }
-                }
-            }
-
             if let Some(ref report_outputs) = output.outputs {
                 for key in report_outputs.keys() {
                     set_outputs.insert(ReadOutput {
@@ -237,28 +298,114 @@ pub async fn apply(
         wrote_files = true;
 
         apply_report_set.push(apply_report);
+        Ok(())
+    };
+
+    if options.parallel > 1 {
+        // Without confirmation to ask for, deferred plans can be planned again and applied
+        // as soon as the outputs they await are written.
+        let replan = if ask_confirm {
+            Vec::new()
+        } else {
+            std::mem::take(&mut deferred)
+        };
+
+        let mut still_deferred = apply_parallel(
+            &config,
+            &connector_filter,
+            plan_report_set.plan_reports,
+            replan,
+            options.parallel,
+            options.allow_destroy_protected,
+            &journal,
+            output,
+            &mut on_apply,
+        )
+        .await?;
+        deferred.append(&mut still_deferred);
+    } else {
+        for plan_report in plan_report_set.plan_reports {
+            let spinner_stop = show_spinner().await;
+            let Some(apply_report) = autoschematic_core::workflow::apply::apply(
+                &config,
+                CONNECTOR_CACHE.clone(),
+                keystore.clone(),
+                &connector_filter,
+                &plan_report,
+                Some(&journal),
+            )
+            .await?
+            else {
+                spinner_stop.send(()).unwrap();
+                continue;
+            };
+
+            spinner_stop.send(()).unwrap();
+
+            on_apply(&plan_report, apply_report)?;
+        }
     }
+
+    journal.complete()?;
+
     if need_print_frame_end {
         print_frame_end();
     }
 
+    if pending_op_count > 0 {
+        output.set_status(ExitStatus::ChangesPending);
+        println!(
+            " ⊬ {} ops were not selected and remain pending. Run apply again to apply them.",
+            pending_op_count
+        );
+    }
+
     let mut did_make_output_progress = false;
 
     if !deferred.is_empty() {
-        println!(" ⊬ Some files were not applied as they were missing outputs.");
+        if output.is_human() {
+            println!(" ⊬ Some files were not applied as they were missing outputs.");
+        }
         for plan_report in deferred {
-            print_plan_addr(&plan_report);
-            for output in &plan_report.missing_outputs {
-                if set_outputs.contains(output) {
-                    did_make_output_progress = true;
-                    println!(" {} {}[{}]  ", "[SET!]".green(), output.addr.display(), output.key);
+            if output.is_human() {
+                print_plan_addr(&plan_report);
+            }
+            for read_output in &plan_report.missing_outputs {
+                let is_set = set_outputs.contains(read_output);
+                did_make_output_progress |= is_set;
+
+                if !output.is_human() {
+                    continue;
+                }
+
+                if is_set {
+                    println!(" {} {}[{}]  ", "[SET!]".green(), read_output.addr.display(), read_output.key);
                 } else {
-                    println!("        {}[{}]", output.addr.display(), output.key);
+                    println!("        {}[{}]", read_output.addr.display(), read_output.key);
                 }
             }
         }
 
-        if did_make_output_progress {
+        if !output.is_human() {
+            // Without a user to ask, keep applying only for as long as each round unblocks something.
+            if did_make_output_progress {
+                let mut reapply_reports = Box::pin(apply(
+                    prefix_filter,
+                    connector_filter,
+                    subpath_filter,
+                    ApplyOptions {
+                        plan_file: None,
+                        resume: false,
+                        ..options.clone()
+                    },
+                    output,
+                ))
+                .await?;
+                apply_report_set.append(&mut reapply_reports);
+            } else {
+                output.set_status(ExitStatus::ChangesPending);
+            }
+        } else if did_make_output_progress {
             let do_reapply = Confirm::new()
                 .with_prompt(" ◈ Apply succeeded! Some resources were deferred on outputs that are now available. Do you wish to continue applying?")
                 .default(true)
@@ -267,11 +414,15 @@ pub async fn apply(
 
             if do_reapply {
                 Box::pin(apply(
-                    _prefix_filter,
+                    prefix_filter,
                     connector_filter,
-                    _subpath_filter,
-                    ask_confirm,
-                    skip_commit,
+                    subpath_filter,
+                    ApplyOptions {
+                        plan_file: None,
+                        resume: false,
+                        ..options.clone()
+                    },
+                    output,
                 ))
                 .await?;
             }
@@ -285,33 +436,845 @@ pub async fn apply(
                 .expect("Exiting...");
 
             if do_reapply {
-                // apply(_prefix_filter, connector_filter, _subpath_filter, ask_confirm, skip_commit).await?;
+                // apply(prefix_filter, connector_filter, subpath_filter, ask_confirm, skip_commit).await?;
                 Box::pin(apply(
-                    _prefix_filter,
+                    prefix_filter,
                     connector_filter,
-                    _subpath_filter,
-                    ask_confirm,
-                    skip_commit,
+                    subpath_filter,
+                    ApplyOptions {
+                        plan_file: None,
+                        resume: false,
+                        ..options.clone()
+                    },
+                    output,
                 ))
                 .await?;
             }
         }
-    } else if wrote_files && !skip_commit {
-        let do_commit = Confirm::new()
-            .with_prompt(" ◈ Apply succeeded! Do you wish to run git commit to track the new state?")
-            .default(true)
+    } else if wrote_files && !skip_commit && output.is_human() {
+        prompt_git_commit();
+    }
+
+    Ok(apply_report_set)
+}
+
+enum ParallelOutcome {
+    Applied {
+        plan_report: PlanReport,
+        apply_report: ApplyReport,
+        replanned: bool,
+    },
+    Deferred(PlanReport),
+    Skipped,
+}
+
+/// Apply `plan_reports` with up to `parallel` resources in flight at once.
+/// Each plan is applied only once every other plan in the set for a resource it reads outputs from
+/// has been applied, so independent resources proceed concurrently and dependents start as soon as
+/// their inputs are written. `replan` holds deferred plans to plan again and apply in the same manner.
+/// Returns those that are still deferred, along with the plans that depend on them.
+/// After the first failure, no further plans are started, and the error is returned once those in flight finish.
+#[allow(clippy::too_many_arguments)]
+async fn apply_parallel(
+    config: &AutoschematicConfig,
+    connector_filter: &Option<String>,
+    plan_reports: Vec<PlanReport>,
+    replan: Vec<PlanReport>,
+    parallel: usize,
+    allow_destroy_protected: bool,
+    journal: &Arc<Journal>,
+    output: &Output,
+    on_apply: &mut impl FnMut(&PlanReport, ApplyReport) -> anyhow::Result<()>,
+) -> anyhow::Result<Vec<PlanReport>> {
+    let config = Arc::new(config.clone());
+
+    let replan_start = plan_reports.len();
+    let plan_reports: Vec<PlanReport> = plan_reports.into_iter().chain(replan).collect();
+    let dependencies = plan_report_dependencies(&plan_reports)?;
+
+    let mut done = vec![false; plan_reports.len()];
+    let mut deferred = vec![false; plan_reports.len()];
+    let mut pending: Vec<Option<PlanReport>> = plan_reports.into_iter().map(Some).collect();
+
+    let mut joinset = JoinSet::new();
+    let mut error = None;
+    let mut still_deferred = Vec::new();
+
+    loop {
+        // Stop starting new applies after the first failure, but let those in flight finish.
+        if error.is_none() {
+            for i in 0..pending.len() {
+                if joinset.len() >= parallel {
+                    break;
+                }
+
+                if pending[i].is_none() || !dependencies[i].iter().all(|d| done[*d]) {
+                    continue;
+                }
+
+                let Some(plan_report) = pending[i].take() else {
+                    continue;
+                };
+
+                let config = config.clone();
+                let connector_filter = connector_filter.clone();
+                let journal = journal.clone();
+                let replan = i >= replan_start;
+                joinset.spawn(async move {
+                    (
+                        i,
+                        apply_parallel_node(
+                            config,
+                            connector_filter,
+                            plan_report,
+                            replan,
+                            allow_destroy_protected,
+                            journal,
+                        )
+                        .await,
+                    )
+                });
+            }
+        }
+
+        let Some(res) = joinset.join_next().await else {
+            break;
+        };
+
+        let (i, outcome) = res?;
+
+        // Only a plan that was applied, or had nothing to apply, unblocks the plans that depend on it.
+        match outcome {
+            Ok(ParallelOutcome::Applied {
+                plan_report,
+                apply_report,
+                replanned,
+            }) => {
+                if replanned {
+                    output.emit("plan_report", &plan_report)?;
+                }
+                match on_apply(&plan_report, apply_report) {
+                    Ok(()) => done[i] = true,
+                    Err(e) => {
+                        error.get_or_insert(e);
+                    }
+                }
+            }
+            Ok(ParallelOutcome::Deferred(plan_report)) => {
+                output.emit("plan_report", &plan_report)?;
+                deferred[i] = true;
+                still_deferred.push(plan_report);
+            }
+            Ok(ParallelOutcome::Skipped) => done[i] = true,
+            Err(e) => {
+                error.get_or_insert(e);
+            }
+        }
+    }
+
+    if let Some(e) = error {
+        return Err(e);
+    }
+
+    // Plans that wait, directly or through others, on a plan that is still deferred
+    // can't be applied until it is, so they are deferred along with it.
+    loop {
+        let blocked: Vec<usize> = (0..pending.len())
+            .filter(|&i| pending[i].is_some() && dependencies[i].iter().any(|&d| deferred[d]))
+            .collect();
+
+        if blocked.is_empty() {
+            break;
+        }
+
+        for i in blocked {
+            deferred[i] = true;
+            if let Some(plan_report) = pending[i].take() {
+                still_deferred.push(plan_report);
+            }
+        }
+    }
+
+    if pending.iter().any(Option::is_some) {
+        bail!("Some plans could not be applied, as they depend on each other in a cycle.");
+    }
+
+    Ok(still_deferred)
+}
+
+async fn apply_parallel_node(
+    config: Arc<AutoschematicConfig>,
+    connector_filter: Option<String>,
+    plan_report: PlanReport,
+    replan: bool,
+    allow_destroy_protected: bool,
+    journal: Arc<Journal>,
+) -> anyhow::Result<ParallelOutcome> {
+    let plan_report = if replan {
+        let path = plan_report.prefix.join(&plan_report.virt_addr);
+        let Some(plan_report) = workflow::plan::plan(&config, CONNECTOR_CACHE.clone(), None, &connector_filter, &path).await?
+        else {
+            return Ok(ParallelOutcome::Skipped);
+        };
+
+        if !plan_report.missing_outputs.is_empty() {
+            return Ok(ParallelOutcome::Deferred(plan_report));
+        }
+
+        if plan_report.connector_ops.is_empty() {
+            return Ok(ParallelOutcome::Skipped);
+        }
+
+        check_protected([&plan_report], allow_destroy_protected)?;
+
+        journal.append(&JournalEntry::Planned {
+            file_hashes: plan_file_hashes(&plan_report)?,
+            plan_report: Box::new(plan_report.clone()),
+        })?;
+
+        plan_report
+    } else {
+        plan_report
+    };
+
+    let Some(apply_report) = workflow::apply::apply(
+        &config,
+        CONNECTOR_CACHE.clone(),
+        None,
+        &connector_filter,
+        &plan_report,
+        Some(&journal),
+    )
+    .await?
+    else {
+        return Ok(ParallelOutcome::Skipped);
+    };
+
+    Ok(ParallelOutcome::Applied {
+        plan_report,
+        apply_report,
+        replanned: replan,
+    })
+}
+
+/// Execute exactly the connector ops in a plan file written by `autoschematic plan --out`,
+/// refusing if any file, output map or connector version it was planned against has since changed.
+/// With `options.interactive`, only the selected ops are executed, and the plan file is rewritten
+/// to hold the rest.
+async fn apply_saved_plan(plan_file: &Path, options: &ApplyOptions, output: &Output) -> anyhow::Result<Vec<ApplyReport>> {
+    let repo_root = repo_root()?;
+    let config = load_autoschematic_config()?;
+
+    let saved_plan = SavedPlan::read(plan_file)?;
+
+    let changed_files = saved_plan.changed_files()?;
+    if !changed_files.is_empty() {
+        let changed_files: Vec<String> = changed_files.iter().map(|path| format!("    {}", path.display())).collect();
+        bail!(
+            "Refusing to apply stale plan {}. The following files have changed since it was written:\n{}",
+            plan_file.display(),
+            changed_files.join("\n")
+        );
+    }
+
+    let connector_versions = get_connector_versions(&config, &saved_plan.plan_report_set).await?;
+    for (prefix_name, versions) in &saved_plan.connector_versions {
+        for (shortname, version) in versions {
+            let current_version = connector_versions.get(prefix_name).and_then(|v| v.get(shortname));
+            if current_version != Some(version) {
+                bail!(
+                    "Refusing to apply stale plan {}: connector {} in prefix {} was at version {} when planned, but is now at {}.",
+                    plan_file.display(),
+                    shortname,
+                    prefix_name,
+                    version,
+                    current_version.map(String::as_str).unwrap_or("<none>")
+                );
+            }
+        }
+    }
+
+    let SavedPlan {
+        mut plan_report_set,
+        file_hashes,
+        connector_versions,
+        ..
+    } = saved_plan;
+    let mut apply_report_set = Vec::new();
+
+    if plan_report_set.plan_reports.is_empty() {
+        if output.is_human() {
+            println!(" ≡ The saved plan is empty, so there is nothing to apply.");
+        }
+        return Ok(apply_report_set);
+    }
+
+    if output.is_human() {
+        print_frame_start();
+        for plan_report in &plan_report_set.plan_reports {
+            print_plan(plan_report);
+        }
+        print_frame_end();
+    }
+
+    print_plan_summary(&plan_report_set.summary, output)?;
+
+    let mut pending = Vec::new();
+    if options.interactive {
+        let selected;
+        (selected, pending) = select_ops(std::mem::take(&mut plan_report_set.plan_reports));
+        plan_report_set.plan_reports = selected;
+    }
+
+    // Check against the protect rules as they are now, rather than as they were at plan time.
+    for plan_report in &mut plan_report_set.plan_reports {
+        flag_protected(&config, plan_report);
+    }
+    check_protected(&plan_report_set.plan_reports, options.allow_destroy_protected)?;
+    check_policy(&plan_report_set.plan_reports, options.override_policy, output)?;
+
+    if options.interactive {
+        if !prompt_apply_selected(&plan_report_set.plan_reports)? {
+            return Ok(apply_report_set);
+        }
+    } else if options.ask_confirm {
+        prompt_verify_code();
+    }
+
+    let mut wrote_files = false;
+
+    if output.is_human() {
+        print_frame_start();
+    }
+    let journal = Journal::create(&plan_report_set.plan_reports)?;
+
+    for plan_report in &plan_report_set.plan_reports {
+        let spinner_stop = show_spinner().await;
+
+        let Some(apply_report) = apply_plan_report_from(&config, plan_report, &journal, 0).await? else {
+            spinner_stop.send(()).unwrap();
+            continue;
+        };
+
+        spinner_stop.send(()).unwrap();
+
+        output.emit("apply_report", &apply_report)?;
+
+        if output.is_human() {
+            print_plan_addr(plan_report);
+            print_apply_outputs(&apply_report);
+        }
+
+        for path in &apply_report.wrote_files {
+            git_add(&repo_root, path)?;
+            wrote_files = true;
+        }
+
+        apply_report_set.push(apply_report);
+    }
+    if output.is_human() {
+        print_frame_end();
+    }
+
+    journal.complete()?;
+
+    if !pending.is_empty() {
+        let pending_op_count: usize = pending.iter().map(|p| p.connector_ops.len()).sum();
+
+        // Applying the selected ops may have written output maps that the pending ops were planned against,
+        // so snapshot them afresh to keep the rewritten plan applicable.
+        let mut pending_plan = SavedPlan {
+            connector_versions,
+            ..Default::default()
+        };
+        for path in file_hashes.keys() {
+            pending_plan.track_file(path)?;
+        }
+        for plan_report in &pending {
+            pending_plan.track_output_maps(plan_report)?;
+        }
+        pending_plan.plan_report_set = PlanReportSet {
+            plan_reports: pending,
+            deferred_count: plan_report_set.deferred_count,
+            object_count: plan_report_set.object_count,
+            ..Default::default()
+        };
+        pending_plan.write(plan_file)?;
+
+        output.set_status(ExitStatus::ChangesPending);
+        println!(
+            " ⊬ {} ops were not selected and remain pending in {}.",
+            pending_op_count,
+            plan_file.display()
+        );
+    }
+
+    if plan_report_set.deferred_count > 0 {
+        output.set_status(ExitStatus::ChangesPending);
+        if output.is_human() {
+            println!(
+                " ⊬ {} resources in this plan were deferred on missing outputs. Run plan again to pick them up.",
+                plan_report_set.deferred_count
+            );
+        }
+    }
+
+    if wrote_files && !options.skip_commit && output.is_human() {
+        prompt_git_commit();
+    }
+
+    Ok(apply_report_set)
+}
+
+/// Apply `plan_report` with the connector it was planned with, starting from op `start_op`
+/// and recording each op in `journal`.
+pub async fn apply_plan_report_from(
+    config: &AutoschematicConfig,
+    plan_report: &PlanReport,
+    journal: &Journal,
+    start_op: usize,
+) -> anyhow::Result<Option<ApplyReport>> {
+    let Some(ref connector_def) = plan_report.connector_def else {
+        return Ok(None);
+    };
+
+    let prefix_name = plan_report.prefix.to_string_lossy();
+
+    let (connector, mut inbox) = CONNECTOR_CACHE
+        .get_or_spawn_connector(config, &prefix_name, connector_def, None, true)
+        .await?;
+
+    let _reader_handle = tokio::spawn(async move {
+        loop {
+            match inbox.recv().await {
+                Ok(Some(stdout)) => {
+                    eprintln!("{}", stdout.to_string_lossy());
+                }
+                Ok(None) => {}
+                Err(_) => break,
+            }
+        }
+    });
+
+    workflow::apply::apply_connector_journaled(connector, plan_report, Some(journal), start_op).await
+}
+
+/// Continue the most recent apply run that was interrupted, executing only the ops its journal
+/// doesn't record as finished. An op that was started but never finished may or may not have
+/// taken effect, so the user decides whether to execute it again or skip it.
+async fn resume_apply(options: &ApplyOptions, output: &Output) -> anyhow::Result<Vec<ApplyReport>> {
+    let ask_confirm = options.ask_confirm;
+    let skip_commit = options.skip_commit;
+
+    let repo_root = repo_root()?;
+    let config = load_autoschematic_config()?;
+
+    let mut apply_report_set = Vec::new();
+
+    let Some((journal_path, entries)) = Journal::latest_incomplete()? else {
+        if output.is_human() {
+            println!(" ∅  No interrupted apply to resume.");
+        }
+        return Ok(apply_report_set);
+    };
+
+    let changed = changed_files(&entries)?;
+    if !changed.is_empty() {
+        let changed: Vec<String> = changed.iter().map(|path| format!("    {}", path.display())).collect();
+        bail!(
+            "These files have changed since the interrupted apply was journaled, so its remaining ops may no longer be what you intend:\n{}\nRevert them, or abandon the interrupted apply with apply --discard-journal and plan again.",
+            changed.join("\n")
+        );
+    }
+
+    let journal = Journal::open(&journal_path)?;
+
+    let mut remaining = Vec::new();
+    for (plan_report, progress) in replay(&entries) {
+        if progress.done_ops >= plan_report.connector_ops.len() {
+            continue;
+        }
+
+        let mut start_op = progress.done_ops;
+
+        if progress.interrupted {
+            let op = &plan_report.connector_ops[start_op];
+            let description = op.friendly_message.as_deref().unwrap_or(&op.op_definition);
+
+            if !ask_confirm {
+                bail!(
+                    "An op at {} was interrupted and may or may not have taken effect: {}\nRun apply --resume without --skip-confirm to decide whether to execute it again.",
+                    plan_report.prefix.join(&plan_report.virt_addr).display(),
+                    description
+                );
+            }
+
+            print_plan_addr(&plan_report);
+            println!("{}", colour_op_message(description, op.op_kind));
+
+            let execute_again = Confirm::new()
+                .with_prompt(" ◈ This op was interrupted, and may or may not have taken effect. Execute it again?")
+                .default(false)
+                .interact()
+                .expect("Exiting...");
+
+            if !execute_again {
+                journal.append(&JournalEntry::OpSkipped {
+                    prefix: plan_report.prefix.clone(),
+                    virt_addr: plan_report.virt_addr.clone(),
+                    op_index: start_op,
+                })?;
+                start_op += 1;
+            }
+        }
+
+        if start_op < plan_report.connector_ops.len() {
+            remaining.push((plan_report, start_op));
+        }
+    }
+
+    if remaining.is_empty() {
+        journal.complete()?;
+        if output.is_human() {
+            println!(" ≡ Every op in the interrupted apply has already been executed.");
+        }
+        return Ok(apply_report_set);
+    }
+
+    for (plan_report, _) in &mut remaining {
+        flag_protected(&config, plan_report);
+    }
+    check_protected(
+        remaining.iter().map(|(plan_report, _)| plan_report),
+        options.allow_destroy_protected,
+    )?;
+
+    if output.is_human() {
+        println!(" ◇ Resuming apply from {}:", journal_path.display());
+        print_frame_start();
+        for (plan_report, start_op) in &remaining {
+            let mut remaining_plan = plan_report.clone();
+            remaining_plan.connector_ops.drain(..*start_op);
+            print_plan(&remaining_plan);
+        }
+        print_frame_end();
+    }
+
+    if ask_confirm {
+        prompt_verify_code();
+    }
+
+    let mut wrote_files = false;
+
+    if output.is_human() {
+        print_frame_start();
+    }
+    for (plan_report, start_op) in &remaining {
+        let spinner_stop = show_spinner().await;
+
+        let Some(apply_report) = apply_plan_report_from(&config, plan_report, &journal, *start_op).await? else {
+            spinner_stop.send(()).unwrap();
+            continue;
+        };
+
+        spinner_stop.send(()).unwrap();
+
+        output.emit("apply_report", &apply_report)?;
+
+        if output.is_human() {
+            print_plan_addr(plan_report);
+            print_apply_outputs(&apply_report);
+        }
+
+        for path in &apply_report.wrote_files {
+            git_add(&repo_root, path)?;
+            wrote_files = true;
+        }
+
+        apply_report_set.push(apply_report);
+    }
+    if output.is_human() {
+        print_frame_end();
+    }
+
+    journal.complete()?;
+
+    if wrote_files && !skip_commit && output.is_human() {
+        prompt_git_commit();
+    }
+
+    Ok(apply_report_set)
+}
+
+/// Refuse to go on if any of `plan_reports` would delete or replace a resource matched by a `protect` rule,
+/// unless `allow_destroy_protected` is set.
+pub fn check_protected<'a>(
+    plan_reports: impl IntoIterator<Item = &'a PlanReport>,
+    allow_destroy_protected: bool,
+) -> anyhow::Result<()> {
+    let protected: Vec<String> = plan_reports
+        .into_iter()
+        .filter(|p| p.protected)
+        .map(|p| format!("    {}", p.prefix.join(&p.virt_addr).display()))
+        .collect();
+
+    if protected.is_empty() || allow_destroy_protected {
+        return Ok(());
+    }
+
+    bail!(
+        "Refusing to apply, as the plan would delete or replace protected resources:\n{}\nPass --allow-destroy-protected to apply anyway.",
+        protected.join("\n")
+    );
+}
+
+/// Refuse to go on if `plan_reports` violate a rule in autoschematic.policy.ron, unless `override_policy` is set.
+/// The CLI can't tell which RBAC roles its user holds, so unlike the server, it doesn't check `override_roles`.
+pub fn check_policy(plan_reports: &[PlanReport], override_policy: bool, output: &Output) -> anyhow::Result<()> {
+    let Some(policy) = load_autoschematic_policy_config()? else {
+        return Ok(());
+    };
+
+    let violations = policy.evaluate(plan_reports)?;
+    if violations.is_empty() {
+        return Ok(());
+    }
+
+    if override_policy {
+        for violation in &violations {
+            output.emit("policy_violation", violation)?;
+        }
+        if output.is_human() {
+            println!(
+                " ⚠  Overriding {} rules in autoschematic.policy.ron, as --override-policy was passed.",
+                violations.len()
+            );
+        }
+        return Ok(());
+    }
+
+    print_policy_violations(&violations, output)?;
+
+    bail!(
+        "Refusing to apply, as the plan violates {} rules in autoschematic.policy.ron. Pass --override-policy to apply anyway.",
+        violations.len()
+    );
+}
+
+/// Ask the user which ops to apply, and split each plan into the selected ops and the pending ops,
+/// preserving their order. Plans left with no ops are dropped.
+/// Since a plan's ops must run in sequence, the ops selected from each plan must be a prefix of its ops:
+/// a selection that skips an op and selects a later one in the same plan is rejected and asked again.
+fn select_ops(plan_reports: Vec<PlanReport>) -> (Vec<PlanReport>, Vec<PlanReport>) {
+    let mut items = Vec::new();
+    for plan_report in &plan_reports {
+        for op in &plan_report.connector_ops {
+            let description = op.friendly_message.as_deref().unwrap_or(&op.op_definition);
+            items.push(format!(
+                "{}: {}",
+                plan_report.prefix.join(&plan_report.virt_addr).display(),
+                description.lines().next().unwrap_or_default()
+            ));
+        }
+    }
+
+    let mut defaults = vec![true; items.len()];
+    let selection = loop {
+        let selection: HashSet<usize> = MultiSelect::new()
+            .with_prompt(" ⊆ Which ops should be applied?")
+            .items(&items)
+            .defaults(&defaults)
             .interact()
+            .expect("Exiting...")
+            .into_iter()
+            .collect();
+
+        let skipped = out_of_order_selections(&plan_reports, &selection);
+        if skipped.is_empty() {
+            break selection;
+        }
+
+        for plan_report in skipped {
+            println!(
+                " ⊬ {}: ops must be applied in order, so an op can only be selected along with every op before it.",
+                plan_report.prefix.join(&plan_report.virt_addr).display()
+            );
+        }
+        defaults = (0..items.len()).map(|i| selection.contains(&i)).collect();
+    };
+
+    let mut selected = Vec::new();
+    let mut pending = Vec::new();
+    let mut i = 0;
+    for plan_report in plan_reports {
+        let mut selected_report = PlanReport {
+            connector_ops: Vec::new(),
+            ..plan_report.clone()
+        };
+        let mut pending_report = PlanReport {
+            connector_ops: Vec::new(),
+            ..plan_report
+        };
+
+        for op in std::mem::take(&mut pending_report.connector_ops) {
+            if selection.contains(&i) {
+                selected_report.connector_ops.push(op);
+            } else {
+                pending_report.connector_ops.push(op);
+            }
+            i += 1;
+        }
+
+        if !selected_report.connector_ops.is_empty() {
+            selected.push(selected_report);
+        }
+        if !pending_report.connector_ops.is_empty() {
+            pending.push(pending_report);
+        }
+    }
+
+    (selected, pending)
+}
+
+/// The plans in `plan_reports` for which `selection`, an index into all of their ops in sequence,
+/// selects an op without selecting every op before it in the same plan.
+fn out_of_order_selections<'a>(plan_reports: &'a [PlanReport], selection: &HashSet<usize>) -> Vec<&'a PlanReport> {
+    let mut out_of_order = Vec::new();
+    let mut i = 0;
+    for plan_report in plan_reports {
+        let selected: Vec<bool> = (i..i + plan_report.connector_ops.len())
+            .map(|j| selection.contains(&j))
+            .collect();
+        if selected.windows(2).any(|w| !w[0] && w[1]) {
+            out_of_order.push(plan_report);
+        }
+        i += plan_report.connector_ops.len();
+    }
+    out_of_order
+}
+
+/// Confirm the ops chosen by `select_ops`. Destructive ops require the user to type the name
+/// of each prefix they belong to. Returns false if nothing should be applied.
+fn prompt_apply_selected(selected: &[PlanReport]) -> anyhow::Result<bool> {
+    if selected.is_empty() {
+        println!(" ∅  No ops were selected, so nothing was applied.");
+        return Ok(false);
+    }
+
+    let mut destructive_prefixes: Vec<&Path> = selected
+        .iter()
+        .filter(|p| p.connector_ops.iter().any(op_is_destructive))
+        .map(|p| p.prefix.as_path())
+        .collect();
+    destructive_prefixes.sort();
+    destructive_prefixes.dedup();
+
+    for prefix in destructive_prefixes {
+        println!(
+            " ⊬ The selected ops include destructive ops in prefix {}:",
+            prefix.display().to_string().bold()
+        );
+        for plan_report in selected.iter().filter(|p| p.prefix == prefix) {
+            for op in plan_report.connector_ops.iter().filter(|op| op_is_destructive(op)) {
+                let description = op.friendly_message.as_deref().unwrap_or(&op.op_definition);
+                println!(
+                    "    {}: {}",
+                    plan_report.virt_addr.display(),
+                    colour_op_message(description.lines().next().unwrap_or_default(), op.op_kind)
+                );
+            }
+        }
+
+        let typed: String = Input::new()
+            .with_prompt(format!(" ◈ Type the prefix name {} to confirm", prefix.display()))
+            .allow_empty(true)
+            .interact_text()
             .expect("Exiting...");
 
-        if do_commit {
-            Command::new("git")
-                .arg("commit")
-                .stdin(Stdio::inherit())
-                .stdout(Stdio::inherit())
-                .output()
-                .expect("git commit: failed to execute");
+        if Path::new(typed.trim()) != prefix {
+            bail!("Prefix name did not match, so nothing was applied.");
         }
     }
 
-    Ok(apply_report_set)
+    let op_count: usize = selected.iter().map(|p| p.connector_ops.len()).sum();
+    Ok(Confirm::new()
+        .with_prompt(format!(" ◈ Apply the {op_count} selected ops?"))
+        .default(false)
+        .interact()
+        .expect("Exiting..."))
+}
+
+/// Ask the user to type a random code before executing anything.
+fn prompt_verify_code() {
+    const CHARSET: &[u8] = b"1234567890";
+    const PASSWORD_LEN: usize = 4;
+
+    let verify_code: String = {
+        let mut rng = rand::rng();
+        (0..PASSWORD_LEN)
+            .map(|_| {
+                let idx = rng.random_range(0..CHARSET.len());
+                CHARSET[idx] as char
+            })
+            .collect()
+    };
+
+    println!(
+        "Type {} to {} of the above actions and commit.",
+        verify_code.clone().bold(),
+        "execute all".underline_dark_grey()
+    );
+    println!("Hit Ctrl-c to cancel.");
+
+    loop {
+        print!(">");
+        let _ = std::io::stdout().flush();
+
+        let mut input_line = String::new();
+        std::io::stdin().read_line(&mut input_line).expect("Failed to read line");
+        let verify_code_attempt = input_line.trim();
+        match verify_code_attempt {
+            s if s == verify_code => {
+                break;
+            }
+            _ => {
+                println!("Wrong code.");
+                continue;
+            }
+        }
+    }
+}
+
+pub fn print_apply_outputs(apply_report: &ApplyReport) {
+    for output in &apply_report.outputs {
+        if let Some(ref friendly_message) = output.friendly_message {
+            let coloured_message =
+                try_colour_op_message_diff(friendly_message).unwrap_or(colour_op_message(friendly_message, OpKind::Other));
+
+            for (i, line) in coloured_message.lines().enumerate() {
+                if i == 0 {
+                    println!("{}  ⟖ {}", frame(), line);
+                } else {
+                    println!("{}  {}", frame(), line)
+                }
+            }
+        }
+    }
+}
+
+pub fn prompt_git_commit() {
+    let do_commit = Confirm::new()
+        .with_prompt(" ◈ Apply succeeded! Do you wish to run git commit to track the new state?")
+        .default(true)
+        .interact()
+        .expect("Exiting...");
+
+    if do_commit {
+        Command::new("git")
+            .arg("commit")
+            .stdin(Stdio::inherit())
+            .stdout(Stdio::inherit())
+            .output()
+            .expect("git commit: failed to execute");
+    }
 }
@@ -11,7 +11,13 @@ use autoschematic_core::{
 };
 use tokio::process::Command;
 
-use crate::{CONNECTOR_CACHE, apply, aux_task::test_task::TestTask, util::try_colour_op_message_diff};
+use crate::{
+    CONNECTOR_CACHE,
+    apply::{self, ApplyOptions},
+    aux_task::test_task::TestTask,
+    output::Output,
+    util::try_colour_op_message_diff,
+};
 
 #[derive(Clone, Serialize, Deserialize)]
 struct FuzzConfig {
@@ -98,7 +104,18 @@ impl TestTask {
                 let ask_confirm = false;
                 let skip_commit = true;
 
-                let apply_reports = apply::apply(prefix, connector, subpath, ask_confirm, skip_commit).await?;
+                let apply_reports = apply::apply(
+                    prefix,
+                    connector,
+                    subpath,
+                    ApplyOptions {
+                        ask_confirm,
+                        skip_commit,
+                        ..Default::default()
+                    },
+                    &Output::default(),
+                )
+                .await?;
 
                 let message = format!("fuzz-test-{rand_suffix}");
                 println!("{}", message);
@@ -115,6 +132,7 @@ impl TestTask {
                         &config,
                         &CONNECTOR_CACHE,
                         None,
+                        None,
                         &apply_report.prefix,
                         &apply_report.virt_addr,
                     )
@@ -138,6 +156,12 @@ impl TestTask {
                                 }
                             }
                         }
+                        check_drift::CheckDriftResult::OutputsDrifted { changed } => {
+                            println!("{}", "check_drift: outputs drifted!".underline_dark_magenta());
+                            for (key, (current, stored)) in changed {
+                                println!("  {key}: {stored:?} -> {current:?}");
+                            }
+                        }
                         check_drift::CheckDriftResult::Equal => {
                             println!("{}", "check_drift: equal!".underline_dark_green());
                         }
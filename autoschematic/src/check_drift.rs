@@ -3,22 +3,120 @@ use std::path::PathBuf;
 use anyhow::bail;
 use autoschematic_core::{
     util::{load_autoschematic_config, split_prefix_addr},
-    workflow,
+    workflow::{
+        self,
+        check_drift::{CheckDriftResult, OutputsDrift},
+    },
 };
+use serde::Serialize;
 
-use crate::CONNECTOR_CACHE;
+use crate::{
+    CONNECTOR_CACHE,
+    output::{ExitStatus, Output},
+};
+
+/// The machine-readable form of a CheckDriftResult.
+#[derive(Serialize)]
+pub struct CheckDriftRecord {
+    pub prefix: PathBuf,
+    pub addr: PathBuf,
+    pub result: &'static str,
+    pub current: Option<String>,
+    pub desired: Option<String>,
+    /// For "outputs_drifted", each changed output key mapped to its (current, stored) values.
+    pub changed_outputs: Option<OutputsDrift>,
+    pub error: Option<String>,
+}
+
+impl CheckDriftRecord {
+    pub fn new(prefix: PathBuf, addr: PathBuf, check_drift_result: &CheckDriftResult) -> Self {
+        let (result, current, desired) = match check_drift_result {
+            CheckDriftResult::NeitherExist => ("neither_exist", None, None),
+            CheckDriftResult::InvalidAddress => ("invalid_address", None, None),
+            CheckDriftResult::NotEqual { current, desired } => (
+                "not_equal",
+                current.as_ref().map(|c| String::from_utf8_lossy(c).to_string()),
+                desired.as_ref().map(|d| String::from_utf8_lossy(d).to_string()),
+            ),
+            CheckDriftResult::OutputsDrifted { .. } => ("outputs_drifted", None, None),
+            CheckDriftResult::Equal => ("equal", None, None),
+        };
+
+        let changed_outputs = match check_drift_result {
+            CheckDriftResult::OutputsDrifted { changed } => Some(changed.clone()),
+            _ => None,
+        };
 
-pub async fn check_drift(path: &str) -> anyhow::Result<()> {
+        Self {
+            prefix,
+            addr,
+            result,
+            current,
+            desired,
+            changed_outputs,
+            error: None,
+        }
+    }
+
+    pub fn error(prefix: PathBuf, addr: PathBuf, error: &anyhow::Error) -> Self {
+        Self {
+            prefix,
+            addr,
+            result: "error",
+            current: None,
+            desired: None,
+            changed_outputs: None,
+            error: Some(format!("{error:#}")),
+        }
+    }
+}
+
+pub async fn check_drift(path: &str, output: &Output) -> anyhow::Result<()> {
     let config = load_autoschematic_config()?;
     let Some((prefix, addr)) = split_prefix_addr(&config, &PathBuf::from(path)) else {
         CONNECTOR_CACHE.clear().await;
         bail!("Not an address for any active connector");
     };
 
-    match workflow::check_drift::check_drift(&config, &CONNECTOR_CACHE, None, &prefix, &addr).await? {
-        workflow::check_drift::CheckDriftResult::NeitherExist => Ok(()),
-        workflow::check_drift::CheckDriftResult::InvalidAddress => bail!("Not an address for any active connector"),
-        workflow::check_drift::CheckDriftResult::NotEqual { .. } => bail!("Resource has drifted"),
-        workflow::check_drift::CheckDriftResult::Equal => Ok(()),
+    let check_drift_result = workflow::check_drift::check_drift(&config, &CONNECTOR_CACHE, None, None, &prefix, &addr).await?;
+
+    output.emit(
+        "check_drift",
+        &CheckDriftRecord::new(prefix, addr.clone(), &check_drift_result),
+    )?;
+
+    match check_drift_result {
+        CheckDriftResult::NeitherExist => Ok(()),
+        CheckDriftResult::InvalidAddress => bail!("Not an address for any active connector"),
+        CheckDriftResult::NotEqual { .. } | CheckDriftResult::OutputsDrifted { .. } if !output.is_human() => {
+            output.set_status(ExitStatus::DriftFound);
+            Ok(())
+        }
+        CheckDriftResult::NotEqual { .. } => bail!("Resource has drifted"),
+        CheckDriftResult::OutputsDrifted { changed } => {
+            println!(" ⊬ Outputs at {} have drifted (stored -> current):", addr.display());
+            for line in describe_changed_outputs(&changed) {
+                println!("    {line}");
+            }
+            bail!("Resource outputs have drifted")
+        }
+        CheckDriftResult::Equal => Ok(()),
+    }
+}
+
+/// Describe each changed output of an "outputs_drifted" result as "key: stored -> current", sorted by key.
+pub fn describe_changed_outputs(changed: &OutputsDrift) -> Vec<String> {
+    let mut lines: Vec<String> = changed
+        .iter()
+        .map(|(key, (current, stored))| format!("{}: {} -> {}", key, fmt_output_value(stored), fmt_output_value(current)))
+        .collect();
+    lines.sort();
+    lines
+}
+
+fn fmt_output_value(value: &Option<String>) -> String {
+    match value {
+        Some(value) => format!("{value:?}"),
+        None => String::from("(unset)"),
     }
 }
@@ -0,0 +1,188 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::bail;
+use autoschematic_core::{
+    connector::OutputMapFile,
+    git_util::{git_add, git_rm},
+    journal::Journal,
+    report::{ApplyReport, PlanReport},
+    util::{load_autoschematic_config, repo_root},
+    workflow,
+};
+use crossterm::style::Stylize;
+use dialoguer::Input;
+
+use crate::{
+    CONNECTOR_CACHE,
+    apply::{apply_plan_report_from, check_policy, check_protected, print_apply_outputs, prompt_git_commit},
+    output::{ExitStatus, Output},
+    plan::{print_frame_end, print_frame_start, print_plan, print_plan_addr},
+    safety_lock::check_safety_lock,
+    spinner::show_spinner,
+};
+
+/// Destroy every remote resource that the connectors in a prefix list, dependents first.
+#[allow(clippy::too_many_arguments)]
+pub async fn destroy(
+    prefix: String,
+    connector_filter: Option<String>,
+    subpath_filter: Option<String>,
+    skip_confirm: bool,
+    skip_commit: bool,
+    remove_files: bool,
+    allow_destroy_protected: bool,
+    override_policy: bool,
+    output: &Output,
+) -> anyhow::Result<Vec<ApplyReport>> {
+    check_safety_lock()?;
+    Journal::check_none_incomplete()?;
+
+    if !skip_confirm && !output.is_human() {
+        bail!("Machine-readable output can't prompt for confirmation. Pass --skip-confirm to destroy non-interactively.");
+    }
+
+    let repo_root = repo_root()?;
+    let config = load_autoschematic_config()?;
+
+    if !config.prefixes.contains_key(&prefix) {
+        bail!("No prefix named {} in autoschematic.ron", prefix);
+    }
+
+    let prefix_path = PathBuf::from(&prefix);
+    let subpath = subpath_filter.map(PathBuf::from).unwrap_or(PathBuf::from("./"));
+
+    let spinner_stop = show_spinner().await;
+    let plan_reports =
+        workflow::destroy::plan_destroy(&config, &CONNECTOR_CACHE, None, &prefix_path, &connector_filter, &subpath).await;
+    spinner_stop.send(()).unwrap();
+
+    let (deferred, plan_reports): (Vec<PlanReport>, Vec<PlanReport>) =
+        plan_reports?.into_iter().partition(|p| !p.missing_outputs.is_empty());
+
+    for plan_report in deferred.iter().chain(&plan_reports) {
+        output.emit("plan_report", plan_report)?;
+    }
+
+    let mut apply_report_set = Vec::new();
+
+    if output.is_human() && !deferred.is_empty() {
+        println!(" ⊬ Some resources can't be destroyed, as their physical addresses depend on missing outputs:");
+        for plan_report in &deferred {
+            print_plan_addr(plan_report);
+        }
+    }
+
+    if !deferred.is_empty() {
+        output.set_status(ExitStatus::ChangesPending);
+    }
+
+    if plan_reports.is_empty() {
+        if output.is_human() {
+            println!(" ≡ No remote resources to destroy in prefix {}.", prefix.clone().bold());
+        }
+        return Ok(apply_report_set);
+    }
+
+    if output.is_human() {
+        print_frame_start();
+        for plan_report in &plan_reports {
+            print_plan(plan_report);
+        }
+        print_frame_end();
+    }
+
+    check_protected(&plan_reports, allow_destroy_protected)?;
+    check_policy(&plan_reports, override_policy, output)?;
+
+    if !skip_confirm {
+        println!(
+            " ⊬ This will destroy {} remote resources in prefix {}.",
+            plan_reports.len(),
+            prefix.clone().bold()
+        );
+
+        let typed: String = Input::new()
+            .with_prompt(format!(" ◈ Type the prefix name {prefix} to confirm"))
+            .allow_empty(true)
+            .interact_text()
+            .expect("Exiting...");
+
+        if typed.trim() != prefix {
+            bail!("Prefix name did not match, so nothing was destroyed.");
+        }
+    }
+
+    let journal = Journal::create(&plan_reports)?;
+
+    let mut wrote_files = false;
+
+    if output.is_human() {
+        print_frame_start();
+    }
+    for plan_report in &plan_reports {
+        let spinner_stop = show_spinner().await;
+
+        let Some(apply_report) = apply_plan_report_from(&config, plan_report, &journal, 0).await? else {
+            spinner_stop.send(()).unwrap();
+            continue;
+        };
+
+        spinner_stop.send(()).unwrap();
+
+        output.emit("apply_report", &apply_report)?;
+
+        if output.is_human() {
+            print_plan_addr(plan_report);
+            print_apply_outputs(&apply_report);
+        }
+
+        for path in &apply_report.wrote_files {
+            git_add(&repo_root, path)?;
+            wrote_files = true;
+        }
+
+        if remove_files {
+            for path in remove_local_files(plan_report)? {
+                git_rm(&repo_root, &path)?;
+                wrote_files = true;
+            }
+        }
+
+        apply_report_set.push(apply_report);
+    }
+    if output.is_human() {
+        print_frame_end();
+    }
+
+    journal.complete()?;
+
+    if wrote_files && !skip_commit && output.is_human() {
+        prompt_git_commit();
+    }
+
+    Ok(apply_report_set)
+}
+
+/// Remove the resource file and output maps for a destroyed resource, returning the paths removed.
+fn remove_local_files(plan_report: &PlanReport) -> anyhow::Result<Vec<PathBuf>> {
+    let mut removed = Vec::new();
+
+    let path = plan_report.prefix.join(&plan_report.virt_addr);
+    if path.is_file() {
+        std::fs::remove_file(&path)?;
+        removed.push(path);
+    }
+
+    let mut addrs: Vec<&Path> = vec![&plan_report.virt_addr];
+    if let Some(phy_addr) = &plan_report.phy_addr {
+        addrs.push(phy_addr);
+    }
+
+    for addr in addrs {
+        if let Some(output_path) = OutputMapFile::delete(&plan_report.prefix, addr)? {
+            removed.push(output_path);
+        }
+    }
+
+    Ok(removed)
+}
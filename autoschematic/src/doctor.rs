@@ -0,0 +1,353 @@
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use anyhow::bail;
+use autoschematic_core::{
+    config,
+    connector::{Connector, handle::ConnectorHandle, spawn::spawn_connector},
+    connector_cache::connector_env,
+    connector_util::check_connector_host_version_match,
+    lockfile::AutoschematicLockfile,
+    util::load_autoschematic_config,
+};
+use crossterm::style::Stylize;
+use serde::Serialize;
+
+use crate::{output::Output, spinner::show_spinner};
+
+#[derive(Serialize)]
+struct Finding {
+    /// None for checks of the host rather than of a single connector.
+    prefix: Option<PathBuf>,
+    connector: Option<String>,
+    check: &'static str,
+    ok: bool,
+    message: String,
+    /// What to do about a failed check.
+    hint: Option<String>,
+}
+
+#[derive(Default)]
+struct Findings {
+    findings: Vec<Finding>,
+}
+
+impl Findings {
+    fn pass(&mut self, prefix: Option<&Path>, connector: Option<&str>, check: &'static str, message: impl Into<String>) {
+        self.findings.push(Finding {
+            prefix: prefix.map(PathBuf::from),
+            connector: connector.map(String::from),
+            check,
+            ok: true,
+            message: message.into(),
+            hint: None,
+        });
+    }
+
+    fn fail(
+        &mut self,
+        prefix: Option<&Path>,
+        connector: Option<&str>,
+        check: &'static str,
+        message: impl Into<String>,
+        hint: impl Into<String>,
+    ) {
+        self.findings.push(Finding {
+            prefix: prefix.map(PathBuf::from),
+            connector: connector.map(String::from),
+            check,
+            ok: false,
+            message: message.into(),
+            hint: Some(hint.into()),
+        });
+    }
+}
+
+/// Check that every connector in autoschematic.ron can be launched and initialized,
+/// and report what to fix for those that can't.
+pub async fn doctor(
+    prefix_filter: Option<String>,
+    connector_filter: Option<String>,
+    timeout: u64,
+    output: &Output,
+) -> anyhow::Result<()> {
+    let config = load_autoschematic_config()?;
+
+    if let Some(prefix_filter) = &prefix_filter
+        && !config.prefixes.contains_key(prefix_filter)
+    {
+        bail!("No prefix named {} in autoschematic.ron", prefix_filter);
+    }
+
+    let mut findings = Findings::default();
+
+    check_sandbox(&mut findings);
+
+    for (prefix_name, prefix_def) in &config.prefixes {
+        if prefix_filter
+            .as_ref()
+            .is_some_and(|prefix_filter| prefix_filter != prefix_name)
+        {
+            continue;
+        }
+
+        for connector_def in &prefix_def.connectors {
+            if connector_filter
+                .as_ref()
+                .is_some_and(|connector_filter| *connector_filter != connector_def.shortname)
+            {
+                continue;
+            }
+
+            let spinner_stop = show_spinner().await;
+            check_connector(&mut findings, Path::new(prefix_name), prefix_def, connector_def, timeout).await;
+            spinner_stop.send(()).unwrap();
+        }
+    }
+
+    let failed = findings.findings.iter().filter(|finding| !finding.ok).count();
+
+    for finding in &findings.findings {
+        if output.is_human() {
+            let subject = match (&finding.prefix, &finding.connector) {
+                (Some(prefix), Some(connector)) => format!("{}/{}", prefix.display(), connector),
+                _ => String::from("host"),
+            };
+
+            if finding.ok {
+                println!(" {} {} {}: {}", "✓".green(), subject.bold(), finding.check, finding.message);
+            } else {
+                println!(" {} {} {}: {}", "✗".red(), subject.bold(), finding.check, finding.message);
+                if let Some(hint) = &finding.hint {
+                    println!("    ↪ {}", hint.clone().yellow());
+                }
+            }
+        }
+
+        output.emit("finding", finding)?;
+    }
+
+    if failed > 0 {
+        bail!("{failed} check(s) failed.");
+    }
+
+    if output.is_human() {
+        println!(" All checks passed.");
+    }
+
+    Ok(())
+}
+
+/// Check a single connector, stopping at the first check that leaves it unable to launch.
+async fn check_connector(
+    findings: &mut Findings,
+    prefix: &Path,
+    prefix_def: &config::Prefix,
+    connector_def: &config::Connector,
+    timeout: u64,
+) {
+    let shortname = connector_def.shortname.as_str();
+    let spec = &connector_def.spec;
+
+    match spec.command() {
+        Ok(command) => {
+            let found = command.binary.is_file() || which::which(&command.binary).is_ok();
+            let command_line = format!("{} {}", command.binary.display(), command.args.join(" "));
+            if found {
+                findings.pass(Some(prefix), Some(shortname), "command", command_line.trim_end());
+            } else {
+                findings.fail(
+                    Some(prefix),
+                    Some(shortname),
+                    "command",
+                    format!("{}: not found", command.binary.display()),
+                    "Run `autoschematic install`, or fix the connector's spec in autoschematic.ron.",
+                );
+                return;
+            }
+        }
+        Err(e) => {
+            findings.fail(
+                Some(prefix),
+                Some(shortname),
+                "command",
+                format!("{e:#}"),
+                "Run `autoschematic install`, or fix the connector's spec in autoschematic.ron.",
+            );
+            return;
+        }
+    }
+
+    if let Err(e) = AutoschematicLockfile::load().and_then(|lockfile| match lockfile {
+        Some(lockfile) => lockfile.verify(prefix, shortname, spec),
+        None => Ok(()),
+    }) {
+        findings.fail(
+            Some(prefix),
+            Some(shortname),
+            "lockfile",
+            format!("{e:#}"),
+            "Run `autoschematic install` to install the pinned build, or `autoschematic install --update-lock` to re-pin it.",
+        );
+        return;
+    }
+
+    let env = match connector_env(prefix_def, connector_def) {
+        Ok(env) => env,
+        Err(e) => {
+            findings.fail(
+                Some(prefix),
+                Some(shortname),
+                "env",
+                format!("{e:#}"),
+                "Create the env file, or remove env_file from autoschematic.ron.",
+            );
+            return;
+        }
+    };
+
+    let mut missing_vars: Vec<(&String, &str)> = env
+        .iter()
+        .filter_map(|(dest_key, value)| {
+            let src_key = value.strip_prefix("env://")?;
+            std::env::var(src_key).is_err().then_some((dest_key, src_key))
+        })
+        .collect();
+    missing_vars.sort();
+
+    if missing_vars.is_empty() {
+        findings.pass(
+            Some(prefix),
+            Some(shortname),
+            "env",
+            format!("{} variables resolve", env.len()),
+        );
+    } else {
+        for (dest_key, src_key) in missing_vars {
+            findings.fail(
+                Some(prefix),
+                Some(shortname),
+                "env",
+                format!("{dest_key} passes through ${src_key}, which is not set"),
+                format!("export {src_key}=..."),
+            );
+        }
+        return;
+    }
+
+    let (connector, mut inbox) = match spawn_connector(shortname, spec, prefix, &env, None).await {
+        Ok(spawned) => spawned,
+        Err(e) => {
+            findings.fail(
+                Some(prefix),
+                Some(shortname),
+                "spawn",
+                format!("{e:#}"),
+                "Check the connector's error output above, and that it was built for this platform.",
+            );
+            return;
+        }
+    };
+
+    // The connector's output isn't interesting here, but its outbox must be drained.
+    let _reader_handle = tokio::spawn(async move { while inbox.recv().await.is_ok() {} });
+
+    match check_connector_host_version_match(shortname, &connector).await {
+        Ok(()) => findings.pass(Some(prefix), Some(shortname), "version", "matches autoschematic"),
+        Err(e) => findings.fail(
+            Some(prefix),
+            Some(shortname),
+            "version",
+            format!("{e:#}"),
+            format!(
+                "Install a build of the connector for autoschematic {}.",
+                env!("CARGO_PKG_VERSION")
+            ),
+        ),
+    }
+
+    match tokio::time::timeout(Duration::from_secs(timeout), connector.init()).await {
+        Ok(Ok(())) => findings.pass(Some(prefix), Some(shortname), "init", "initialized"),
+        Ok(Err(e)) => findings.fail(
+            Some(prefix),
+            Some(shortname),
+            "init",
+            format!("{e:#}"),
+            "Check the connector's credentials and configuration files in this prefix.",
+        ),
+        Err(_) => findings.fail(
+            Some(prefix),
+            Some(shortname),
+            "init",
+            format!("init() did not complete within {timeout}s"),
+            "Check the connector's network access, or retry with a longer --timeout.",
+        ),
+    }
+
+    let _ = connector.kill().await;
+}
+
+#[cfg(target_os = "linux")]
+fn check_sandbox(findings: &mut Findings) {
+    use autoschematic_core::connector::spawn::is_sandbox_enabled;
+
+    if !is_sandbox_enabled() {
+        findings.pass(None, None, "sandbox", "disabled; set AUTOSCHEMATIC_SANDBOX=true to enable it");
+        return;
+    }
+
+    match std::env::var("AUTOSCHEMATIC_SANDBOX_ROOT") {
+        Ok(root) if Path::new(&root).is_dir() => {
+            findings.pass(None, None, "sandbox", format!("AUTOSCHEMATIC_SANDBOX_ROOT is {root}"))
+        }
+        Ok(root) => findings.fail(
+            None,
+            None,
+            "sandbox",
+            format!("AUTOSCHEMATIC_SANDBOX_ROOT is {root}, which is not a directory"),
+            "Point AUTOSCHEMATIC_SANDBOX_ROOT at an unpacked root filesystem for connectors to run in.",
+        ),
+        Err(_) => findings.fail(
+            None,
+            None,
+            "sandbox",
+            "AUTOSCHEMATIC_SANDBOX_ROOT is not set",
+            "Point AUTOSCHEMATIC_SANDBOX_ROOT at an unpacked root filesystem, or set AUTOSCHEMATIC_SANDBOX=false.",
+        ),
+    }
+
+    let read_sysctl = |path: &str| std::fs::read_to_string(path).ok().map(|s| s.trim().to_string());
+
+    if read_sysctl("/proc/sys/user/max_user_namespaces").as_deref() == Some("0") {
+        findings.fail(
+            None,
+            None,
+            "sandbox",
+            "user namespaces are disabled (user.max_user_namespaces = 0)",
+            "sysctl -w user.max_user_namespaces=15000, or set AUTOSCHEMATIC_SANDBOX=false.",
+        );
+    } else if read_sysctl("/proc/sys/kernel/unprivileged_userns_clone").as_deref() == Some("0") {
+        findings.fail(
+            None,
+            None,
+            "sandbox",
+            "unprivileged user namespaces are disabled (kernel.unprivileged_userns_clone = 0)",
+            "sysctl -w kernel.unprivileged_userns_clone=1, or set AUTOSCHEMATIC_SANDBOX=false.",
+        );
+    } else if read_sysctl("/proc/sys/kernel/apparmor_restrict_unprivileged_userns").as_deref() == Some("1") {
+        findings.fail(
+            None,
+            None,
+            "sandbox",
+            "AppArmor restricts unprivileged user namespaces",
+            "sysctl -w kernel.apparmor_restrict_unprivileged_userns=0, or set AUTOSCHEMATIC_SANDBOX=false.",
+        );
+    } else {
+        findings.pass(None, None, "sandbox", "user namespaces are available");
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn check_sandbox(_findings: &mut Findings) {}
@@ -0,0 +1,331 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::bail;
+use autoschematic_core::{
+    config::AutoschematicConfig,
+    connector::FilterResponse,
+    glob::addr_matches_filter,
+    util::{diff_text_markdown, list_prefix_addrs, load_autoschematic_config},
+    workflow,
+};
+use crossterm::style::Stylize;
+use tokio::{sync::Semaphore, task::JoinSet};
+
+use crate::{
+    CONNECTOR_CACHE,
+    check_drift::{CheckDriftRecord, describe_changed_outputs},
+    output::{ExitStatus, Output},
+    spinner::show_spinner,
+};
+
+#[derive(Default)]
+struct DriftCounts {
+    checked: usize,
+    equal: usize,
+    drifted: usize,
+    errors: usize,
+}
+
+/// Check every resource file in the repository for drift against its remote state.
+pub async fn drift(
+    prefix_filter: Option<String>,
+    connector_filter: Option<String>,
+    subpath_filter: Option<String>,
+    concurrency: usize,
+    junit: Option<PathBuf>,
+    output: &Output,
+) -> anyhow::Result<()> {
+    let config = Arc::new(load_autoschematic_config()?);
+
+    let subpath = subpath_filter.map(PathBuf::from).unwrap_or(PathBuf::from("./"));
+
+    let semaphore = Arc::new(Semaphore::new(concurrency.max(1)));
+
+    let spinner_stop = show_spinner().await;
+
+    let mut joinset: JoinSet<Option<CheckDriftRecord>> = JoinSet::new();
+
+    for (prefix_name, prefix_def) in &config.prefixes {
+        if let Some(prefix_filter) = &prefix_filter
+            && prefix_name != prefix_filter
+        {
+            continue;
+        }
+
+        let connector_shortnames: Vec<String> = prefix_def
+            .connectors
+            .iter()
+            .filter(|c| connector_filter.as_ref().is_none_or(|f| c.shortname == *f))
+            .map(|c| c.shortname.clone())
+            .collect();
+
+        // Spawn connectors up front, so that the concurrent checks below don't race to spawn them.
+        for connector_def in &prefix_def.connectors {
+            if !connector_shortnames.contains(&connector_def.shortname) {
+                continue;
+            }
+
+            let (_connector, mut inbox) = CONNECTOR_CACHE
+                .get_or_spawn_connector(&config, prefix_name, connector_def, None, true)
+                .await?;
+
+            let _reader_handle = tokio::spawn(async move {
+                loop {
+                    match inbox.recv().await {
+                        Ok(Some(stdout)) => {
+                            eprintln!("{}", stdout.to_string_lossy());
+                        }
+                        Ok(None) => {}
+                        Err(_) => break,
+                    }
+                }
+            });
+        }
+
+        let prefix = PathBuf::from(prefix_name);
+
+        for virt_addr in list_prefix_addrs(&prefix) {
+            if !addr_matches_filter(&virt_addr, &subpath) {
+                continue;
+            }
+
+            let config = config.clone();
+            let semaphore = semaphore.clone();
+            let prefix = prefix.clone();
+            let connector_shortnames = connector_shortnames.clone();
+            let connector_filter = connector_filter.clone();
+            joinset.spawn(async move {
+                let _permit = semaphore.acquire_owned().await.ok()?;
+
+                match check_addr_drift(
+                    &config,
+                    &connector_shortnames,
+                    connector_filter.as_deref(),
+                    &prefix,
+                    &virt_addr,
+                )
+                .await
+                {
+                    Ok(Some(result)) => Some(CheckDriftRecord::new(prefix, virt_addr, &result)),
+                    Ok(None) => None,
+                    Err(e) => Some(CheckDriftRecord::error(prefix, virt_addr, &e)),
+                }
+            });
+        }
+    }
+
+    let mut records = Vec::new();
+    while let Some(res) = joinset.join_next().await {
+        if let Some(record) = res? {
+            records.push(record);
+        }
+    }
+
+    spinner_stop.send(()).unwrap();
+
+    records.sort_by(|a, b| (&a.prefix, &a.addr).cmp(&(&b.prefix, &b.addr)));
+
+    let mut counts: BTreeMap<PathBuf, DriftCounts> = BTreeMap::new();
+    for record in &records {
+        output.emit("check_drift", record)?;
+
+        let prefix_counts = counts.entry(record.prefix.clone()).or_default();
+        prefix_counts.checked += 1;
+        match record.result {
+            "equal" | "neither_exist" => prefix_counts.equal += 1,
+            "error" => prefix_counts.errors += 1,
+            _ => prefix_counts.drifted += 1,
+        }
+    }
+
+    if output.is_human() {
+        print_drift_summary(&records, &counts);
+    }
+
+    if let Some(junit) = junit {
+        write_junit_report(&junit, &records, &counts)?;
+        if output.is_human() {
+            println!(" ◇ Wrote JUnit report to {}.", junit.display().to_string().bold());
+        }
+    }
+
+    let drifted: usize = counts.values().map(|c| c.drifted).sum();
+    let errors: usize = counts.values().map(|c| c.errors).sum();
+
+    if errors > 0 {
+        bail!("Failed to check drift for {} resources.", errors);
+    }
+
+    if drifted > 0 {
+        if !output.is_human() {
+            output.set_status(ExitStatus::DriftFound);
+            return Ok(());
+        }
+        bail!("{} resources have drifted.", drifted);
+    }
+
+    Ok(())
+}
+
+/// Check drift at `prefix`/`virt_addr`, returning None if none of the given connectors
+/// consider it a resource. With `connector_filter` set, only that connector is queried.
+async fn check_addr_drift(
+    config: &AutoschematicConfig,
+    connector_shortnames: &[String],
+    connector_filter: Option<&str>,
+    prefix: &Path,
+    virt_addr: &Path,
+) -> anyhow::Result<Option<workflow::check_drift::CheckDriftResult>> {
+    let mut is_resource = false;
+    for shortname in connector_shortnames {
+        if CONNECTOR_CACHE.filter_cached(shortname, prefix, virt_addr).await? == FilterResponse::Resource {
+            is_resource = true;
+            break;
+        }
+    }
+
+    if !is_resource {
+        return Ok(None);
+    }
+
+    match workflow::check_drift::check_drift(config, &CONNECTOR_CACHE, None, connector_filter, prefix, virt_addr).await? {
+        workflow::check_drift::CheckDriftResult::InvalidAddress => Ok(None),
+        result => Ok(Some(result)),
+    }
+}
+
+fn print_drift_summary(records: &[CheckDriftRecord], counts: &BTreeMap<PathBuf, DriftCounts>) {
+    if counts.is_empty() {
+        println!(" ∅  No resource files matched.");
+        return;
+    }
+
+    let prefix_width = counts
+        .keys()
+        .map(|p| p.to_string_lossy().len())
+        .max()
+        .unwrap_or_default()
+        .max("PREFIX".len());
+
+    println!(
+        "   {:<prefix_width$}  {:>7}  {:>7}  {:>7}  {:>7}",
+        "PREFIX", "CHECKED", "EQUAL", "DRIFTED", "ERRORS"
+    );
+    for (prefix, c) in counts {
+        println!(
+            "   {:<prefix_width$}  {:>7}  {:>7}  {:>7}  {:>7}",
+            prefix.to_string_lossy(),
+            c.checked,
+            c.equal,
+            c.drifted,
+            c.errors
+        );
+    }
+
+    let drifted: Vec<&CheckDriftRecord> = records
+        .iter()
+        .filter(|r| !matches!(r.result, "equal" | "neither_exist" | "error"))
+        .collect();
+    if !drifted.is_empty() {
+        println!(" ⊬ Drifted:");
+        for record in drifted {
+            println!(
+                "    {}/{}",
+                record.prefix.display().to_string().dark_grey(),
+                record.addr.display().to_string().bold()
+            );
+        }
+    }
+
+    let errors: Vec<&CheckDriftRecord> = records.iter().filter(|r| r.result == "error").collect();
+    if !errors.is_empty() {
+        println!(" ⊬ Errors:");
+        for record in errors {
+            println!(
+                "    {}/{}: {}",
+                record.prefix.display().to_string().dark_grey(),
+                record.addr.display().to_string().bold(),
+                record.error.as_deref().unwrap_or_default().red()
+            );
+        }
+    }
+
+    if counts.values().all(|c| c.drifted == 0 && c.errors == 0) {
+        println!(" ≡ No drift found.");
+    }
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Write a JUnit XML report with a test suite per prefix and a test case per resource,
+/// for CI systems that render them.
+fn write_junit_report(
+    path: &Path,
+    records: &[CheckDriftRecord],
+    counts: &BTreeMap<PathBuf, DriftCounts>,
+) -> anyhow::Result<()> {
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+
+    xml.push_str(&format!(
+        "<testsuites name=\"autoschematic drift\" tests=\"{}\" failures=\"{}\" errors=\"{}\">\n",
+        records.len(),
+        counts.values().map(|c| c.drifted).sum::<usize>(),
+        counts.values().map(|c| c.errors).sum::<usize>()
+    ));
+
+    for (prefix, c) in counts {
+        let prefix_name = xml_escape(&prefix.to_string_lossy());
+        xml.push_str(&format!(
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\">\n",
+            prefix_name, c.checked, c.drifted, c.errors
+        ));
+
+        for record in records.iter().filter(|r| r.prefix == *prefix) {
+            xml.push_str(&format!(
+                "    <testcase classname=\"{}\" name=\"{}\"",
+                prefix_name,
+                xml_escape(&record.addr.to_string_lossy())
+            ));
+
+            match record.result {
+                "equal" | "neither_exist" => xml.push_str("/>\n"),
+                "error" => xml.push_str(&format!(
+                    ">\n      <error message=\"{}\"/>\n    </testcase>\n",
+                    xml_escape(record.error.as_deref().unwrap_or_default())
+                )),
+                result => {
+                    let body = match (&record.current, &record.desired, &record.changed_outputs) {
+                        (Some(current), Some(desired), _) => diff_text_markdown(current, desired)?,
+                        (_, _, Some(changed)) => describe_changed_outputs(changed)
+                            .into_iter()
+                            .map(|line| line + "\n")
+                            .collect(),
+                        _ => String::new(),
+                    };
+                    xml.push_str(&format!(
+                        ">\n      <failure message=\"Resource has drifted ({})\">{}</failure>\n    </testcase>\n",
+                        result,
+                        xml_escape(&body)
+                    ));
+                }
+            }
+        }
+
+        xml.push_str("  </testsuite>\n");
+    }
+
+    xml.push_str("</testsuites>\n");
+
+    std::fs::write(path, xml)?;
+    Ok(())
+}
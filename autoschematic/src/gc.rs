@@ -0,0 +1,55 @@
+use autoschematic_core::{
+    git_util::git_rm,
+    util::{load_autoschematic_config, repo_root},
+    workflow::gc::find_garbage,
+};
+use crossterm::style::Stylize;
+
+use crate::output::Output;
+
+pub fn gc(prefix: Option<String>, dry_run: bool, no_stage: bool, output: &Output) -> anyhow::Result<()> {
+    let config = load_autoschematic_config()?;
+    let repo_root = repo_root()?;
+
+    let garbage = find_garbage(&config, prefix.as_deref())?;
+
+    if output.is_human() && garbage.is_empty() {
+        println!(" ∅  No orphaned output or bundle map files found.");
+        return Ok(());
+    }
+
+    for item in &garbage {
+        output.emit("garbage", item)?;
+
+        if output.is_human() {
+            println!(
+                " {} {}: {}",
+                if dry_run { "∙" } else { "✗" },
+                item.path.display().to_string().bold(),
+                item.kind
+            );
+        }
+
+        if dry_run {
+            continue;
+        }
+
+        std::fs::remove_file(&item.path)?;
+        if !no_stage {
+            git_rm(&repo_root, &item.path)?;
+        }
+    }
+
+    if output.is_human() {
+        if dry_run {
+            println!(
+                " Found {} orphaned map files. Run without --dry-run to remove them.",
+                garbage.len()
+            );
+        } else {
+            println!(" Removed {} orphaned map files.", garbage.len());
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,154 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::bail;
+use autoschematic_core::{
+    template::template_config,
+    util::{RON, diff_ron_values, diff_text_markdown, load_autoschematic_config, split_prefix_addr},
+    workflow,
+};
+use crossterm::style::Stylize;
+use serde::Serialize;
+
+use crate::{
+    CONNECTOR_CACHE,
+    output::{ExitStatus, Output},
+    spinner::show_spinner,
+};
+
+#[derive(Serialize)]
+struct GetRecord {
+    prefix: PathBuf,
+    addr: PathBuf,
+    /// The current remote body of the resource, or None if it doesn't exist.
+    current: Option<String>,
+}
+
+#[derive(Serialize)]
+struct DiffRecord {
+    prefix: PathBuf,
+    addr: PathBuf,
+    equal: bool,
+    /// A markdown diff from the current remote body to the templated local file.
+    diff: Option<String>,
+    /// out:// references in the local file that have no value yet, and so are diffed as written.
+    missing_outputs: Vec<String>,
+}
+
+/// Fetch the current remote body of the resource at `path`.
+async fn get_current(path: &Path) -> anyhow::Result<(PathBuf, PathBuf, Option<String>)> {
+    let config = load_autoschematic_config()?;
+
+    let Some((prefix, addr)) = split_prefix_addr(&config, path) else {
+        bail!("{} is not in any prefix in autoschematic.ron", path.display());
+    };
+
+    let spinner_stop = show_spinner().await;
+    let current = workflow::get::get(&config, &CONNECTOR_CACHE, None, &prefix, &addr).await;
+    spinner_stop.send(()).unwrap();
+
+    let current = match current? {
+        Some(body) => Some(String::from_utf8(body)?),
+        None => None,
+    };
+
+    Ok((prefix, addr, current))
+}
+
+pub async fn get(path: &Path, output: &Output) -> anyhow::Result<()> {
+    let (prefix, addr, current) = get_current(path).await?;
+
+    if output.is_human() {
+        match &current {
+            Some(current) => print!("{current}"),
+            None => eprintln!(" ∅  {} does not exist remotely.", path.display()),
+        }
+    }
+
+    output.emit("get", &GetRecord { prefix, addr, current })
+}
+
+/// Diff `current` against `desired`, field by field where both parse as RON, or line by line otherwise.
+fn diff_bodies(current: &str, desired: &str) -> anyhow::Result<String> {
+    match (RON.from_str::<ron::Value>(current), RON.from_str::<ron::Value>(desired)) {
+        (Ok(current), Ok(desired)) => diff_ron_values(&current, &desired),
+        _ => diff_text_markdown(current, desired),
+    }
+}
+
+fn print_diff(diff: &str) {
+    for line in diff.lines().filter(|line| !line.starts_with("```")) {
+        if line.starts_with('-') {
+            println!("{}", line.red());
+        } else if line.starts_with('+') {
+            println!("{}", line.green());
+        } else {
+            println!("{line}");
+        }
+    }
+}
+
+pub async fn diff(path: &Path, output: &Output) -> anyhow::Result<()> {
+    let (prefix, addr, current) = get_current(path).await?;
+
+    let mut missing_outputs = Vec::new();
+    let desired = match std::fs::read_to_string(prefix.join(&addr)) {
+        Ok(local) => {
+            let template_result = template_config(&prefix, &local)?;
+            missing_outputs = template_result
+                .missing
+                .iter()
+                .map(|read_output| read_output.into_string())
+                .collect();
+            missing_outputs.sort();
+            Some(template_result.body)
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => return Err(e.into()),
+    };
+
+    let diff = match (&current, &desired) {
+        (None, None) => bail!("{} exists neither locally nor remotely", path.display()),
+        (Some(current), Some(desired)) => {
+            let diff = diff_bodies(current, desired)?;
+            // A diff with no changed lines means the two are equivalent.
+            diff.lines().any(|line| line.starts_with(['-', '+'])).then_some(diff)
+        }
+        (current, desired) => Some(diff_text_markdown(
+            current.as_deref().unwrap_or_default(),
+            desired.as_deref().unwrap_or_default(),
+        )?),
+    };
+
+    if output.is_human() {
+        for read_output in &missing_outputs {
+            println!(" ⚠  {} has no value yet", read_output.clone().yellow());
+        }
+
+        match (&diff, &current, &desired) {
+            (None, _, _) => println!(" ≡ {} matches its remote state.", path.display()),
+            (Some(diff), current, desired) => {
+                if current.is_none() {
+                    println!(" {} does not exist remotely.", path.display());
+                } else if desired.is_none() {
+                    println!(" {} exists remotely, but not locally.", path.display());
+                }
+                println!("{}", "--- remote".red());
+                println!("{}", "+++ local".green());
+                print_diff(diff);
+            }
+        }
+    } else if diff.is_some() {
+        output.set_status(ExitStatus::DriftFound);
+    }
+
+    output.emit(
+        "diff",
+        &DiffRecord {
+            prefix,
+            addr,
+            equal: diff.is_none(),
+            diff,
+            missing_outputs,
+        },
+    )
+}
@@ -0,0 +1,80 @@
+use autoschematic_core::{
+    graph::{EdgeKind, ResourceGraph, ResourceNode, add_deferred_edges, build_graph},
+    util::load_autoschematic_config,
+};
+use crossterm::style::Stylize;
+use serde::Serialize;
+
+use crate::{
+    CONNECTOR_CACHE,
+    output::{Output, OutputFormat},
+    spinner::show_spinner,
+};
+
+#[derive(Serialize)]
+struct GraphRecord<'a> {
+    #[serde(flatten)]
+    graph: &'a ResourceGraph,
+    /// The order in which resources would be applied, or None if the graph has a cycle.
+    order: Option<Vec<ResourceNode>>,
+    cycle: Option<Vec<ResourceNode>>,
+}
+
+/// Print the dependency graph between resources.
+pub async fn graph(prefix_filter: Option<String>, connector_filter: Option<String>, output: &Output) -> anyhow::Result<()> {
+    let config = load_autoschematic_config()?;
+
+    let spinner_stop = show_spinner().await;
+
+    let mut graph = build_graph(&config, prefix_filter.as_deref())?;
+
+    let res = add_deferred_edges(&mut graph, &config, &CONNECTOR_CACHE, None, connector_filter.as_deref()).await;
+
+    spinner_stop.send(()).unwrap();
+    res?;
+
+    let order = graph.topological_order();
+
+    match output.format {
+        OutputFormat::Dot => print!("{}", graph.to_dot()),
+        OutputFormat::Human => print_graph(&graph, order.as_deref().ok()),
+        OutputFormat::Env => unreachable!("--format env is rejected for graph"),
+        OutputFormat::Json | OutputFormat::Ndjson => {
+            output.emit(
+                "graph",
+                &GraphRecord {
+                    graph: &graph,
+                    order: order.as_ref().ok().cloned(),
+                    cycle: graph.find_cycle(),
+                },
+            )?;
+        }
+    }
+
+    order.map(|_| ())
+}
+
+fn print_graph(graph: &ResourceGraph, order: Option<&[ResourceNode]>) {
+    if graph.nodes.is_empty() {
+        println!(" ∅  No resource files found.");
+        return;
+    }
+
+    let nodes: Vec<&ResourceNode> = match order {
+        Some(order) => order.iter().collect(),
+        None => graph.nodes.iter().collect(),
+    };
+
+    for node in nodes {
+        println!(" ◇ {}", node.path().display().to_string().bold());
+        for edge in graph.dependencies(node) {
+            let kind = match &edge.kind {
+                EdgeKind::ReadsOutput { key } => format!("[{key}]"),
+                EdgeKind::Deferred { key } => format!("[{key}] (deferred)"),
+                EdgeKind::ChildOf => String::from("(bundle)"),
+                EdgeKind::Transitive => String::from("(transitive)"),
+            };
+            println!("    ⟵ {} {}", edge.from.path().display(), kind.dark_grey());
+        }
+    }
+}
@@ -15,7 +15,7 @@ use crossterm::style::Stylize;
 use dialoguer::{Confirm, MultiSelect};
 use tokio::{sync::Semaphore, task::JoinSet};
 
-use crate::CONNECTOR_CACHE;
+use crate::{CONNECTOR_CACHE, output::Output};
 
 pub async fn import(
     prefix: Option<String>,
@@ -23,6 +23,7 @@ pub async fn import(
     subpath: Option<String>,
     overwrite: bool,
     commit: Option<bool>,
+    output: &Output,
 ) -> anyhow::Result<()> {
     let config = load_autoschematic_config()?;
     let config = Arc::new(config);
@@ -116,11 +117,15 @@ pub async fn import(
     }
 
     if total_connectors == 0 {
-        println!(" ∅ Selection matched no connectors, or your prefix(es) are empty.");
+        if output.is_human() {
+            println!(" ∅ Selection matched no connectors, or your prefix(es) are empty.");
+        }
         return Ok(());
     }
 
-    println!(" Starting import. This may take a while!");
+    if output.is_human() {
+        println!(" Starting import. This may take a while!");
+    }
 
     let repo_root = repo_root()?;
     let mut wrote_files = false;
@@ -133,9 +138,19 @@ pub async fn import(
             let reader_handle: tokio::task::JoinHandle<anyhow::Result<Vec<PathBuf>>> = {
                 let prefix_name = prefix_name.clone();
                 let connector_name = connector_name.clone();
+                let output = output.clone();
                 tokio::spawn(async move {
                     let mut written_files = Vec::new();
                     while let Some(msg) = receiver.recv().await {
+                        output.emit("import_message", &msg)?;
+
+                        if !output.is_human() {
+                            if let ImportMessage::WroteFile { path } = msg {
+                                written_files.push(path);
+                            }
+                            continue;
+                        }
+
                         match msg {
                             ImportMessage::StartImport { subpath } => {
                                 println!(
@@ -207,11 +222,14 @@ pub async fn import(
         }
     }
 
-    println!("{}", " Success!".dark_green());
+    if output.is_human() {
+        println!("{}", " Success!".dark_green());
+    }
 
     if wrote_files {
         let do_commit = match commit {
             Some(commit) => commit,
+            None if !output.is_human() => false,
             None => Confirm::new()
                 .with_prompt(" ◈ Import succeeded! Do you wish to run git commit to track the imported files?")
                 .default(true)
@@ -223,7 +241,11 @@ pub async fn import(
             Command::new("git")
                 .arg("commit")
                 .stdin(Stdio::inherit())
-                .stdout(Stdio::inherit())
+                .stdout(if output.is_human() {
+                    Stdio::inherit()
+                } else {
+                    Stdio::from(std::io::stderr())
+                })
                 .output()
                 .expect("git commit: failed to execute");
         }
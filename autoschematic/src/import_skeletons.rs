@@ -0,0 +1,116 @@
+use std::path::PathBuf;
+
+use anyhow::bail;
+use autoschematic_core::{
+    git_util::git_add,
+    glob::addr_matches_filter,
+    util::{load_autoschematic_config, repo_root},
+    workflow::get_skeletons::get_skeletons,
+};
+use crossterm::style::Stylize;
+use serde::Serialize;
+
+use crate::{CONNECTOR_CACHE, output::Output, spinner::show_spinner};
+
+#[derive(Serialize)]
+struct SkeletonRecord {
+    prefix: PathBuf,
+    addr: PathBuf,
+    connector: String,
+    /// Whether the skeleton was written, as opposed to skipped because a file already exists at its address.
+    wrote: bool,
+}
+
+/// For each connector, write its skeleton resources into its prefix as examples to start from.
+pub async fn import_skeletons(
+    prefix_filter: Option<String>,
+    connector_filter: Option<String>,
+    subpath: Option<String>,
+    overwrite: bool,
+    git_stage: bool,
+    output: &Output,
+) -> anyhow::Result<()> {
+    let config = load_autoschematic_config()?;
+    let repo_root = repo_root()?;
+    let subpath = PathBuf::from(subpath.unwrap_or(String::from("./")));
+
+    if let Some(prefix_filter) = &prefix_filter
+        && !config.prefixes.contains_key(prefix_filter)
+    {
+        bail!("No prefix named {} in autoschematic.ron", prefix_filter);
+    }
+
+    let mut wrote_count = 0;
+
+    for (prefix_name, prefix_def) in &config.prefixes {
+        if prefix_filter
+            .as_ref()
+            .is_some_and(|prefix_filter| prefix_filter != prefix_name)
+        {
+            continue;
+        }
+
+        let prefix = PathBuf::from(prefix_name);
+
+        for connector_def in &prefix_def.connectors {
+            if connector_filter
+                .as_ref()
+                .is_some_and(|connector_filter| *connector_filter != connector_def.shortname)
+            {
+                continue;
+            }
+
+            let spinner_stop = show_spinner().await;
+            let skeletons = get_skeletons(&config, &CONNECTOR_CACHE, None, &prefix, connector_def).await;
+            spinner_stop.send(()).unwrap();
+
+            for skeleton in skeletons? {
+                if !addr_matches_filter(&skeleton.addr, &subpath) {
+                    continue;
+                }
+
+                let path = prefix.join(&skeleton.addr);
+                let wrote = overwrite || !path.exists();
+
+                if wrote {
+                    if let Some(parent) = path.parent() {
+                        std::fs::create_dir_all(parent)?;
+                    }
+                    std::fs::write(&path, &skeleton.body)?;
+
+                    if git_stage {
+                        git_add(&repo_root, &path)?;
+                    }
+                    wrote_count += 1;
+                }
+
+                if output.is_human() {
+                    if wrote {
+                        println!(" ⇣ {}", path.display().to_string().bold());
+                    } else {
+                        println!(
+                            " ∙ {} already exists, skipping. Use --overwrite to replace it.",
+                            path.display().to_string().dark_grey()
+                        );
+                    }
+                }
+
+                output.emit(
+                    "skeleton",
+                    &SkeletonRecord {
+                        prefix: prefix.clone(),
+                        addr: skeleton.addr,
+                        connector: connector_def.shortname.clone(),
+                        wrote,
+                    },
+                )?;
+            }
+        }
+    }
+
+    if output.is_human() {
+        println!(" Imported {wrote_count} skeletons.");
+    }
+
+    Ok(())
+}
@@ -1,18 +1,261 @@
-use std::{collections::HashMap, path::PathBuf, process::Stdio};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    process::Stdio,
+};
 
-use anyhow::bail;
-use autoschematic_core::{config::AutoschematicConfig, util::load_autoschematic_config};
+use anyhow::{Context, bail};
+use autoschematic_core::{
+    config::{AutoschematicConfig, Spec},
+    lockfile::{AutoschematicLockfile, LockEntry, lockfile_key},
+    util::{load_autoschematic_config, python_venv_binary, python_venv_dir},
+};
+use crossterm::style::Stylize;
 use toml::Table;
 
-pub async fn install() -> anyhow::Result<()> {
+type PackageName = String;
+type Version = String;
+type Binary = String;
+
+pub async fn install(update_lock: bool) -> anyhow::Result<()> {
     let config = load_autoschematic_config()?;
 
-    cargo_install_missing(&config).await?;
+    // Unless re-pinning, install the versions in autoschematic.lock.ron, such that the builds can match it.
+    let lockfile = if update_lock { None } else { AutoschematicLockfile::load()? };
+
+    let statuses = install_connectors(&config, lockfile.as_ref()).await?;
+
+    println!();
+    for (key, spec_kind, status) in &statuses {
+        match status {
+            InstallStatus::Failed(e) => println!(" {} {} ({}): {}", "✗".red(), key.display(), spec_kind, e),
+            status => println!(" {} {} ({}): {}", "✓".green(), key.display(), spec_kind, status),
+        }
+    }
+    println!();
+
+    write_lockfile(&config, update_lock).await?;
+
+    let failed = statuses
+        .iter()
+        .filter(|(_, _, status)| matches!(status, InstallStatus::Failed(_)))
+        .count();
+
+    if failed > 0 {
+        bail!("{failed} connector(s) could not be made runnable.");
+    }
+
+    Ok(())
+}
+
+/// The outcome of installing a single connector.
+enum InstallStatus {
+    /// Installed by cargo or pip.
+    Installed,
+    /// Built from a local source tree.
+    Built,
+    /// Nothing to install; the connector's executable or source is already in place.
+    Ready,
+    Failed(String),
+}
+
+impl std::fmt::Display for InstallStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InstallStatus::Installed => write!(f, "installed"),
+            InstallStatus::Built => write!(f, "built"),
+            InstallStatus::Ready => write!(f, "ready"),
+            InstallStatus::Failed(e) => write!(f, "failed: {e}"),
+        }
+    }
+}
+
+fn spec_kind(spec: &Spec) -> &'static str {
+    match spec {
+        Spec::Binary { .. } => "Binary",
+        Spec::Cargo { .. } => "Cargo",
+        Spec::CargoLocal { .. } => "CargoLocal",
+        Spec::Pip { .. } => "Pip",
+        Spec::TypescriptLocal { .. } => "TypescriptLocal",
+        Spec::PythonLocal { .. } => "PythonLocal",
+    }
+}
+
+/// Install, build or check every connector in autoschematic.ron, carrying on past failures
+/// so that they can all be reported together. Connectors with an entry in `lockfile` are installed
+/// at the version it records, rather than the version in their spec.
+async fn install_connectors(
+    config: &AutoschematicConfig,
+    lockfile: Option<&AutoschematicLockfile>,
+) -> anyhow::Result<Vec<(PathBuf, &'static str, InstallStatus)>> {
+    let mut statuses = Vec::new();
+
+    for (prefix_name, prefix) in &config.prefixes {
+        for connector in &prefix.connectors {
+            let key = lockfile_key(&PathBuf::from(prefix_name), &connector.shortname);
+
+            let locked_version = lockfile
+                .and_then(|lockfile| lockfile.entries.get(&key))
+                .and_then(|lock_entry| lock_entry.version.as_deref());
+
+            let status = match &connector.spec {
+                Spec::Cargo { .. } => cargo_install(&connector.spec, locked_version)
+                    .await
+                    .map(|_| InstallStatus::Installed),
+                Spec::Pip { name, version, .. } => pip_install(name, locked_version.or(version.as_deref()))
+                    .await
+                    .map(|_| InstallStatus::Installed),
+                Spec::CargoLocal { .. } => cargo_build_local(&connector.spec).await.map(|_| InstallStatus::Built),
+                Spec::Binary { path, .. } => check_executable(path).map(|_| InstallStatus::Ready),
+                Spec::TypescriptLocal { path } | Spec::PythonLocal { path } => {
+                    if path.is_file() {
+                        Ok(InstallStatus::Ready)
+                    } else {
+                        Err(anyhow::anyhow!("{}: not found", path.display()))
+                    }
+                }
+            };
+
+            let status = status.unwrap_or_else(|e| InstallStatus::Failed(format!("{e:#}")));
+            statuses.push((key, spec_kind(&connector.spec), status));
+        }
+    }
+
+    Ok(statuses)
+}
+
+async fn run_command(mut command: tokio::process::Command) -> anyhow::Result<()> {
+    let status = command
+        .stdin(Stdio::inherit())
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .kill_on_drop(true)
+        .spawn()
+        .context(format!("Spawning {command:?}"))?
+        .wait()
+        .await?;
+
+    if !status.success() {
+        bail!("{:?}: {}", command.as_std(), status)
+    }
+
+    Ok(())
+}
+
+/// Install the package in a Cargo spec, at `locked_version` if given, or else at the spec's version.
+async fn cargo_install(spec: &Spec, locked_version: Option<&str>) -> anyhow::Result<()> {
+    let Spec::Cargo {
+        name,
+        version,
+        git,
+        features,
+        ..
+    } = spec
+    else {
+        return Ok(());
+    };
+
+    // TODO check cargo_installed_packages() and skip existing with same version, features
+    println!("Installing {name} via Cargo");
+
+    let mut command = tokio::process::Command::new("cargo");
+
+    command.args(["install"]);
+
+    if let Some(git) = git {
+        command.args(["--git", git]);
+    } else if let Some(version) = locked_version.or(version.as_deref()) {
+        command.args([format!("{name}@{version}")]);
+    } else {
+        command.args([name]);
+    }
+
+    if let Some(features) = features
+        && !features.is_empty()
+    {
+        command.args(["--features", &features.join(",")]);
+    }
+
+    run_command(command).await
+}
+
+/// Create the per-repository virtualenv if it doesn't exist yet, and return its directory.
+async fn ensure_python_venv() -> anyhow::Result<PathBuf> {
+    let venv_dir = python_venv_dir()?;
+
+    if python_venv_binary("python").is_none() {
+        println!("Creating virtualenv at {}", venv_dir.display());
+
+        let python = which::which("python3")
+            .or_else(|_| which::which("python"))
+            .context("Neither python3 nor python found in $PATH")?;
+
+        let mut command = tokio::process::Command::new(python);
+        command.args(["-m", "venv"]).arg(&venv_dir);
+        run_command(command).await?;
+    }
+
+    let gitignore = venv_dir.join(".gitignore");
+    if !gitignore.exists() {
+        std::fs::write(&gitignore, "*\n").context(format!("Writing {}", gitignore.display()))?;
+    }
+
+    Ok(venv_dir)
+}
+
+async fn pip_install(name: &str, version: Option<&str>) -> anyhow::Result<()> {
+    let venv_dir = ensure_python_venv().await?;
+
+    println!("Installing {name} via Pip");
+
+    let mut command = tokio::process::Command::new(venv_dir.join("bin").join("pip"));
+
+    command.args(["install", "--upgrade"]);
+
+    if let Some(version) = version {
+        command.args([format!("{name}=={version}")]);
+    } else {
+        command.args([name]);
+    }
+
+    run_command(command).await
+}
+
+async fn cargo_build_local(spec: &Spec) -> anyhow::Result<()> {
+    let Some(pre_command) = spec.pre_command()? else {
+        return Ok(());
+    };
+
+    println!("Building {}", pre_command.args.join(" "));
+
+    let mut command = tokio::process::Command::new(pre_command.binary);
+    command.args(pre_command.args);
+
+    run_command(command).await
+}
+
+/// Check that `path`, or the binary it names in $PATH, exists and is executable.
+fn check_executable(path: &Path) -> anyhow::Result<()> {
+    let path = if path.is_file() {
+        path.to_path_buf()
+    } else {
+        which::which(path).context(format!("{}: not found", path.display()))?
+    };
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        if std::fs::metadata(&path)?.permissions().mode() & 0o111 == 0 {
+            bail!("{}: not executable", path.display());
+        }
+    }
 
     Ok(())
 }
 
-pub async fn cargo_install_missing(config: &AutoschematicConfig) -> anyhow::Result<()> {
+/// Parse $CARGO_HOME/.crates.toml into the versions and binaries of each package installed with `cargo install`.
+fn cargo_installed_packages() -> anyhow::Result<HashMap<PackageName, HashMap<Version, Vec<Binary>>>> {
     let cargo_home = match std::env::var("CARGO_HOME") {
         Ok(p) => PathBuf::from(p),
         Err(_) => {
@@ -30,9 +273,6 @@ pub async fn cargo_install_missing(config: &AutoschematicConfig) -> anyhow::Resu
 
     let pkg_table = cargo_registry_v1.as_table().unwrap();
 
-    type PackageName = String;
-    type Version = String;
-    type Binary = String;
     let mut pkg_map: HashMap<PackageName, HashMap<Version, Vec<Binary>>> = HashMap::new();
 
     for (pkg, binaries) in pkg_table {
@@ -53,79 +293,84 @@ pub async fn cargo_install_missing(config: &AutoschematicConfig) -> anyhow::Resu
             .insert(pkg_name[1].to_string(), binaries);
     }
 
-    for prefix in config.prefixes.values() {
-        for connector in &prefix.connectors {
-            match &connector.spec {
-                autoschematic_core::config::Spec::Cargo {
-                    name,
-                    version,
-                    git,
-                    features,
-                    ..
-                } => {
-                    // TODO check pkg_map and skip existing with same version, features
-                    println!("Installing {name} via Cargo");
-
-                    let mut command = tokio::process::Command::new("cargo");
-
-                    command.args(["install"]);
-
-                    if let Some(git) = git {
-                        command.args(["--git", git]);
-                    } else if let Some(version) = version {
-                        command.args([format!("{name}@{version}")]);
-                    } else {
-                        command.args([name]);
-                    }
+    Ok(pkg_map)
+}
 
-                    if let Some(features) = features
-                        && !features.is_empty()
-                    {
-                        command.args(["--features", &features.join(",")]);
-                    }
+/// The version of `name` installed by pip, if any.
+async fn pip_installed_version(name: &str) -> anyhow::Result<Option<Version>> {
+    let pip = python_venv_binary("pip").unwrap_or("pip".into());
 
-                    let status = command
-                        .stdin(Stdio::inherit())
-                        .stdout(Stdio::inherit())
-                        .stderr(Stdio::inherit())
-                        .kill_on_drop(true)
-                        .spawn()?
-                        .wait()
-                        .await?;
-
-                    if !status.success() {
-                        bail!("Pre-command failed: {:?}: {}", command, status)
-                    }
-                }
-                autoschematic_core::config::Spec::Pip { name, version, .. } => {
-                    println!("Installing {name} via Pip");
+    let output = tokio::process::Command::new(pip)
+        .args(["show", name])
+        .stderr(Stdio::null())
+        .output()
+        .await?;
 
-                    let mut command = tokio::process::Command::new("pip");
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find_map(|line| line.strip_prefix("Version:"))
+        .map(|version| version.trim().to_string()))
+}
 
-                    command.args(["install"]);
+/// Hash the executable of every connector and record it in autoschematic.lock.ron.
+/// Existing entries are checked rather than replaced, unless `update_lock` is set.
+async fn write_lockfile(config: &AutoschematicConfig, update_lock: bool) -> anyhow::Result<()> {
+    let mut lockfile = AutoschematicLockfile::load()?.unwrap_or_default();
 
-                    if let Some(version) = version {
-                        command.args([format!("{name}~={version}")]);
-                    } else {
-                        command.args([name]);
-                    }
+    let cargo_packages = cargo_installed_packages().unwrap_or_default();
 
-                    let status = command
-                        .stdin(Stdio::inherit())
-                        .stdout(Stdio::inherit())
-                        .stderr(Stdio::inherit())
-                        .kill_on_drop(true)
-                        .spawn()?
-                        .wait()
-                        .await?;
-
-                    if !status.success() {
-                        bail!("Pre-command failed: {:?}: {}", command, status)
-                    }
+    let mut mismatched = false;
+
+    for (prefix_name, prefix) in &config.prefixes {
+        for connector in &prefix.connectors {
+            let key = lockfile_key(&PathBuf::from(prefix_name), &connector.shortname);
+
+            if !update_lock && lockfile.entries.contains_key(&key) {
+                if let Err(e) = lockfile.verify(&PathBuf::from(prefix_name), &connector.shortname, &connector.spec) {
+                    println!(" {} {}: {:#}", "✗".red(), key.display(), e);
+                    mismatched = true;
+                }
+                continue;
+            }
+
+            let version = match &connector.spec {
+                Spec::Cargo { name, .. } => cargo_packages
+                    .get(name)
+                    .and_then(|versions| versions.keys().max_by_key(|v| semver::Version::parse(v).ok()).cloned()),
+                Spec::Pip { name, .. } => pip_installed_version(name).await?,
+                _ => None,
+            };
+
+            match LockEntry::for_spec(&connector.spec, version)? {
+                Some(lock_entry) => {
+                    println!(" Locked {} at {}", key.display(), &lock_entry.sha256[..12]);
+                    lockfile.entries.insert(key, lock_entry);
+                }
+                None => {
+                    lockfile.entries.remove(&key);
                 }
-                _ => continue,
             }
         }
     }
+
+    // Drop the entries of connectors that are no longer in autoschematic.ron.
+    lockfile.entries.retain(|key, _| {
+        config.prefixes.iter().any(|(prefix_name, prefix)| {
+            prefix
+                .connectors
+                .iter()
+                .any(|connector| lockfile_key(&PathBuf::from(prefix_name), &connector.shortname) == *key)
+        })
+    });
+
+    let lockfile_path = lockfile.write()?;
+    println!(" Wrote {}", lockfile_path.display());
+
+    if mismatched {
+        bail!(
+            "Some connectors don't match the builds pinned in autoschematic.lock.ron. Run `autoschematic install --update-lock` to re-pin them."
+        );
+    }
+
     Ok(())
 }
@@ -2,12 +2,15 @@ use std::{path::PathBuf, sync::Arc, time::Duration};
 
 use autoschematic_core::connector_cache::ConnectorCache;
 
-use clap::{Parser, Subcommand};
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand};
 use crossterm::style::Stylize;
 use lazy_static::lazy_static;
 use tracing_subscriber::EnvFilter;
 
 use crate::{
+    apply::ApplyOptions,
+    output::{ExitStatus, Output, OutputFormat},
+    outputs::AutoschematicOutputsSubcommand,
     safety_lock::{set_safety_lock, unset_safety_lock},
     util::try_fetch_motd,
 };
@@ -22,10 +25,21 @@ mod aux_task;
 mod check_drift;
 mod config;
 mod create;
+mod destroy;
+mod doctor;
+mod drift;
+mod gc;
+mod get;
+mod graph;
 mod import;
+mod import_skeletons;
 mod init;
 mod install;
+mod output;
+mod outputs;
 mod plan;
+mod pull_state;
+mod rename;
 mod safety_lock;
 mod seal;
 mod spinner;
@@ -40,6 +54,12 @@ mod validate;
 pub struct AutoschematicCommand {
     #[command(subcommand)]
     pub command: AutoschematicSubcommand,
+
+    /// Output format. In the json and ndjson formats, plan, apply, import and check-drift emit versioned
+    /// JSON records on stdout, and exit with 0 for no changes, 1 for an error,
+    /// 2 for changes pending, or 3 for drift found.
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Human)]
+    pub format: OutputFormat,
 }
 
 #[derive(Subcommand, Default, Debug)]
@@ -70,13 +90,18 @@ pub enum AutoschematicSubcommand {
         kind: AutoschematicSafetySubcommand,
     },
     /// Validate that the Autoschematic config within this repository is well-formed.
-    /// Includes autoschematic.lock.ron and autoschematic.rbac.ron if present.
+    /// Includes autoschematic.lock.ron, autoschematic.rbac.ron and autoschematic.policy.ron if present.
     Validate {},
     /// Install or upgrade the connectors listed in autoschematic.ron.
+    /// Pip connectors go into a per-repository virtualenv under .autoschematic/venv, and local Cargo connectors are pre-built.
+    /// Also hashes each connector's executable into autoschematic.lock.ron, so that every checkout runs the same builds.
     Install {
         // url: String,
         // #[arg(short, long, default_value = None)]
         // version: Option<String>,
+        /// If set, re-pin every connector to the build now installed, rather than checking it against autoschematic.lock.ron.
+        #[arg(long, default_value_t = false)]
+        update_lock: bool,
     },
     /// Seal a secret against a server's public key.
     Seal {
@@ -129,19 +154,32 @@ pub enum AutoschematicSubcommand {
         /// Optional path (can be a glob) to filter which resources are imported.
         #[arg(short, long, value_name = "subpath")]
         subpath: Option<String>,
+
+        /// Optional: plan the files changed between this git ref and --head, including deletions and renames,
+        /// instead of the files staged in git.
+        #[arg(long, value_name = "base")]
+        base: Option<String>,
+
+        /// Optional: with --base, the git ref to diff against. Defaults to HEAD.
+        #[arg(long, value_name = "head", requires = "base")]
+        head: Option<String>,
+
+        /// Optional: write the plan to this file, to be executed later with apply --plan-file.
+        #[arg(short, long, value_name = "out")]
+        out: Option<PathBuf>,
     },
     /// Execute the series of operations needed to apply the changeset.
     Apply {
         /// Optional: run for a single prefix by name
-        #[arg(short, long, value_name = "prefix")]
+        #[arg(short, long, value_name = "prefix", conflicts_with_all = ["plan_file", "resume"])]
         prefix: Option<String>,
 
         /// Optional: run for a single connector by name
-        #[arg(short, long, value_name = "connector")]
+        #[arg(short, long, value_name = "connector", conflicts_with_all = ["plan_file", "resume"])]
         connector: Option<String>,
 
         /// Optional path (can be a glob) to filter which resources are imported.
-        #[arg(short, long, value_name = "subpath")]
+        #[arg(short, long, value_name = "subpath", conflicts_with_all = ["plan_file", "resume"])]
         subpath: Option<String>,
 
         /// If set, don't ask for any confirmation before executing. Use with caution!
@@ -151,6 +189,77 @@ pub enum AutoschematicSubcommand {
         /// If set, don't ask to run git commit (assume 'no').
         #[arg(long, value_name = "skip_commit", default_value_t = false)]
         skip_commit: bool,
+
+        /// Optional: execute exactly the operations in a plan file written by plan --out,
+        /// instead of planning again. Refuses if anything has changed since the plan was written.
+        #[arg(long, value_name = "plan_file")]
+        plan_file: Option<PathBuf>,
+
+        /// Apply up to this many resources at once. Resources are only applied after
+        /// the resources whose outputs they read.
+        #[arg(long, value_name = "parallel", default_value_t = 1, conflicts_with_all = ["plan_file", "resume"])]
+        parallel: usize,
+
+        /// Continue the most recent apply that was interrupted, from the first op
+        /// its journal in .autoschematic/journal/ doesn't record as finished.
+        #[arg(long, value_name = "resume", default_value_t = false, conflicts_with = "plan_file")]
+        resume: bool,
+
+        /// Abandon the journal of an interrupted apply, such that it can no longer be resumed, and apply as usual.
+        /// Apply refuses to run while such a journal exists, as it could execute the same ops twice.
+        #[arg(long, value_name = "discard_journal", default_value_t = false, conflicts_with = "resume")]
+        discard_journal: bool,
+
+        /// Choose which ops to apply from a checklist. Unselected ops stay pending,
+        /// and with --plan-file, remain in the plan file.
+        #[arg(short, long, value_name = "interactive", default_value_t = false, conflicts_with_all = ["skip_confirm", "resume"])]
+        interactive: bool,
+
+        /// Apply even if the plan would delete or replace resources matched by a `protect` rule in autoschematic.ron.
+        #[arg(long, value_name = "allow_destroy_protected", default_value_t = false)]
+        allow_destroy_protected: bool,
+
+        /// Apply even if the plan violates a rule in autoschematic.policy.ron.
+        /// The CLI can't tell which roles in autoschematic.rbac.ron its user holds, so anyone may pass this:
+        /// the policy is only advisory here. The server only lets users with one of its `override_roles` override it.
+        #[arg(long, value_name = "override_policy", default_value_t = false)]
+        override_policy: bool,
+    },
+    /// Destroy every remote resource in a prefix, as listed by its connectors.
+    /// Resources are destroyed before the resources whose outputs they read.
+    Destroy {
+        /// The prefix to destroy, by name
+        #[arg(short, long, value_name = "prefix")]
+        prefix: String,
+
+        /// Optional: run for a single connector by name
+        #[arg(short, long, value_name = "connector")]
+        connector: Option<String>,
+
+        /// Optional path (can be a glob) to filter which resources are destroyed.
+        #[arg(short, long, value_name = "subpath")]
+        subpath: Option<String>,
+
+        /// If set, don't ask to type the prefix name before destroying. Use with extreme caution!
+        #[arg(long, value_name = "skip_confirm", default_value_t = false)]
+        skip_confirm: bool,
+
+        /// If set, don't ask to run git commit (assume 'no').
+        #[arg(long, value_name = "skip_commit", default_value_t = false)]
+        skip_commit: bool,
+
+        /// If set, also remove the local resource files and output maps of destroyed resources.
+        #[arg(long, value_name = "remove_files", default_value_t = false)]
+        remove_files: bool,
+
+        /// Destroy even resources matched by a `protect` rule in autoschematic.ron.
+        #[arg(long, value_name = "allow_destroy_protected", default_value_t = false)]
+        allow_destroy_protected: bool,
+
+        /// Destroy even if doing so violates a rule in autoschematic.policy.ron.
+        /// As with apply, anyone may pass this to the CLI: only the server enforces the policy's `override_roles`.
+        #[arg(long, value_name = "override_policy", default_value_t = false)]
+        override_policy: bool,
     },
     /// Unpack bundle files to produce or refresh their children.
     Unbundle {
@@ -210,6 +319,51 @@ pub enum AutoschematicSubcommand {
         #[arg(long, value_name = "commit")]
         commit: Option<bool>,
     },
+    /// For each resource file staged in git, fetch its current remote state and write it over the local file.
+    PullState {
+        /// Optional: run for a single prefix by name
+        #[arg(short, long, value_name = "prefix")]
+        prefix: Option<String>,
+
+        /// Optional: run for a single connector by name
+        #[arg(short, long, value_name = "connector")]
+        connector: Option<String>,
+
+        /// Optional path (can be a glob) to filter which resources are pulled.
+        #[arg(short, long, value_name = "subpath")]
+        subpath: Option<String>,
+
+        /// If set, delete the local files of resources that don't exist remotely.
+        #[arg(long, default_value_t = false)]
+        delete: bool,
+
+        /// If set, don't stage the pulled files in git.
+        #[arg(long, value_name = "no-stage", default_value_t = false)]
+        no_stage: bool,
+    },
+    /// For each connector, write its "skeleton" resources into the prefix.
+    /// Skeletons are example resources that show the format and path structure of resources a connector can create.
+    ImportSkeletons {
+        /// Optional: run for a single prefix by name
+        #[arg(short, long, value_name = "prefix")]
+        prefix: Option<String>,
+
+        /// Optional: run for a single connector by name
+        #[arg(short, long, value_name = "connector")]
+        connector: Option<String>,
+
+        /// Optional path (can be a glob) to filter which skeletons are imported.
+        #[arg(short, long, value_name = "subpath")]
+        subpath: Option<String>,
+
+        /// If set, overwrite existing files with the skeletons.
+        #[arg(long, value_name = "overwrite", default_value_t = false)]
+        overwrite: bool,
+
+        /// If set, don't stage the new skeleton files in git.
+        #[arg(long, value_name = "no-stage", default_value_t = false)]
+        no_stage: bool,
+    },
     /// Scaffold new resource definitions from templates.
     Create {
         /// Optional path (can be a glob) to filter the changeset.
@@ -220,11 +374,98 @@ pub enum AutoschematicSubcommand {
         #[arg(short, long, value_name = "connector")]
         connector: Option<String>,
     },
+    /// Print the dependency graph between resources, as formed by their out:// references,
+    /// deferred physical addresses and bundles.
+    Graph {
+        /// Optional: run for a single prefix by name
+        #[arg(short, long, value_name = "prefix")]
+        prefix: Option<String>,
+
+        /// Optional: run for a single connector by name
+        #[arg(short, long, value_name = "connector")]
+        connector: Option<String>,
+    },
+    /// Read the outputs that applied resources have written to their output maps in .autoschematic/.
+    Outputs {
+        #[command(subcommand)]
+        kind: AutoschematicOutputsSubcommand,
+    },
+    /// Rename the resource at `old` to `new`, moving its output map
+    /// and rewriting every out:// reference to it across the prefix.
+    Mv {
+        old: PathBuf,
+        new: PathBuf,
+
+        /// If set, don't stage the renamed and rewritten files in git.
+        #[arg(long, value_name = "no-stage", default_value_t = false)]
+        no_stage: bool,
+    },
+    /// Find output and bundle map files that no longer describe any resource file,
+    /// including broken links and children of deleted bundles, and remove them.
+    Gc {
+        /// Optional: run for a single prefix by name
+        #[arg(short, long, value_name = "prefix")]
+        prefix: Option<String>,
+
+        /// If set, only report the orphaned map files without removing them.
+        #[arg(long, default_value_t = false)]
+        dry_run: bool,
+
+        /// If set, don't stage the removals in git.
+        #[arg(long, value_name = "no-stage", default_value_t = false)]
+        no_stage: bool,
+    },
+    /// Print the current remote body of the resource at `path`.
+    Get {
+        path: PathBuf,
+    },
+    /// Print a diff from the current remote body of the resource at `path` to its local file, after templating.
+    Diff {
+        path: PathBuf,
+    },
+    /// Check that every connector can be launched and initialized, and report what to fix for those that can't.
+    /// Checks each connector's command, env files and env:// passthroughs, version and init(),
+    /// as well as the sandbox's prerequisites if it is enabled.
+    Doctor {
+        /// Optional: run for a single prefix by name
+        #[arg(short, long, value_name = "prefix")]
+        prefix: Option<String>,
+
+        /// Optional: run for a single connector by name
+        #[arg(short, long, value_name = "connector")]
+        connector: Option<String>,
+
+        /// How long to wait for each connector's init(), in seconds.
+        #[arg(long, value_name = "timeout", default_value_t = 30)]
+        timeout: u64,
+    },
     /// Check if a resource at `path` has drifted.
     CheckDrift {
         #[arg(short, long, value_name = "path")]
         path: String,
     },
+    /// Check every resource file in the repository for drift against its remote state.
+    Drift {
+        /// Optional: run for a single prefix by name
+        #[arg(short, long, value_name = "prefix")]
+        prefix: Option<String>,
+
+        /// Optional: run for a single connector by name
+        #[arg(short, long, value_name = "connector")]
+        connector: Option<String>,
+
+        /// Optional path (can be a glob) to filter which resources are checked.
+        #[arg(short, long, value_name = "subpath")]
+        subpath: Option<String>,
+
+        /// The maximum number of resources to check at once.
+        #[arg(long, value_name = "concurrency", default_value_t = 10)]
+        concurrency: usize,
+
+        /// Optional: write a JUnit XML report to this path.
+        #[arg(long, value_name = "junit")]
+        junit: Option<PathBuf>,
+    },
     Version,
 }
 
@@ -250,7 +491,33 @@ async fn main() -> anyhow::Result<()> {
         }
     });
 
-    let cmd = AutoschematicCommand::parse();
+    // clap exits with code 2 on usage errors, which would read as ExitStatus::ChangesPending.
+    let matches = match AutoschematicCommand::command().try_get_matches() {
+        Ok(matches) => matches,
+        Err(e) if e.use_stderr() => {
+            let _ = e.print();
+            std::process::exit(ExitStatus::Error as i32);
+        }
+        Err(e) => e.exit(),
+    };
+    let cmd = AutoschematicCommand::from_arg_matches(&matches)?;
+
+    if cmd.format == OutputFormat::Dot && !matches!(cmd.command, AutoschematicSubcommand::Graph { .. }) {
+        anyhow::bail!("--format dot is only supported by autoschematic graph");
+    }
+
+    if cmd.format == OutputFormat::Env
+        && !matches!(
+            cmd.command,
+            AutoschematicSubcommand::Outputs {
+                kind: AutoschematicOutputsSubcommand::Export { .. }
+            }
+        )
+    {
+        anyhow::bail!("--format env is only supported by autoschematic outputs export");
+    }
+
+    let output = Output::new(cmd.format, matches.subcommand_name().unwrap_or_default());
 
     let result = match cmd.command {
         AutoschematicSubcommand::Seal {
@@ -270,23 +537,66 @@ async fn main() -> anyhow::Result<()> {
         //     let token = login_via_github().await?;
         //     persist_github_token(&token)?;
         // }
-        AutoschematicSubcommand::Install {} => install::install().await,
+        AutoschematicSubcommand::Install { update_lock } => install::install(update_lock).await,
         AutoschematicSubcommand::Plan {
             prefix,
             connector,
             subpath,
-        } => plan::plan(&prefix, &connector, &subpath).await,
+            base,
+            head,
+            out,
+        } => plan::plan(&prefix, &connector, &subpath, &base, &head, &out, &output).await,
         AutoschematicSubcommand::Apply {
             prefix,
             connector,
             subpath,
             skip_confirm,
             skip_commit,
+            plan_file,
+            parallel,
+            resume,
+            discard_journal,
+            interactive,
+            allow_destroy_protected,
+            override_policy,
         } => {
-            let ask_confirm = !skip_confirm;
-            let _apply_reports = apply::apply(prefix, connector, subpath, ask_confirm, skip_commit).await?;
-            Ok(())
+            let options = ApplyOptions {
+                ask_confirm: !skip_confirm,
+                skip_commit,
+                plan_file,
+                parallel,
+                resume,
+                discard_journal,
+                interactive,
+                allow_destroy_protected,
+                override_policy,
+            };
+            apply::apply(prefix, connector, subpath, options, &output)
+                .await
+                .map(|_apply_reports| ())
         }
+        AutoschematicSubcommand::Destroy {
+            prefix,
+            connector,
+            subpath,
+            skip_confirm,
+            skip_commit,
+            remove_files,
+            allow_destroy_protected,
+            override_policy,
+        } => destroy::destroy(
+            prefix,
+            connector,
+            subpath,
+            skip_confirm,
+            skip_commit,
+            remove_files,
+            allow_destroy_protected,
+            override_policy,
+            &output,
+        )
+        .await
+        .map(|_apply_reports| ()),
         AutoschematicSubcommand::Unbundle {
             prefix,
             connector,
@@ -303,7 +613,21 @@ async fn main() -> anyhow::Result<()> {
             subpath,
             overwrite,
             commit,
-        } => import::import(prefix, connector, subpath, overwrite, commit).await,
+        } => import::import(prefix, connector, subpath, overwrite, commit, &output).await,
+        AutoschematicSubcommand::PullState {
+            prefix,
+            connector,
+            subpath,
+            delete,
+            no_stage,
+        } => pull_state::pull_state(prefix, connector, subpath, delete, !no_stage, &output).await,
+        AutoschematicSubcommand::ImportSkeletons {
+            prefix,
+            connector,
+            subpath,
+            overwrite,
+            no_stage,
+        } => import_skeletons::import_skeletons(prefix, connector, subpath, overwrite, !no_stage, &output).await,
         AutoschematicSubcommand::RunAuxTask { name, prefix } => {
             aux_task::spawn_task("", "", &PathBuf::from(prefix), &name, 0, serde_json::Value::Null, true).await
         }
@@ -316,7 +640,29 @@ async fn main() -> anyhow::Result<()> {
             AutoschematicSafetySubcommand::Lock => set_safety_lock(),
             AutoschematicSafetySubcommand::Unlock => unset_safety_lock(),
         },
-        AutoschematicSubcommand::CheckDrift { path } => check_drift::check_drift(&path).await,
+        AutoschematicSubcommand::Graph { prefix, connector } => graph::graph(prefix, connector, &output).await,
+        AutoschematicSubcommand::Outputs { kind } => outputs::outputs(kind, &output),
+        AutoschematicSubcommand::Mv { old, new, no_stage } => rename::mv(&old, &new, !no_stage).await,
+        AutoschematicSubcommand::Gc {
+            prefix,
+            dry_run,
+            no_stage,
+        } => gc::gc(prefix, dry_run, no_stage, &output),
+        AutoschematicSubcommand::Get { path } => get::get(&path, &output).await,
+        AutoschematicSubcommand::Diff { path } => get::diff(&path, &output).await,
+        AutoschematicSubcommand::Doctor {
+            prefix,
+            connector,
+            timeout,
+        } => doctor::doctor(prefix, connector, timeout, &output).await,
+        AutoschematicSubcommand::CheckDrift { path } => check_drift::check_drift(&path, &output).await,
+        AutoschematicSubcommand::Drift {
+            prefix,
+            connector,
+            subpath,
+            concurrency,
+            junit,
+        } => drift::drift(prefix, connector, subpath, concurrency, junit, &output).await,
         AutoschematicSubcommand::Version => {
             eprintln!("{}", env!("CARGO_PKG_VERSION"));
             Ok(())
@@ -326,6 +672,14 @@ async fn main() -> anyhow::Result<()> {
     // Clean up connector cache before exiting
     CONNECTOR_CACHE.clear().await;
 
+    if !output.is_human() {
+        let exit_status = output.finish(&result)?;
+        if exit_status != ExitStatus::NoChanges {
+            std::process::exit(exit_status as i32);
+        }
+        return Ok(());
+    }
+
     // Print MOTD if we have one...
     if let Ok(Some(motd)) = motd
         && !motd.is_empty()
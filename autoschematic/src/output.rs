@@ -0,0 +1,132 @@
+use std::sync::{Arc, Mutex};
+
+use clap::ValueEnum;
+use serde::Serialize;
+
+/// The version of the machine-readable output format.
+/// Bump this whenever the shape of emitted records changes incompatibly.
+pub const OUTPUT_VERSION: u32 = 1;
+
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Human-readable output with colours and frames.
+    #[default]
+    Human,
+    /// A single JSON document on stdout once the command completes.
+    Json,
+    /// One JSON record per line on stdout, emitted as results become available.
+    Ndjson,
+    /// Graphviz DOT. Only supported by `autoschematic graph`.
+    Dot,
+    /// KEY=value lines. Only supported by `autoschematic outputs export`.
+    Env,
+}
+
+/// Process exit codes used in the machine-readable output formats.
+/// Usage errors from argument parsing exit with ExitStatus::Error, not clap's default of 2.
+#[derive(Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExitStatus {
+    /// The command succeeded, and no changes are pending.
+    #[default]
+    NoChanges = 0,
+    /// The command failed.
+    Error = 1,
+    /// The command succeeded, and there are changes pending that have not been applied.
+    ChangesPending = 2,
+    /// The command succeeded, and found that remote resources have drifted from their definitions.
+    DriftFound = 3,
+}
+
+#[derive(Serialize)]
+struct Record<'a, T: Serialize> {
+    version: u32,
+    kind: &'a str,
+    data: T,
+}
+
+#[derive(Serialize)]
+struct Document<'a> {
+    version: u32,
+    command: &'a str,
+    status: ExitStatus,
+    records: Vec<serde_json::Value>,
+    error: Option<String>,
+}
+
+/// Shared handle through which subcommands emit machine-readable records
+/// and report their exit status.
+#[derive(Clone, Default)]
+pub struct Output {
+    pub format: OutputFormat,
+    command: String,
+    records: Arc<Mutex<Vec<serde_json::Value>>>,
+    status: Arc<Mutex<ExitStatus>>,
+}
+
+impl Output {
+    pub fn new(format: OutputFormat, command: &str) -> Self {
+        Self {
+            format,
+            command: command.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Whether the human-readable output should be printed.
+    pub fn is_human(&self) -> bool {
+        self.format == OutputFormat::Human
+    }
+
+    /// Emit a record of the given kind. Does nothing in human output mode.
+    pub fn emit<T: Serialize>(&self, kind: &str, data: &T) -> anyhow::Result<()> {
+        let record = Record {
+            version: OUTPUT_VERSION,
+            kind,
+            data,
+        };
+
+        match self.format {
+            OutputFormat::Human | OutputFormat::Dot | OutputFormat::Env => {}
+            OutputFormat::Json => self.records.lock().unwrap().push(serde_json::to_value(&record)?),
+            OutputFormat::Ndjson => println!("{}", serde_json::to_string(&record)?),
+        }
+
+        Ok(())
+    }
+
+    pub fn set_status(&self, status: ExitStatus) {
+        *self.status.lock().unwrap() = status;
+    }
+
+    /// Print the final JSON document or ndjson status record for the command's `result`,
+    /// and return the exit status it should terminate with.
+    pub fn finish(&self, result: &anyhow::Result<()>) -> anyhow::Result<ExitStatus> {
+        let (status, error) = match result {
+            Ok(()) => (*self.status.lock().unwrap(), None),
+            Err(e) => (ExitStatus::Error, Some(format!("{e:#}"))),
+        };
+
+        match self.format {
+            OutputFormat::Human | OutputFormat::Dot | OutputFormat::Env => {}
+            OutputFormat::Json => {
+                let document = Document {
+                    version: OUTPUT_VERSION,
+                    command: &self.command,
+                    status,
+                    records: std::mem::take(&mut *self.records.lock().unwrap()),
+                    error,
+                };
+                println!("{}", serde_json::to_string_pretty(&document)?);
+            }
+            OutputFormat::Ndjson => {
+                if let Some(error) = error {
+                    self.emit("error", &error)?;
+                }
+                self.emit("status", &status)?;
+            }
+        }
+
+        Ok(status)
+    }
+}
@@ -0,0 +1,228 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use anyhow::bail;
+use autoschematic_core::{
+    config::AutoschematicConfig,
+    connector::OutputMapFile,
+    util::{load_autoschematic_config, split_prefix_addr},
+};
+use clap::Subcommand;
+use crossterm::style::Stylize;
+use serde::Serialize;
+
+use crate::output::{Output, OutputFormat};
+
+#[derive(Subcommand, Debug)]
+pub enum AutoschematicOutputsSubcommand {
+    /// Print the outputs of the resource at `path`, following links, or only the value of `key`.
+    Get {
+        /// The path of the resource, including its prefix.
+        path: PathBuf,
+        /// Optional: print only the value of this output.
+        key: Option<String>,
+    },
+    /// List the resources that have outputs, along with their output keys.
+    List {
+        /// Optional: run for a single prefix by name
+        #[arg(short, long, value_name = "prefix")]
+        prefix: Option<String>,
+    },
+    /// Print every output, as KEY=value lines with --format env, or as a JSON record with --format json.
+    Export {
+        /// Optional: run for a single prefix by name
+        #[arg(short, long, value_name = "prefix")]
+        prefix: Option<String>,
+    },
+}
+
+#[derive(Serialize)]
+struct OutputMapRecord {
+    prefix: PathBuf,
+    addr: PathBuf,
+    /// The address that this resource's output map links to, if it is a link.
+    link: Option<PathBuf>,
+    outputs: BTreeMap<String, String>,
+}
+
+pub fn outputs(kind: AutoschematicOutputsSubcommand, output: &Output) -> anyhow::Result<()> {
+    let config = load_autoschematic_config()?;
+
+    match kind {
+        AutoschematicOutputsSubcommand::Get { path, key } => get(&config, &path, key.as_deref(), output),
+        AutoschematicOutputsSubcommand::List { prefix } => list(&config, prefix.as_deref(), output),
+        AutoschematicOutputsSubcommand::Export { prefix } => export(&config, prefix.as_deref(), output),
+    }
+}
+
+/// Read the outputs of `addr` in `prefix`, following links.
+fn read_outputs(prefix: &Path, addr: &Path) -> anyhow::Result<Option<BTreeMap<String, String>>> {
+    match OutputMapFile::read_recurse(prefix, addr)? {
+        Some(OutputMapFile::OutputMap(map)) => Ok(Some(map.into_iter().collect())),
+        _ => Ok(None),
+    }
+}
+
+fn get(config: &AutoschematicConfig, path: &Path, key: Option<&str>, output: &Output) -> anyhow::Result<()> {
+    let Some((prefix, addr)) = split_prefix_addr(config, path) else {
+        bail!("{} is not in any prefix in autoschematic.ron", path.display());
+    };
+
+    let Some(outputs) = read_outputs(&prefix, &addr)? else {
+        bail!("No outputs for {}", path.display());
+    };
+
+    let outputs = match key {
+        Some(key) => {
+            let Some(value) = outputs.get(key) else {
+                bail!("No output {} for {}", key, path.display());
+            };
+
+            if output.is_human() {
+                println!("{value}");
+            }
+            BTreeMap::from([(key.to_string(), value.clone())])
+        }
+        None => {
+            if output.is_human() {
+                for (key, value) in &outputs {
+                    println!("{} = {}", key.clone().bold(), value);
+                }
+            }
+            outputs
+        }
+    };
+
+    output.emit(
+        "output_map",
+        &OutputMapRecord {
+            link: OutputMapFile::resolve(&prefix, &addr)?
+                .map(|virt_addr| virt_addr.0)
+                .filter(|virt_addr| *virt_addr != addr),
+            prefix,
+            addr,
+            outputs,
+        },
+    )
+}
+
+/// Read every output map file in `prefix_filter`, or in every prefix.
+fn read_all(config: &AutoschematicConfig, prefix_filter: Option<&str>) -> anyhow::Result<Vec<OutputMapRecord>> {
+    if let Some(prefix_filter) = prefix_filter
+        && !config.prefixes.contains_key(prefix_filter)
+    {
+        bail!("No prefix named {} in autoschematic.ron", prefix_filter);
+    }
+
+    let mut records = Vec::new();
+
+    for prefix_name in config.prefixes.keys() {
+        if prefix_filter.is_some_and(|prefix_filter| prefix_filter != prefix_name) {
+            continue;
+        }
+
+        let prefix = PathBuf::from(prefix_name);
+        for addr in OutputMapFile::list(&prefix) {
+            let (link, outputs) = match OutputMapFile::read(&prefix, &addr)? {
+                Some(OutputMapFile::PointerToVirtual(virt_addr)) => (Some(virt_addr), BTreeMap::new()),
+                Some(OutputMapFile::OutputMap(map)) => (None, map.into_iter().collect()),
+                None => continue,
+            };
+
+            records.push(OutputMapRecord {
+                prefix: prefix.clone(),
+                addr,
+                link,
+                outputs,
+            });
+        }
+    }
+
+    records.sort_by(|a, b| (&a.prefix, &a.addr).cmp(&(&b.prefix, &b.addr)));
+    Ok(records)
+}
+
+fn list(config: &AutoschematicConfig, prefix_filter: Option<&str>, output: &Output) -> anyhow::Result<()> {
+    let records = read_all(config, prefix_filter)?;
+
+    if output.is_human() && records.is_empty() {
+        println!(" ∅  No output maps found.");
+    }
+
+    for record in &records {
+        output.emit("output_map", record)?;
+
+        if !output.is_human() {
+            continue;
+        }
+
+        let path = record.prefix.join(&record.addr).display().to_string();
+        match &record.link {
+            Some(link) => println!("{} ↪ {}", path.bold(), record.prefix.join(link).display()),
+            None => println!(
+                "{} [{}]",
+                path.bold(),
+                record.outputs.keys().cloned().collect::<Vec<_>>().join(", ")
+            ),
+        }
+    }
+
+    Ok(())
+}
+
+/// The environment variable name for output `key` of `addr` in `prefix`,
+/// like MAIN_AWS_EC2_INSTANCES_WEB_INSTANCE_ID for main/aws/ec2/instances/web.ron[instance_id].
+fn env_var_name(prefix: &Path, addr: &Path, key: &str) -> String {
+    let path = prefix.join(addr.with_extension(""));
+
+    format!("{}_{}", path.display(), key)
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>()
+        .trim_matches('_')
+        .to_string()
+}
+
+/// Quote `value` for a POSIX shell, if it needs it.
+fn shell_quote(value: &str) -> String {
+    if !value.is_empty() && value.chars().all(|c| c.is_ascii_alphanumeric() || "_-./:@,+=".contains(c)) {
+        return value.to_string();
+    }
+
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+fn export(config: &AutoschematicConfig, prefix_filter: Option<&str>, output: &Output) -> anyhow::Result<()> {
+    // Links only point at outputs that are exported under their own address.
+    let records: Vec<OutputMapRecord> = read_all(config, prefix_filter)?
+        .into_iter()
+        .filter(|record| record.link.is_none())
+        .collect();
+
+    match output.format {
+        OutputFormat::Human | OutputFormat::Env => {
+            for record in &records {
+                for (key, value) in &record.outputs {
+                    println!("{}={}", env_var_name(&record.prefix, &record.addr, key), shell_quote(value));
+                }
+            }
+        }
+        _ => {
+            let outputs: BTreeMap<String, &BTreeMap<String, String>> = records
+                .iter()
+                .map(|record| (record.prefix.join(&record.addr).display().to_string(), &record.outputs))
+                .collect();
+            output.emit("outputs", &outputs)?;
+        }
+    }
+
+    Ok(())
+}
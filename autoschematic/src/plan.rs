@@ -1,42 +1,88 @@
-use autoschematic_core::{git_util::get_staged_files, report::PlanReport, util::load_autoschematic_config};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use autoschematic_core::{
+    config::AutoschematicConfig,
+    config_policy::PolicyViolation,
+    connector::Connector,
+    git_util::{ChangedFile, get_changed_files, get_staged_files, read_head_file},
+    graph::sort_changed_files_by_dependencies,
+    report::{PlanChangeCounts, PlanReport, PlanReportSet, PlanSummary, SavedPlan},
+    util::{load_autoschematic_config, load_autoschematic_policy_config},
+};
 use crossterm::style::Stylize;
 
 use crate::{
     CONNECTOR_CACHE,
+    output::{ExitStatus, Output},
     spinner::show_spinner,
-    util::{colour_op_message, try_colour_op_message_diff},
+    util::{colour_op_message, format_field_diffs, path_matches_filter, print_no_files_matched, try_colour_op_message_diff},
 };
 
 pub async fn plan(
     prefix_filter: &Option<String>,
     connector_filter: &Option<String>,
-    _subpath_filter: &Option<String>,
+    subpath_filter: &Option<String>,
+    base: &Option<String>,
+    head: &Option<String>,
+    out: &Option<PathBuf>,
+    output: &Output,
 ) -> anyhow::Result<()> {
     let config = load_autoschematic_config()?;
 
-    if let Some(_prefix_filter) = prefix_filter {}
-
-    let staged_files = get_staged_files()?;
+    let mut changed_files = match base {
+        Some(base) => get_changed_files(base, head.as_deref())?,
+        None => get_staged_changed_files().await?,
+    };
 
     let keystore = None;
 
-    if staged_files.is_empty() {
-        println!(" ∅  No files staged in git. Stage modified files with git add to plan or apply them.");
+    if changed_files.is_empty() {
+        if output.is_human() {
+            match base {
+                Some(base) => println!(
+                    " ∅  No files changed between {} and {}.",
+                    base,
+                    head.as_deref().unwrap_or("HEAD")
+                ),
+                None => println!(" ∅  No files staged in git. Stage modified files with git add to plan or apply them."),
+            }
+        }
+        return Ok(());
+    }
+
+    let changed_count = changed_files.len();
+    changed_files.retain(|f| path_matches_filter(&config, &f.path, prefix_filter, subpath_filter));
+
+    if changed_files.is_empty() {
+        if output.is_human() {
+            print_no_files_matched(changed_count, prefix_filter, subpath_filter);
+        }
         return Ok(());
     }
 
+    sort_changed_files_by_dependencies(&config, &mut changed_files)?;
+
+    let mut plan_report_set = PlanReportSet {
+        overall_success: true,
+        ..Default::default()
+    };
+
     let mut need_print_frame_start = true;
     let mut need_print_frame_end = false;
     let mut have_nonempty_plan = false;
-    for path in staged_files {
+    for changed_file in &changed_files {
         let spinner_stop = show_spinner().await;
 
-        let Some(plan_report) = autoschematic_core::workflow::plan::plan(
+        let Some(plan_report) = autoschematic_core::workflow::plan::plan_with_desired(
             &config,
             CONNECTOR_CACHE.clone(),
             keystore.clone(),
             connector_filter,
-            &path,
+            &changed_file.path,
+            changed_file.contents.clone(),
         )
         .await?
         else {
@@ -48,13 +94,25 @@ pub async fn plan(
 
         spinner_stop.send(()).unwrap();
 
+        plan_report_set.object_count += 1;
+        plan_report_set.summary.add(&plan_report);
+
+        output.emit("plan_report", &plan_report)?;
+
         if !plan_report.missing_outputs.is_empty() {
-            print_plan_addr(&plan_report);
-            println!("{}  ⊬ Missing outputs: ", frame());
-            for output in &plan_report.missing_outputs {
-                println!("{}    {}[{}]", frame(), output.addr.display(), output.key);
+            if output.is_human() {
+                print_plan_addr(&plan_report);
+                println!("{}  ⊬ Missing outputs: ", frame());
+                for read_output in &plan_report.missing_outputs {
+                    println!("{}    {}[{}]", frame(), read_output.addr.display(), read_output.key);
+                }
             }
 
+            output.set_status(ExitStatus::ChangesPending);
+            plan_report_set.deferred_count += 1;
+            plan_report_set
+                .deferred_pending_outputs
+                .extend(plan_report.missing_outputs.iter().cloned());
             continue;
         }
 
@@ -63,22 +121,44 @@ pub async fn plan(
         }
 
         have_nonempty_plan = true;
+        output.set_status(ExitStatus::ChangesPending);
+
+        if output.is_human() {
+            if need_print_frame_start {
+                need_print_frame_start = false;
+                need_print_frame_end = true;
+                print_frame_start();
+            }
 
-        if need_print_frame_start {
-            need_print_frame_start = false;
-            need_print_frame_end = true;
-            print_frame_start();
+            print_plan(&plan_report);
         }
 
-        print_plan(&plan_report);
+        plan_report_set.plan_reports.push(plan_report);
     }
 
     if need_print_frame_end {
         print_frame_end();
     }
 
-    println!(" ◇ Plan complete.");
-    if !have_nonempty_plan {
+    if output.is_human() {
+        println!(" ◇ Plan complete.");
+    }
+
+    print_plan_summary(&plan_report_set.summary, output)?;
+
+    if let Some(policy) = load_autoschematic_policy_config()? {
+        let violations = policy.evaluate(&plan_report_set.plan_reports)?;
+        print_policy_violations(&violations, output)?;
+    }
+
+    if let Some(out) = out {
+        write_saved_plan(&config, plan_report_set, &changed_files, out).await?;
+        if output.is_human() {
+            println!(" ◇ Wrote plan to {}.", out.display().to_string().bold());
+        }
+    }
+
+    if !have_nonempty_plan && output.is_human() {
         println!(
             " ≡ All plans are empty, implying that the remote configuration matches the desired configuration for all staged files."
         );
@@ -88,6 +168,86 @@ pub async fn plan(
     Ok(())
 }
 
+/// Read the files staged in git along with their contents on disk and at HEAD.
+pub async fn get_staged_changed_files() -> anyhow::Result<Vec<ChangedFile>> {
+    let mut changed_files = Vec::new();
+
+    for path in get_staged_files()? {
+        let contents = if path.is_file() {
+            Some(tokio::fs::read(&path).await?)
+        } else {
+            None
+        };
+        let previous_contents = read_head_file(&path)?;
+        changed_files.push(ChangedFile {
+            path,
+            contents,
+            previous_contents,
+        });
+    }
+
+    Ok(changed_files)
+}
+
+/// Snapshot the changed files, output maps and connector versions that `plan_report_set` was
+/// planned against, and write it to `out` for a later `autoschematic apply --plan-file`.
+async fn write_saved_plan(
+    config: &AutoschematicConfig,
+    plan_report_set: PlanReportSet,
+    changed_files: &[ChangedFile],
+    out: &Path,
+) -> anyhow::Result<()> {
+    let mut saved_plan = SavedPlan {
+        connector_versions: get_connector_versions(config, &plan_report_set).await?,
+        ..Default::default()
+    };
+
+    for changed_file in changed_files {
+        saved_plan.track_contents(&changed_file.path, changed_file.contents.as_deref())?;
+    }
+
+    for plan_report in &plan_report_set.plan_reports {
+        saved_plan.track_output_maps(plan_report)?;
+    }
+
+    saved_plan.plan_report_set = plan_report_set;
+    saved_plan.write(out)
+}
+
+/// Fetch the version of each connector used in `plan_report_set`, by prefix and then connector shortname.
+pub async fn get_connector_versions(
+    config: &AutoschematicConfig,
+    plan_report_set: &PlanReportSet,
+) -> anyhow::Result<HashMap<String, HashMap<String, String>>> {
+    let mut versions: HashMap<String, HashMap<String, String>> = HashMap::new();
+
+    for plan_report in &plan_report_set.plan_reports {
+        let Some(ref connector_def) = plan_report.connector_def else {
+            continue;
+        };
+
+        let prefix_name = plan_report.prefix.to_string_lossy().to_string();
+
+        if versions
+            .get(&prefix_name)
+            .is_some_and(|v| v.contains_key(&connector_def.shortname))
+        {
+            continue;
+        }
+
+        let (connector, _inbox) = CONNECTOR_CACHE
+            .get_or_spawn_connector(config, &prefix_name, connector_def, None, true)
+            .await?;
+
+        versions
+            .entry(prefix_name)
+            .or_default()
+            .insert(connector_def.shortname.clone(), connector.version().await?);
+    }
+
+    Ok(versions)
+}
+
 pub fn print_frame_start() {
     let term_width = crossterm::terminal::size().unwrap_or((80, 0)).0;
 
@@ -146,6 +306,81 @@ pub fn print_plan_addr(plan_report: &PlanReport) {
     }
 }
 
+/// Describe the nonzero counts in `counts`, like "2 to create, 1 to delete".
+fn describe_change_counts(counts: &PlanChangeCounts) -> String {
+    let parts: Vec<String> = [
+        (counts.created, "to create".green()),
+        (counts.modified, "to modify".yellow()),
+        (counts.deleted, "to delete".red()),
+        (counts.deferred, "deferred".dark_grey()),
+        (counts.failed, "failed".red()),
+    ]
+    .into_iter()
+    .filter(|(count, _)| *count > 0)
+    .map(|(count, label)| format!("{count} {label}"))
+    .collect();
+
+    if parts.is_empty() {
+        return String::from("no changes");
+    }
+
+    format!("{} ({} ops)", parts.join(", "), counts.ops)
+}
+
+/// Emit `summary`, and for a human, print its change counts by prefix and connector.
+pub fn print_plan_summary(summary: &PlanSummary, output: &Output) -> anyhow::Result<()> {
+    output.emit("plan_summary", summary)?;
+
+    if summary.is_empty() || !output.is_human() {
+        return Ok(());
+    }
+
+    println!(" ◇ Summary:");
+    for (prefix, connectors) in &summary.counts {
+        for (connector, counts) in connectors {
+            println!(
+                "    {}/{}: {}",
+                prefix.clone().dark_grey(),
+                connector,
+                describe_change_counts(counts)
+            );
+        }
+    }
+    println!("    {}: {}", "Total".bold(), describe_change_counts(&summary.total));
+
+    if !summary.missing_outputs.is_empty() {
+        println!("    Missing outputs:");
+        for missing_output in &summary.missing_outputs {
+            println!("        {missing_output}");
+        }
+    }
+
+    Ok(())
+}
+
+/// Emit each of `violations`, and list them for a human, noting that apply will refuse the plan.
+pub fn print_policy_violations(violations: &[PolicyViolation], output: &Output) -> anyhow::Result<()> {
+    for violation in violations {
+        output.emit("policy_violation", violation)?;
+    }
+
+    if violations.is_empty() || !output.is_human() {
+        return Ok(());
+    }
+
+    println!(
+        " {}",
+        "⊬ This plan violates autoschematic.policy.ron. Apply will refuse it unless run with --override-policy:"
+            .red()
+            .bold()
+    );
+    for violation in violations {
+        println!("    {violation}");
+    }
+
+    Ok(())
+}
+
 pub fn print_plan(plan_report: &PlanReport) {
     // let prefix = plan_report.prefix.to_string_lossy().to_string().dark_grey();
     // let virt_addr = plan_report.virt_addr.to_string_lossy().to_string().bold();
@@ -162,13 +397,27 @@ pub fn print_plan(plan_report: &PlanReport) {
 
     print_plan_addr(plan_report);
 
+    if plan_report.protected {
+        println!(
+            "{}  {}",
+            frame(),
+            "⊬ This would delete or replace a protected resource.".red().bold()
+        );
+    }
+
     for connector_op in &plan_report.connector_ops {
         let friendly_message = connector_op
             .friendly_message
             .clone()
             .unwrap_or(connector_op.op_definition.clone());
 
-        let coloured_message = try_colour_op_message_diff(&friendly_message).unwrap_or(colour_op_message(&friendly_message));
+        let mut coloured_message =
+            try_colour_op_message_diff(&friendly_message).unwrap_or(colour_op_message(&friendly_message, connector_op.op_kind));
+
+        if !connector_op.diffs.is_empty() {
+            coloured_message.push('\n');
+            coloured_message.push_str(&format_field_diffs(&connector_op.diffs));
+        }
 
         for (i, line) in coloured_message.lines().enumerate() {
             if i == 0 {
@@ -0,0 +1,177 @@
+use std::path::PathBuf;
+
+use autoschematic_core::{
+    connector::FilterResponse,
+    git_util::{get_staged_files, git_add, git_rm},
+    glob::addr_matches_filter,
+    util::{load_autoschematic_config, repo_root, split_prefix_addr},
+    workflow::pull_state::{PullStateReport, PullStateResult, pull_state_connector},
+};
+use crossterm::style::Stylize;
+use serde::Serialize;
+
+use crate::{CONNECTOR_CACHE, output::Output, spinner::show_spinner};
+
+#[derive(Serialize)]
+struct PullStateRecord {
+    prefix: PathBuf,
+    addr: PathBuf,
+    connector: String,
+    /// One of "deferred", "unchanged", "pulled" or "deleted".
+    result: &'static str,
+    /// The files written or removed.
+    files: Vec<PathBuf>,
+    missing_outputs: Vec<String>,
+}
+
+/// For each resource file staged in git, fetch its current remote state and write it over the local file and output map.
+pub async fn pull_state(
+    prefix_filter: Option<String>,
+    connector_filter: Option<String>,
+    subpath: Option<String>,
+    delete: bool,
+    git_stage: bool,
+    output: &Output,
+) -> anyhow::Result<()> {
+    let config = load_autoschematic_config()?;
+    let repo_root = repo_root()?;
+    let subpath = PathBuf::from(subpath.unwrap_or(String::from("./")));
+
+    let staged_files = get_staged_files()?;
+
+    if staged_files.is_empty() {
+        if output.is_human() {
+            println!(" ∅  No files staged in git. Stage resource files with `git add` to pull their state.");
+        }
+        return Ok(());
+    }
+
+    let mut report = PullStateReport::default();
+
+    for path in staged_files {
+        let Some((prefix, virt_addr)) = split_prefix_addr(&config, &path) else {
+            continue;
+        };
+
+        let Some(prefix_name) = prefix.to_str() else {
+            continue;
+        };
+
+        if prefix_filter
+            .as_ref()
+            .is_some_and(|prefix_filter| prefix_filter != prefix_name)
+            || !addr_matches_filter(&virt_addr, &subpath)
+        {
+            continue;
+        }
+
+        let Some(prefix_def) = config.prefixes.get(prefix_name) else {
+            continue;
+        };
+
+        report.object_count += 1;
+
+        for connector_def in &prefix_def.connectors {
+            if connector_filter
+                .as_ref()
+                .is_some_and(|connector_filter| *connector_filter != connector_def.shortname)
+            {
+                continue;
+            }
+
+            let spinner_stop = show_spinner().await;
+
+            let (connector, mut inbox) = CONNECTOR_CACHE
+                .get_or_spawn_connector(&config, prefix_name, connector_def, None, true)
+                .await?;
+
+            let _reader_handle = tokio::spawn(async move {
+                loop {
+                    match inbox.recv().await {
+                        Ok(Some(stdout)) => {
+                            eprintln!("{}", stdout.to_string_lossy());
+                        }
+                        Ok(None) => {}
+                        Err(_) => break,
+                    }
+                }
+            });
+
+            if CONNECTOR_CACHE
+                .filter_cached(&connector_def.shortname, &prefix, &virt_addr)
+                .await?
+                != FilterResponse::Resource
+            {
+                spinner_stop.send(()).unwrap();
+                continue;
+            }
+
+            let result = pull_state_connector(&connector_def.shortname, connector, &prefix, &virt_addr, delete).await;
+            spinner_stop.send(()).unwrap();
+
+            let Some(result) = result? else {
+                continue;
+            };
+
+            let (result_name, files, missing_outputs) = match &result {
+                PullStateResult::Deferred { missing_outputs } => (
+                    "deferred",
+                    Vec::new(),
+                    missing_outputs.iter().map(|read_output| read_output.into_string()).collect(),
+                ),
+                PullStateResult::Unchanged => ("unchanged", Vec::new(), Vec::new()),
+                PullStateResult::Pulled { wrote_files } => ("pulled", wrote_files.clone(), Vec::new()),
+                PullStateResult::Deleted { removed_files } => ("deleted", removed_files.clone(), Vec::new()),
+            };
+
+            if git_stage {
+                for file in &files {
+                    match &result {
+                        PullStateResult::Deleted { .. } => git_rm(&repo_root, file)?,
+                        _ => git_add(&repo_root, file)?,
+                    }
+                }
+            }
+
+            if output.is_human() {
+                let path = path.display().to_string();
+                match &result {
+                    PullStateResult::Deferred { .. } => {
+                        println!(" ⋯ {}: deferred, reads outputs that don't exist yet:", path.bold());
+                        for read_output in &missing_outputs {
+                            println!("    {read_output}");
+                        }
+                    }
+                    PullStateResult::Unchanged => println!(" ≡ {}: already matches its remote state.", path.bold()),
+                    PullStateResult::Pulled { .. } => println!(" ⇣ {}: pulled remote state.", path.bold()),
+                    PullStateResult::Deleted { .. } => {
+                        println!(" ✗ {}: doesn't exist remotely, removed.", path.bold())
+                    }
+                }
+            }
+
+            output.emit(
+                "pull_state",
+                &PullStateRecord {
+                    prefix: prefix.clone(),
+                    addr: virt_addr.clone(),
+                    connector: connector_def.shortname.clone(),
+                    result: result_name,
+                    files,
+                    missing_outputs,
+                },
+            )?;
+
+            report.add(&result);
+        }
+    }
+
+    if output.is_human() {
+        println!(
+            " Pulled the state of {} of {} resources; deleted {}, deferred {}.",
+            report.import_count, report.object_count, report.delete_count, report.deferred_count
+        );
+    }
+
+    Ok(())
+}
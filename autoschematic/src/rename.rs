@@ -0,0 +1,45 @@
+use std::path::Path;
+
+use autoschematic_core::{
+    git_util::{git_add, git_rm},
+    util::{load_autoschematic_config, repo_root},
+    workflow::rename::rename,
+};
+use crossterm::style::Stylize;
+
+use crate::{CONNECTOR_CACHE, spinner::show_spinner};
+
+pub async fn mv(old_path: &Path, new_path: &Path, git_stage: bool) -> anyhow::Result<()> {
+    let config = load_autoschematic_config()?;
+    let repo_root = repo_root()?;
+
+    let spinner_stop = show_spinner().await;
+    let report = rename(&config, &CONNECTOR_CACHE, None, old_path, new_path).await;
+    spinner_stop.send(()).unwrap();
+    let report = report?;
+
+    println!(
+        " {} → {}",
+        old_path.display().to_string().dark_grey(),
+        new_path.display().to_string().bold()
+    );
+
+    for path in &report.rewrote_references {
+        println!(
+            " ↪ Rewrote references in {}",
+            path.display().to_string().underline_dark_grey()
+        );
+    }
+
+    if git_stage {
+        for path in report.wrote_files.iter().chain(&report.rewrote_references) {
+            git_add(&repo_root, path)?;
+        }
+
+        for path in &report.removed_files {
+            git_rm(&repo_root, path)?;
+        }
+    }
+
+    Ok(())
+}
@@ -1,4 +1,7 @@
-use std::{io::Write, time::Duration};
+use std::{
+    io::{IsTerminal, Write},
+    time::Duration,
+};
 
 use colored::Colorize;
 use tokio::time::interval;
@@ -27,6 +30,14 @@ pub async fn show_spinner() -> tokio::sync::oneshot::Sender<()> {
 
     let (send, mut recv) = tokio::sync::oneshot::channel::<()>();
 
+    if !std::io::stdout().is_terminal() {
+        // Don't scribble over piped output, but keep the receiver alive so that stopping still succeeds.
+        tokio::spawn(async move {
+            let _ = recv.await;
+        });
+        return send;
+    }
+
     tokio::spawn(async move {
         let mut frame_i: usize = 0;
 
@@ -1,14 +1,53 @@
 use std::{
     collections::HashMap,
-    path::PathBuf,
+    path::{Path, PathBuf},
     time::{Duration, SystemTime},
 };
 
+use autoschematic_core::{
+    config::AutoschematicConfig,
+    connector::{FieldDiff, OpKind},
+    glob::addr_matches_filter,
+    util::split_prefix_addr,
+};
 use crossterm::style::Stylize;
 use regex::Regex;
 use tokio::fs::create_dir_all;
 
-pub fn colour_op_message(message: &str) -> String {
+/// Colour the keywords in an op's description, and mark it by its `op_kind` so that
+/// creates, updates, deletes and replacements read distinctly.
+pub fn colour_op_message(message: &str, op_kind: OpKind) -> String {
+    let message = colour_op_keywords(message);
+    match op_kind {
+        OpKind::Create => format!("{} {}", "+".green().bold(), message),
+        OpKind::Update => format!("{} {}", "~".yellow().bold(), message),
+        OpKind::Delete => format!("{} {}", "-".red().bold(), message),
+        OpKind::Replace => format!("{} {}", "±".magenta().bold(), message),
+        OpKind::Other => message,
+    }
+}
+
+/// Render an op's field-level diffs, one line per field.
+pub fn format_field_diffs(diffs: &[FieldDiff]) -> String {
+    diffs
+        .iter()
+        .map(|diff| match (&diff.before, &diff.after) {
+            (None, Some(after)) => format!("{} {} = {}", "+".green(), diff.path.clone().bold(), after.clone().green()),
+            (Some(before), None) => format!("{} {} = {}", "-".red(), diff.path.clone().bold(), before.clone().red()),
+            (Some(before), Some(after)) => format!(
+                "{} {}: {} → {}",
+                "~".yellow(),
+                diff.path.clone().bold(),
+                before.clone().red(),
+                after.clone().green()
+            ),
+            (None, None) => format!("{} {}", "~".yellow(), diff.path.clone().bold()),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn colour_op_keywords(message: &str) -> String {
     let re = Regex::new(r"(Deleted|deleted|DELETED|Delete|delete|DELETE|Destroy|destroy|DESTROY|DROPPED|DROP)").unwrap();
 
     // let message = re.replace_all(message, |captures: &regex::Captures| match &captures[0] {
@@ -134,3 +173,48 @@ pub async fn try_fetch_motd() -> Option<String> {
 
     new_motd
 }
+
+/// Whether a staged or changed file at `path` lies within `prefix_filter` and under `subpath_filter`,
+/// in the same manner as the server's ChangeSet::plan.
+pub fn path_matches_filter(
+    config: &AutoschematicConfig,
+    path: &Path,
+    prefix_filter: &Option<String>,
+    subpath_filter: &Option<String>,
+) -> bool {
+    if prefix_filter.is_none() && subpath_filter.is_none() {
+        return true;
+    }
+
+    let Some((prefix, virt_addr)) = split_prefix_addr(config, path) else {
+        return false;
+    };
+
+    if let Some(prefix_filter) = prefix_filter
+        && prefix.to_str() != Some(prefix_filter)
+    {
+        return false;
+    }
+
+    if let Some(subpath_filter) = subpath_filter {
+        return addr_matches_filter(&virt_addr, &PathBuf::from(subpath_filter));
+    }
+
+    true
+}
+
+pub fn print_no_files_matched(file_count: usize, prefix_filter: &Option<String>, subpath_filter: &Option<String>) {
+    let mut filters = Vec::new();
+    if let Some(prefix_filter) = prefix_filter {
+        filters.push(format!("prefix {}", prefix_filter.clone().bold()));
+    }
+    if let Some(subpath_filter) = subpath_filter {
+        filters.push(format!("subpath {}", subpath_filter.clone().bold()));
+    }
+
+    println!(
+        " ∅  None of the {} changed files matched {}.",
+        file_count,
+        filters.join(" and ")
+    );
+}
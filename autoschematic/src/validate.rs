@@ -2,7 +2,8 @@ use anyhow::Context;
 use autoschematic_core::{
     config::AutoschematicConfig,
     config_rbac::AutoschematicRbacConfig,
-    util::{RON, repo_root},
+    lockfile::AutoschematicLockfile,
+    util::{RON, load_autoschematic_policy_config, repo_root},
 };
 
 pub fn validate() -> anyhow::Result<()> {
@@ -19,5 +20,12 @@ pub fn validate() -> anyhow::Result<()> {
             .context("Parsing autoschematic.rbac.ron")?;
     }
 
+    let _lockfile = AutoschematicLockfile::load()?;
+
+    // Also checks that every regex in the policy's rules compiles.
+    if let Some(policy) = load_autoschematic_policy_config()? {
+        policy.evaluate(&[])?;
+    }
+
     Ok(())
 }